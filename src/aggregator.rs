@@ -0,0 +1,241 @@
+//! Export of live postings in the Indeed XML format that job aggregators
+//! consume.
+//!
+//! [`IndeedExport`] pages through the live postings newest first and renders
+//! one chunk of XML per page, so neither the route nor the CLI subcommand
+//! holds more than a page of postings in memory.
+
+use std::fmt::Write;
+
+use chrono::{ DateTime, Utc };
+use rust_decimal::Decimal;
+
+use crate::{
+    config::FeedConfig,
+    feed,
+    markup,
+    models::{ job_posting::JobTypeOption, pay::CadenceOption, work_arrangement::WorkArrangementType },
+    pagination::{ Cursor, MAX_PAGE_SIZE },
+    AppError,
+    Employer,
+    JobPosting,
+    Pay,
+    Repository,
+};
+
+pub const CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// The aggregator's job type for a job type. There is no seasonal type, so
+/// seasonal work is temporary.
+fn job_type(job_type: JobTypeOption) -> &'static str {
+    match job_type {
+        JobTypeOption::FullTime => "fulltime",
+        JobTypeOption::PartTime => "parttime",
+        JobTypeOption::Contract => "contract",
+        JobTypeOption::Temporary | JobTypeOption::Seasonal => "temporary",
+    }
+}
+
+fn cadence(cadence: CadenceOption) -> &'static str {
+    match cadence {
+        CadenceOption::Hour => "hour",
+        CadenceOption::Day => "day",
+        CadenceOption::Week => "week",
+        CadenceOption::Month => "month",
+        CadenceOption::Year => "year",
+    }
+}
+
+/// An amount with its currency, e.g. `$24.50`, `€70000` or `CHF 5000`.
+fn amount(amount: Decimal, currency: &str) -> String {
+    let amount = amount.normalize();
+    let amount = if amount.scale() == 0 {
+        amount.to_string()
+    } else {
+        format!("{:.2}", amount)
+    };
+
+    match currency {
+        "USD" | "CAD" | "AUD" | "NZD" => format!("${}", amount),
+        "EUR" => format!("€{}", amount),
+        "GBP" => format!("£{}", amount),
+        currency => format!("{} {}", currency, amount),
+    }
+}
+
+/// The `salary` string, e.g. `$24.50 - $32 per hour`.
+pub fn salary(pay: &Pay) -> String {
+    let range = match pay.max {
+        Some(max) if max != pay.min =>
+            format!("{} - {}", amount(pay.min, &pay.currency), amount(max, &pay.currency)),
+        _ => amount(pay.min, &pay.currency),
+    };
+
+    format!("{} per {}", range, cadence(pay.cadence))
+}
+
+fn element(xml: &mut String, name: &str, text: &str) {
+    let _ = write!(xml, "<{}>", name);
+    markup::cdata_into(text, xml);
+    let _ = writeln!(xml, "</{}>", name);
+}
+
+/// Streams the live postings as aggregator XML, one chunk at a time.
+pub struct IndeedExport {
+    repo: Repository,
+    config: FeedConfig,
+    now: DateTime<Utc>,
+    after: Option<Cursor>,
+    state: State,
+}
+
+enum State {
+    Header,
+    Jobs,
+    Footer,
+    Done,
+}
+
+impl IndeedExport {
+    /// Exports the postings live at `now`.
+    pub fn new(repo: Repository, config: FeedConfig, now: DateTime<Utc>) -> Self {
+        Self {
+            repo,
+            config,
+            now,
+            after: None,
+            state: State::Header,
+        }
+    }
+
+    /// The next chunk of the document, or `None` once it is complete.
+    pub async fn next_chunk(&mut self) -> Result<Option<String>, AppError> {
+        match self.state {
+            State::Header => {
+                self.state = State::Jobs;
+                Ok(Some(self.header()))
+            }
+            State::Jobs => {
+                let page = self.repo.query::<JobPosting>(
+                    &JobPosting::latest_query(None, self.now),
+                    MAX_PAGE_SIZE,
+                    self.after.take()
+                ).await?;
+
+                self.after = page.next_cursor.clone();
+                if self.after.is_none() {
+                    self.state = State::Footer;
+                }
+
+                let job_postings = page.into_entities();
+                let employers = self.employers(&job_postings).await?;
+
+                let mut xml = String::new();
+
+                for job_posting in &job_postings {
+                    let employer = job_posting.employer_id
+                        .as_ref()
+                        .and_then(|id| employers.iter().find(|employer| employer.id == *id));
+                    self.job(&mut xml, job_posting, employer);
+                }
+
+                Ok(Some(xml))
+            }
+            State::Footer => {
+                self.state = State::Done;
+                Ok(Some("</source>\n".to_string()))
+            }
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Writes the whole document to `out`, returning the number of bytes
+    /// written.
+    pub async fn write_to<W>(mut self, out: &mut W) -> Result<usize, AppError>
+        where W: tokio::io::AsyncWrite + Unpin
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut written = 0;
+
+        while let Some(chunk) = self.next_chunk().await? {
+            out
+                .write_all(chunk.as_bytes()).await
+                .map_err(|e| AppError::InternalServerError(format!("Failed to write export: {}", e)))?;
+            written += chunk.len();
+        }
+
+        out
+            .flush().await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write export: {}", e)))?;
+
+        Ok(written)
+    }
+
+    async fn employers(&self, job_postings: &[JobPosting]) -> Result<Vec<Employer>, AppError> {
+        let mut ids: Vec<String> = job_postings
+            .iter()
+            .filter_map(|job_posting| job_posting.employer_id.clone())
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(self.repo.get_many::<Employer>(&ids).await?.into_values().collect())
+    }
+
+    fn header(&self) -> String {
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<source>\n");
+        element(&mut xml, "publisher", &self.config.title);
+        element(&mut xml, "publisherurl", &self.config.site_url);
+        element(&mut xml, "lastBuildDate", &feed::http_date(&self.now));
+
+        xml
+    }
+
+    fn job(&self, xml: &mut String, job_posting: &JobPosting, employer: Option<&Employer>) {
+        let address = &job_posting.address;
+        let url = format!("{}/jobs/{}", self.config.site_url.trim_end_matches('/'), job_posting.id);
+        let published = job_posting.publish_at.unwrap_or(job_posting.created_at);
+
+        xml.push_str("<job>\n");
+        element(xml, "title", &job_posting.job_title);
+        element(xml, "date", &feed::http_date(&published));
+        element(xml, "referencenumber", &job_posting.id);
+        element(xml, "url", &url);
+
+        if let Some(employer) = employer {
+            element(xml, "company", &employer.name);
+        }
+
+        element(xml, "city", &address.city);
+        element(xml, "state", &address.state);
+        element(xml, "country", &address.country);
+        element(xml, "postalcode", &address.zip);
+        element(xml, "streetaddress", &address.street);
+        element(xml, "description", &markup::job_posting_description(job_posting));
+
+        if let Some(pay) = &job_posting.pay {
+            element(xml, "salary", &salary(pay));
+        }
+
+        element(xml, "jobtype", job_type(job_posting.job_type));
+
+        match job_posting.work_arrangement.arrangement_type {
+            WorkArrangementType::Remote => element(xml, "remotetype", "Fully remote"),
+            WorkArrangementType::Hybrid => element(xml, "remotetype", "Hybrid remote"),
+            WorkArrangementType::OnSite => {}
+        }
+
+        if let Some(expires_at) = &job_posting.expires_at {
+            element(xml, "expirationdate", &feed::http_date(expires_at));
+        }
+
+        xml.push_str("</job>\n");
+    }
+}
@@ -0,0 +1,176 @@
+//! Field guards for role and ownership checks.
+//!
+//! Guards run before a resolver. Anonymous callers are rejected with
+//! `UNAUTHORIZED`, authenticated callers lacking access with `FORBIDDEN`.
+//! Ownership guards let unknown application ids through so the resolver can
+//! report `NOT_FOUND` as usual, but only site admins get past an unknown
+//! posting id, since records can outlive the posting they belonged to.
+
+use async_graphql::{ Context, Guard, Result };
+
+use crate::{
+    context::ContextExtensions,
+    models::{
+        job_application::{ normalize_email, JobApplication },
+        job_posting::JobPosting,
+        user::Role,
+    },
+    AppError,
+    Repository,
+};
+
+use super::Principal;
+
+fn forbidden(message: &str) -> async_graphql::Error {
+    AppError::Forbidden(message.to_string()).to_graphql_error()
+}
+
+/// Whether `principal` can act for the employer owning `job_posting_id`.
+/// Unknown postings have no owner, so only site admins act for them.
+async fn acts_for_posting(
+    repo: &Repository,
+    principal: &Principal,
+    job_posting_id: &str
+) -> Result<bool, AppError> {
+    let job_posting = repo.get::<JobPosting>(job_posting_id.to_string()).await?;
+
+    Ok(match job_posting {
+        Some(posting) => principal.acts_for(posting.employer_id.as_deref()),
+        None => principal.has_role(Role::SiteAdmin),
+    })
+}
+
+/// Requires the caller to hold `role`, or a role that includes it.
+pub struct RoleGuard {
+    role: Role,
+}
+
+impl RoleGuard {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        if !principal.has_role(self.role) {
+            return Err(forbidden(&format!("Requires the {} role", self.role)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Requires the caller to be an admin of the given employer, or a site admin.
+pub struct EmployerAdminGuard {
+    employer_id: String,
+}
+
+impl EmployerAdminGuard {
+    pub fn new(employer_id: &str) -> Self {
+        Self { employer_id: employer_id.to_string() }
+    }
+}
+
+impl Guard for EmployerAdminGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        if !principal.has_role(Role::EmployerAdmin) || !principal.acts_for(Some(&self.employer_id)) {
+            return Err(forbidden("Only admins of this employer can manage its profile"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Requires the caller to be a member of the employer that owns a job posting.
+pub struct JobPostingOwnerGuard {
+    job_posting_id: String,
+}
+
+impl JobPostingOwnerGuard {
+    pub fn new(job_posting_id: &str) -> Self {
+        Self { job_posting_id: job_posting_id.to_string() }
+    }
+}
+
+impl Guard for JobPostingOwnerGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        if !acts_for_posting(repo, principal, &self.job_posting_id).await.map_err(|e| e.to_graphql_error())? {
+            return Err(forbidden("Only members of the owning employer can manage this job posting"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Requires the caller to be the account that applied, or a member of the
+/// employer that owns the posting applied to.
+pub struct JobApplicationAccessGuard {
+    job_application_id: String,
+}
+
+impl JobApplicationAccessGuard {
+    pub fn new(job_application_id: &str) -> Self {
+        Self { job_application_id: job_application_id.to_string() }
+    }
+}
+
+impl Guard for JobApplicationAccessGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let job_application = repo
+            .get::<JobApplication>(self.job_application_id.clone()).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let Some(job_application) = job_application else {
+            return Ok(());
+        };
+
+        if job_application.applicant_id.as_deref() == Some(principal.subject.as_str()) {
+            return Ok(());
+        }
+
+        if !acts_for_posting(repo, principal, &job_application.job_posting_id).await.map_err(|e| e.to_graphql_error())? {
+            return Err(
+                forbidden("Only the applicant or the owning employer can access this application")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Requires the caller's email to be this applicant email, or a site admin.
+///
+/// Emails are not verified, so resolvers must still limit non-admins to the
+/// applications recorded under their own account.
+pub struct ApplicantGuard {
+    applicant_email: String,
+}
+
+impl ApplicantGuard {
+    pub fn new(applicant_email: &str) -> Self {
+        Self { applicant_email: normalize_email(applicant_email) }
+    }
+}
+
+impl Guard for ApplicantGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        if principal.email != self.applicant_email && !principal.has_role(Role::SiteAdmin) {
+            return Err(forbidden("Applications can only be listed by the applicant"));
+        }
+
+        Ok(())
+    }
+}
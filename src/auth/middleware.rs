@@ -0,0 +1,59 @@
+use axum::{
+    extract::{ Request, State },
+    http::{ header, StatusCode },
+    middleware::Next,
+    response::{ IntoResponse, Response },
+    Json,
+};
+use serde_json::json;
+
+use crate::{ config::AuthConfig, AppError };
+
+use super::verify_token;
+
+// Shaped like a GraphQL error response so clients can handle it uniformly
+fn unauthorized(error: AppError) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(
+            json!({ "errors": [{ "message": error.to_string(), "extensions": { "code": "AUTH_ERROR" } }] })
+        ),
+    ).into_response()
+}
+
+/// Authenticates requests that carry an `Authorization: Bearer` header.
+///
+/// A valid token adds a [`Principal`](super::Principal) to the request
+/// extensions. Requests without the header pass through anonymously; resolvers
+/// decide whether that is acceptable. A header that is present but invalid is
+/// rejected with `401 Unauthorized`.
+pub async fn authenticate(
+    State(config): State<AuthConfig>,
+    mut request: Request,
+    next: Next
+) -> Response {
+    let Some(value) = request.headers().get(header::AUTHORIZATION) else {
+        return next.run(request).await;
+    };
+
+    let token = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    let Some(token) = token else {
+        return unauthorized(
+            AppError::AuthError("Authorization header must be a bearer token".to_string())
+        );
+    };
+
+    match verify_token(token, &config) {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(e) => unauthorized(e),
+    }
+}
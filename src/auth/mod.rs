@@ -0,0 +1,204 @@
+//! Bearer token authentication.
+//!
+//! Tokens are HS256 JWTs signed with `AuthConfig::jwt_secret`. The
+//! [`middleware::authenticate`] layer validates them on every request and
+//! stores the resulting [`Principal`], which the GraphQL handler forwards into
+//! the request data for resolvers to read through
+//! [`ContextExtensions::principal`](crate::context::ContextExtensions::principal).
+
+pub mod guard;
+pub mod middleware;
+pub mod password;
+
+use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+use chrono::Utc;
+use jsonwebtoken::{
+    decode,
+    encode,
+    errors::ErrorKind,
+    Algorithm,
+    DecodingKey,
+    EncodingKey,
+    Header,
+    Validation,
+};
+use rand_core::{ OsRng, TryRngCore };
+use serde::{ Deserialize, Serialize };
+use sha2::{ Digest, Sha256 };
+
+use crate::{ config::AuthConfig, models::user::{ Role, User }, AppError, Employer, Repository };
+
+/// Claims carried by access tokens: the registered ones plus the caller's
+/// authorization attributes, so guards need no lookup.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub iss: String,
+    pub aud: String,
+    pub iat: i64,
+    pub exp: i64,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub role: Role,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub employer_id: Option<String>,
+}
+
+/// The authenticated caller of a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    // The token subject, a user id
+    pub subject: String,
+    pub email: String,
+    pub role: Role,
+    pub employer_id: Option<String>,
+}
+
+impl Principal {
+    pub fn has_role(&self, role: Role) -> bool {
+        self.role.includes(role)
+    }
+
+    /// Whether the caller can act for the employer owning a resource.
+    /// Site admins can act for every employer.
+    pub fn acts_for(&self, employer_id: Option<&str>) -> bool {
+        if self.has_role(Role::SiteAdmin) {
+            return true;
+        }
+
+        self.has_role(Role::EmployerMember) &&
+            employer_id.is_some_and(|id| self.employer_id.as_deref() == Some(id))
+    }
+
+    /// The employer a new job posting by the caller belongs to. Site admins
+    /// name any existing employer with `requested`; everyone else posts for
+    /// their own employer, which `requested` may only repeat.
+    pub async fn posting_employer_id(
+        &self,
+        repo: &Repository,
+        requested: Option<&str>
+    ) -> Result<String, AppError> {
+        match (requested, &self.employer_id) {
+            (Some(requested), _) if self.has_role(Role::SiteAdmin) => {
+                Employer::ensure_exists(repo, requested).await?;
+                Ok(requested.to_string())
+            }
+            (Some(requested), Some(own)) if requested == own => Ok(own.clone()),
+            (Some(_), _) =>
+                Err(
+                    AppError::Forbidden(
+                        "Job postings can only be created for your own employer".to_string()
+                    )
+                ),
+            (None, Some(own)) => Ok(own.clone()),
+            (None, None) =>
+                Err(AppError::Forbidden("Account is not attached to an employer".to_string())),
+        }
+    }
+}
+
+impl From<Claims> for Principal {
+    fn from(claims: Claims) -> Self {
+        Self {
+            subject: claims.sub,
+            email: claims.email,
+            role: claims.role,
+            employer_id: claims.employer_id,
+        }
+    }
+}
+
+impl From<&User> for Principal {
+    fn from(user: &User) -> Self {
+        Self {
+            subject: user.id.clone(),
+            email: user.email.clone(),
+            role: user.role,
+            employer_id: user.employer_id.clone(),
+        }
+    }
+}
+
+/// Signs an access token for `principal`, valid for `AuthConfig::token_expiry`
+/// seconds.
+pub fn issue_token(principal: &Principal, config: &AuthConfig) -> Result<String, AppError> {
+    let now = Utc::now().timestamp();
+
+    let claims = Claims {
+        sub: principal.subject.clone(),
+        email: principal.email.clone(),
+        role: principal.role,
+        employer_id: principal.employer_id.clone(),
+        iss: config.issuer.clone(),
+        aud: config.audience.clone(),
+        iat: now,
+        exp: now + (config.token_expiry as i64),
+    };
+
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes())
+    ).map_err(|e| AppError::InternalServerError(format!("Failed to sign token: {}", e)))
+}
+
+/// Validates the signature, expiry, issuer and audience of `token`.
+pub fn verify_token(token: &str, config: &AuthConfig) -> Result<Principal, AppError> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_issuer(&[&config.issuer]);
+    validation.set_audience(&[&config.audience]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        &validation
+    ).map_err(|e| {
+        AppError::AuthError(
+            (
+                match e.kind() {
+                    ErrorKind::ExpiredSignature => "Token has expired",
+                    ErrorKind::InvalidIssuer => "Token issuer is not accepted",
+                    ErrorKind::InvalidAudience => "Token audience is not accepted",
+                    ErrorKind::InvalidSignature => "Token signature is invalid",
+                    ErrorKind::MissingRequiredClaim(_) => "Token is missing a required claim",
+                    _ => "Token is malformed",
+                }
+            ).to_string()
+        )
+    })?;
+
+    Ok(Principal::from(data.claims))
+}
+
+/// Generates an opaque refresh token with 256 bits of entropy.
+pub fn generate_refresh_token() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    OsRng.try_fill_bytes(&mut bytes).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to generate refresh token: {}", e))
+    })?;
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// The storage key for a refresh token. Tokens are high-entropy, so a plain
+/// SHA-256 is sufficient and keeps lookups deterministic.
+pub fn hash_refresh_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Makes the account registered under `email` a site admin, for the
+/// `create-admin` command. Takes effect when the user next logs in or
+/// refreshes their token.
+pub async fn grant_site_admin(repo: &Repository, email: &str) -> Result<User, AppError> {
+    let mut user = User::find_by_email(repo, email).await?.ok_or_else(|| {
+        AppError::NotFound(format!("No account is registered with {}", email.trim()))
+    })?;
+
+    user.role = Role::SiteAdmin;
+    user.employer_id = None;
+    user.updated_at = Utc::now();
+
+    repo.update(user).await
+}
@@ -0,0 +1,59 @@
+use argon2::{
+    password_hash::{ PasswordHash, PasswordHasher, PasswordVerifier, SaltString },
+    Argon2,
+};
+use rand_core::{ OsRng, TryRngCore };
+
+use crate::AppError;
+
+const MIN_PASSWORD_LENGTH: usize = 12;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+pub fn validate_password(password: &str) -> Result<(), AppError> {
+    let length = password.chars().count();
+
+    if length < MIN_PASSWORD_LENGTH {
+        return Err(
+            AppError::ValidationError(
+                format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH)
+            )
+        );
+    }
+    if length > MAX_PASSWORD_LENGTH {
+        return Err(
+            AppError::ValidationError(
+                format!("Password cannot exceed {} characters", MAX_PASSWORD_LENGTH)
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Hashes `password` with argon2id and a random salt, returning a PHC string.
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    let mut salt = [0u8; 16];
+    OsRng.try_fill_bytes(&mut salt).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to generate salt: {}", e))
+    })?;
+
+    let salt = SaltString::encode_b64(&salt).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to encode salt: {}", e))
+    })?;
+
+    // Argon2::default() is argon2id v19 with the recommended parameters
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::InternalServerError(format!("Failed to hash password: {}", e)))
+}
+
+/// Checks `password` against a stored PHC string. The parameters embedded in
+/// the string are used, so hashes made with older settings keep verifying.
+pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, AppError> {
+    let parsed = PasswordHash::new(password_hash).map_err(|e| {
+        AppError::InternalServerError(format!("Stored password hash is invalid: {}", e))
+    })?;
+
+    Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
@@ -9,12 +9,44 @@ pub struct Config {
     pub environment: String,
     pub allow_origins: String,
     pub log_level: String,
+    #[serde(default)]
+    pub run_mode: RunMode,
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    #[serde(default)]
+    pub lifecycle: LifecycleConfig,
+    #[serde(default)]
+    pub geocoding: GeocodingConfig,
+    #[serde(default)]
+    pub feeds: FeedConfig,
+}
+
+/// How the service receives requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunMode {
+    // Local HTTP server listening on PORT
+    #[default]
+    Http,
+    // Invocations from API Gateway or a Function URL via the Lambda runtime API
+    Lambda,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfig {
     pub region: String,
     pub endpoint: Option<String>, // For local DynamoDB
+    #[serde(default)]
+    pub backend: StorageBackendKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackendKind {
+    #[default]
+    DynamoDb,
+    // Process-local storage; data is lost on restart
+    Memory,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -29,6 +61,140 @@ pub struct GraphQLConfig {
 pub struct AuthConfig {
     pub jwt_secret: String,
     pub token_expiry: u64, // seconds
+    // Lifetime of refresh tokens, in seconds
+    #[serde(default = "default_refresh_token_expiry")]
+    pub refresh_token_expiry: u64,
+    // Expected `iss` claim of access tokens
+    #[serde(default = "default_issuer")]
+    pub issuer: String,
+    // Expected `aud` claim of access tokens
+    #[serde(default = "default_audience")]
+    pub audience: String,
+}
+
+fn default_refresh_token_expiry() -> u64 {
+    30 * 24 * 3600 // 30 days
+}
+
+fn default_issuer() -> String {
+    "job_board_lambda".to_string()
+}
+
+fn default_audience() -> String {
+    "job_board_api".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VerificationConfig {
+    // DNS-over-HTTPS JSON endpoint used to look up TXT records
+    #[serde(default = "default_doh_endpoint")]
+    pub doh_endpoint: String,
+    // Timeout for each DNS or well-known file lookup, in seconds
+    #[serde(default = "default_lookup_timeout")]
+    pub lookup_timeout: u64,
+    // Lifetime of a domain challenge token, in seconds
+    #[serde(default = "default_challenge_expiry")]
+    pub challenge_expiry: u64,
+    // Hold postings from employers without a verified domain until a site
+    // admin approves them
+    #[serde(default)]
+    pub hold_unverified_postings: bool,
+}
+
+fn default_doh_endpoint() -> String {
+    "https://cloudflare-dns.com/dns-query".to_string()
+}
+
+fn default_lookup_timeout() -> u64 {
+    10
+}
+
+fn default_challenge_expiry() -> u64 {
+    7 * 24 * 3600 // 7 days
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            doh_endpoint: default_doh_endpoint(),
+            lookup_timeout: default_lookup_timeout(),
+            challenge_expiry: default_challenge_expiry(),
+            hold_unverified_postings: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LifecycleConfig {
+    // How long a posting stays listed when it is published without an
+    // expiry, in seconds
+    #[serde(default = "default_posting_lifetime")]
+    pub default_posting_lifetime: u64,
+    // How long closed and expired postings are kept before the sweep archives
+    // them, in seconds
+    #[serde(default = "default_archive_after")]
+    pub archive_after: u64,
+    // How long archived postings are kept before Time to Live deletes them,
+    // in seconds
+    #[serde(default = "default_purge_after")]
+    pub purge_after: u64,
+}
+
+fn default_posting_lifetime() -> u64 {
+    30 * 24 * 3600 // 30 days
+}
+
+fn default_archive_after() -> u64 {
+    90 * 24 * 3600 // 90 days
+}
+
+fn default_purge_after() -> u64 {
+    365 * 24 * 3600 // 1 year
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            default_posting_lifetime: default_posting_lifetime(),
+            archive_after: default_archive_after(),
+            purge_after: default_purge_after(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GeocodingConfig {
+    // CSV table of postal code and city coordinates used to locate postings;
+    // without one, postings are only located when created with coordinates
+    #[serde(default)]
+    pub table_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    // Base URL of the public site; feed entries link to `{site_url}/jobs/{id}`
+    #[serde(default = "default_site_url")]
+    pub site_url: String,
+    // Title of the RSS and Atom feeds
+    #[serde(default = "default_feed_title")]
+    pub title: String,
+}
+
+fn default_site_url() -> String {
+    "http://localhost:3000".to_string()
+}
+
+fn default_feed_title() -> String {
+    "Job Board".to_string()
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            site_url: default_site_url(),
+            title: default_feed_title(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -52,6 +218,7 @@ impl Default for Config {
             database: DatabaseConfig {
                 region: "us-east-2".to_string(),
                 endpoint: Some("http://localhost:8000".to_string()),
+                backend: StorageBackendKind::DynamoDb,
             },
             graphql: GraphQLConfig {
                 playground: true,
@@ -62,6 +229,9 @@ impl Default for Config {
             auth: AuthConfig {
                 jwt_secret: "default-secret-change-in-production".to_string(),
                 token_expiry: 3600, // 1 hour
+                refresh_token_expiry: default_refresh_token_expiry(),
+                issuer: default_issuer(),
+                audience: default_audience(),
             },
             aws: AwsConfig {
                 region: "us-east-2".to_string(),
@@ -71,6 +241,11 @@ impl Default for Config {
             environment: "dev".to_string(),
             allow_origins: "".to_string(),
             log_level: "error".to_string(),
+            run_mode: RunMode::Http,
+            verification: VerificationConfig::default(),
+            lifecycle: LifecycleConfig::default(),
+            geocoding: GeocodingConfig::default(),
+            feeds: FeedConfig::default(),
         }
     }
 }
@@ -1,19 +1,26 @@
-use async_graphql::Context;
-use aws_sdk_dynamodb::Client;
+use async_graphql::{ dataloader::DataLoader, Context };
 use std::sync::Arc;
 
-use crate::{config::Config, AppError};
+use crate::{
+    auth::Principal,
+    config::Config,
+    geo::{ Geocoder, SharedGeocoder },
+    schema::loader::EmployerLoader,
+    verification::DomainVerifier,
+    AppError,
+    Repository,
+};
 
 #[derive(Clone)]
 pub struct AppContext {
-    pub db_client: Arc<Client>,
+    pub repository: Repository,
     pub config: Arc<Config>,
 }
 
 impl AppContext {
-    pub fn new(db_client: Client, config: Config) -> Self {
+    pub fn new(repository: Repository, config: Config) -> Self {
         Self {
-            db_client: Arc::new(db_client),
+            repository,
             config: Arc::new(config),
         }
     }
@@ -21,14 +28,19 @@ impl AppContext {
 
 // Extension trait for GraphQL Context
 pub trait ContextExtensions {
-    fn db_client(&self) -> Result<&Client, AppError>;
+    fn repository(&self) -> Result<&Repository, AppError>;
     fn config(&self) -> Result<&Config, AppError>;
+    /// The authenticated caller, or `Unauthorized` for anonymous requests.
+    fn principal(&self) -> Result<&Principal, AppError>;
+    fn employer_loader(&self) -> Result<&DataLoader<EmployerLoader>, AppError>;
+    fn domain_verifier(&self) -> Result<&DomainVerifier, AppError>;
+    fn geocoder(&self) -> Result<&dyn Geocoder, AppError>;
 }
 
 impl<'a> ContextExtensions for Context<'a> {
-    fn db_client(&self) -> Result<&Client, AppError> {
-        self.data::<Client>().map_err(|_| {
-            AppError::InternalServerError("Database client not available in context".to_string())
+    fn repository(&self) -> Result<&Repository, AppError> {
+        self.data::<Repository>().map_err(|_| {
+            AppError::InternalServerError("Repository not available in context".to_string())
         })
     }
 
@@ -37,4 +49,30 @@ impl<'a> ContextExtensions for Context<'a> {
             AppError::InternalServerError("Config not available in context".to_string())
         })
     }
-}
\ No newline at end of file
+
+    fn principal(&self) -> Result<&Principal, AppError> {
+        self.data_opt::<Principal>().ok_or_else(|| {
+            AppError::Unauthorized("Authentication is required".to_string())
+        })
+    }
+
+    fn employer_loader(&self) -> Result<&DataLoader<EmployerLoader>, AppError> {
+        self.data::<DataLoader<EmployerLoader>>().map_err(|_| {
+            AppError::InternalServerError("Employer loader not available in context".to_string())
+        })
+    }
+
+    fn domain_verifier(&self) -> Result<&DomainVerifier, AppError> {
+        self.data::<DomainVerifier>().map_err(|_| {
+            AppError::InternalServerError("Domain verifier not available in context".to_string())
+        })
+    }
+
+    fn geocoder(&self) -> Result<&dyn Geocoder, AppError> {
+        self.data::<SharedGeocoder>()
+            .map(|geocoder| geocoder.as_ref())
+            .map_err(|_| {
+                AppError::InternalServerError("Geocoder not available in context".to_string())
+            })
+    }
+}
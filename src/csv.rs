@@ -0,0 +1,138 @@
+//! Reading and writing CSV as described by RFC 4180.
+//!
+//! Fields may be quoted, with `""` for a quote inside a quoted field, and
+//! quoted fields may span lines. Records end with LF or CRLF when read, and
+//! with CRLF when written. A leading byte order mark and blank lines are
+//! ignored.
+
+use crate::AppError;
+
+/// Splits `data` into records of fields.
+pub fn parse(data: &str) -> Result<Vec<Vec<String>>, AppError> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let invalid = |line: usize, message: &str| {
+        AppError::ValidationError(format!("Invalid CSV on line {}: {}", line, message))
+    };
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // Whether the current field was quoted, and whether its quotes are open
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => {
+                    in_quotes = false;
+                }
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                in_quotes = true;
+            }
+            '"' => {
+                return Err(invalid(line, "quote inside an unquoted field"));
+            }
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                end_record(&mut records, &mut record, &mut field, quoted);
+                quoted = false;
+                line += 1;
+            }
+            _ if quoted => {
+                return Err(invalid(line, "text after a closing quote"));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(invalid(line, "unterminated quoted field"));
+    }
+
+    end_record(&mut records, &mut record, &mut field, quoted);
+
+    Ok(records)
+}
+
+fn end_record(
+    records: &mut Vec<Vec<String>>,
+    record: &mut Vec<String>,
+    field: &mut String,
+    quoted: bool
+) {
+    let blank = record.is_empty() && field.is_empty() && !quoted;
+
+    record.push(std::mem::take(field));
+    let record = std::mem::take(record);
+
+    if !blank {
+        records.push(record);
+    }
+}
+
+/// Leading characters that make spreadsheet applications evaluate a cell as a
+/// formula.
+const FORMULA_TRIGGERS: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Prefixes `field` with `'` when a spreadsheet would otherwise evaluate it as
+/// a formula, so exported text cannot run as one.
+pub fn escape_formula(field: String) -> String {
+    if field.starts_with(FORMULA_TRIGGERS) {
+        format!("'{}", field)
+    } else {
+        field
+    }
+}
+
+/// Undoes [`escape_formula`], for reading back exported files.
+pub fn unescape_formula(field: String) -> String {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(FORMULA_TRIGGERS) => rest.to_string(),
+        _ => field,
+    }
+}
+
+/// Appends `fields` as one record, quoting the fields that need it.
+pub fn write_record<S: AsRef<str>>(fields: &[S], out: &mut String) {
+    for (i, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+
+        if i > 0 {
+            out.push(',');
+        }
+
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+
+    out.push_str("\r\n");
+}
@@ -0,0 +1,174 @@
+//! Account and session table definitions.
+//!
+//! This module contains table definitions for user accounts and the refresh
+//! tokens issued to their sessions.
+
+use aws_sdk_dynamodb::{
+    Client,
+    operation::list_tables::ListTablesOutput,
+    types::{
+        AttributeDefinition,
+        BillingMode,
+        KeySchemaElement,
+        KeyType,
+        GlobalSecondaryIndex,
+        Projection,
+        ProjectionType,
+        ScalarAttributeType,
+    },
+};
+
+use crate::{db::common::build, error::AppError};
+
+/// GSI on `email` in the Users table.
+pub const EMAIL_INDEX: &str = "EmailIndex";
+/// GSI on `family_id` in the RefreshTokens table.
+pub const TOKEN_FAMILY_INDEX: &str = "TokenFamilyIndex";
+
+/// Creates the Users table.
+///
+/// This table stores user accounts with the following structure:
+/// - Primary Key: id (String)
+/// - Global Secondary Indexes:
+///   - EmailIndex: email (for login lookups)
+pub async fn create_users_table(tables: &ListTablesOutput, client: &Client) -> Result<(), AppError> {
+    let table_name = "Users";
+
+    if tables.table_names().contains(&table_name.to_string()) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ad_email = build(
+        AttributeDefinition::builder()
+            .attribute_name("email")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build email attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI 1: Email Index
+    let gsi1_pk = build(
+        KeySchemaElement::builder().attribute_name("email").key_type(KeyType::Hash).build(),
+        "Failed to build Email GSI PK"
+    )?;
+
+    let gsi1 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name(EMAIL_INDEX)
+            .key_schema(gsi1_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build EmailIndex GSI"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .attribute_definitions(ad_email)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi1)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    println!("Users table created: {:?}", response);
+    Ok(())
+}
+
+/// Creates the RefreshTokens table.
+///
+/// This table tracks issued refresh tokens so they can be rotated and revoked.
+/// Tokens are stored under a hash of their value, never in the clear.
+/// - Primary Key: id (String, SHA-256 of the token)
+/// - Global Secondary Indexes:
+///   - TokenFamilyIndex: family_id (for revoking every token of a session)
+pub async fn create_refresh_tokens_table(
+    tables: &ListTablesOutput,
+    client: &Client
+) -> Result<(), AppError> {
+    let table_name = "RefreshTokens";
+
+    if tables.table_names().contains(&table_name.to_string()) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ad_family_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("family_id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build family_id attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI 1: Token Family Index
+    let gsi1_pk = build(
+        KeySchemaElement::builder().attribute_name("family_id").key_type(KeyType::Hash).build(),
+        "Failed to build TokenFamily GSI PK"
+    )?;
+
+    let gsi1 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name(TOKEN_FAMILY_INDEX)
+            .key_schema(gsi1_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build TokenFamilyIndex GSI"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .attribute_definitions(ad_family_id)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi1)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    println!("RefreshTokens table created: {:?}", response);
+    Ok(())
+}
@@ -13,7 +13,7 @@ pub async fn setup_local_client() -> Result<Client, AppError> {
 
     let config = aws_config
         ::from_env()
-        .behavior_version(BehaviorVersion::v2025_01_17())
+        .behavior_version(BehaviorVersion::v2025_08_07())
         .region(region_provider)
         .load().await;
 
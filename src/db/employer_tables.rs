@@ -0,0 +1,99 @@
+//! Employer table definitions.
+//!
+//! This module contains the table definition for employer profiles, which job
+//! postings reference by `employer_id`.
+
+use aws_sdk_dynamodb::{
+    Client,
+    operation::list_tables::ListTablesOutput,
+    types::{
+        AttributeDefinition,
+        BillingMode,
+        KeySchemaElement,
+        KeyType,
+        GlobalSecondaryIndex,
+        Projection,
+        ProjectionType,
+        ScalarAttributeType,
+    },
+};
+
+use crate::{db::common::build, error::AppError};
+
+/// GSI on `employer_name` in the Employers table.
+pub const EMPLOYER_NAME_INDEX: &str = "EmployerNameIndex";
+
+/// Creates the Employers table.
+///
+/// This table stores employer profiles with the following structure:
+/// - Primary Key: id (String)
+/// - Global Secondary Indexes:
+///   - EmployerNameIndex: employer_name (for lookups by name)
+pub async fn create_employers_table(
+    tables: &ListTablesOutput,
+    client: &Client
+) -> Result<(), AppError> {
+    let table_name = "Employers";
+
+    if tables.table_names().contains(&table_name.to_string()) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ad_employer_name = build(
+        AttributeDefinition::builder()
+            .attribute_name("employer_name")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build employer_name attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI 1: Employer Name Index
+    let gsi1_pk = build(
+        KeySchemaElement::builder().attribute_name("employer_name").key_type(KeyType::Hash).build(),
+        "Failed to build EmployerName GSI PK"
+    )?;
+
+    let gsi1 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name(EMPLOYER_NAME_INDEX)
+            .key_schema(gsi1_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build EmployerNameIndex GSI"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .attribute_definitions(ad_employer_name)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi1)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    println!("Employers table created: {:?}", response);
+    Ok(())
+}
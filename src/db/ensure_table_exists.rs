@@ -6,7 +6,7 @@
 use aws_sdk_dynamodb::Client;
 use crate::error::AppError;
 
-use super::job_posting_tables;
+use super::{ auth_tables, employer_tables, job_posting_tables, search_tables };
 
 /// Main function to ensure all required DynamoDB tables exist.
 ///
@@ -38,6 +38,16 @@ pub async fn ensure_all_tables_exist(client: &Client) -> Result<(), AppError> {
     job_posting_tables::create_job_postings_table(&tables, client).await?;
     job_posting_tables::create_job_categories_table(&tables, client).await?;
     job_posting_tables::create_job_applications_table(&tables, client).await?;
+    search_tables::create_job_posting_terms_table(&tables, client).await?;
+
+    // Create employer tables
+    println!("Creating employer tables...");
+    employer_tables::create_employers_table(&tables, client).await?;
+
+    // Create account tables
+    println!("Creating account tables...");
+    auth_tables::create_users_table(&tables, client).await?;
+    auth_tables::create_refresh_tokens_table(&tables, client).await?;
 
     println!("All tables created successfully!");
     Ok(())
@@ -20,17 +20,71 @@ use aws_sdk_dynamodb::{
 
 use crate::{db::common::build, error::AppError};
 
-
+/// GSI on `employer_id` in the JobPostings table.
+pub const EMPLOYER_INDEX: &str = "EmployerIdIndex";
+/// The original GSI hashed on the free-text `employer_name`; replaced by
+/// [`EMPLOYER_INDEX`].
+pub const LEGACY_EMPLOYER_NAME_INDEX: &str = "EmployerIndex";
+/// GSI on `job_type` in the JobPostings table.
+pub const JOB_TYPE_INDEX: &str = "JobTypeIndex";
+/// GSI on the denormalized `city` attribute in the JobPostings table.
+pub const LOCATION_INDEX: &str = "LocationIndex";
+/// GSI for listing postings in creation order.
+///
+/// Every posting shares the constant `listing_partition` value, and `created_at`
+/// (RFC 3339, UTC) is the sort key. A job board's write rate sits far below a
+/// single partition's throughput, so the partition is not sharded.
+pub const TIMELINE_INDEX: &str = "TimelineIndex";
+/// Partition key attribute of [`TIMELINE_INDEX`].
+pub const LISTING_PARTITION_ATTRIBUTE: &str = "listing_partition";
+/// The value every job posting stores in [`LISTING_PARTITION_ATTRIBUTE`].
+pub const LISTING_PARTITION: &str = "JOB_POSTING";
+/// The original GSI hashed on `created_at`; replaced by [`TIMELINE_INDEX`].
+pub const LEGACY_CREATED_AT_INDEX: &str = "CreatedAtIndex";
+/// GSI for finding postings near a point.
+///
+/// The partition key is the first [`GEOHASH_PARTITION_PRECISION`] characters
+/// of the posting's geohash, a cell roughly 1,250 by 625 km, and the sort key
+/// is the full [`GEOHASH_ATTRIBUTE`], so one `Query` with `begins_with` reads
+/// a smaller cell. Postings without coordinates are left out of the index.
+pub const GEOHASH_INDEX: &str = "GeohashIndex";
+/// Partition key attribute of [`GEOHASH_INDEX`].
+pub const GEOHASH_PARTITION_ATTRIBUTE: &str = "geohash_partition";
+/// Sort key attribute of [`GEOHASH_INDEX`], the geohash of the posting's
+/// coordinates.
+pub const GEOHASH_ATTRIBUTE: &str = "geohash";
+/// Length of the geohash prefix stored in [`GEOHASH_PARTITION_ATTRIBUTE`].
+pub const GEOHASH_PARTITION_PRECISION: usize = 2;
+/// Length of the geohash stored in [`GEOHASH_ATTRIBUTE`]; cells are a few
+/// metres across.
+pub const GEOHASH_PRECISION: usize = 9;
+/// Time to Live attribute of the JobPostings table, in epoch seconds. Only
+/// archived postings carry it, and DynamoDB deletes them once it passes.
+pub const PURGE_AT_ATTRIBUTE: &str = "purge_at";
+
+/// GSI on `category_name` in the JobCategories table.
+pub const CATEGORY_NAME_INDEX: &str = "CategoryNameIndex";
+
+/// GSI on `job_posting_id` in the JobApplications table.
+pub const JOB_POSTING_INDEX: &str = "JobPostingIndex";
+/// GSI on `applicant_email` in the JobApplications table.
+pub const APPLICANT_INDEX: &str = "ApplicantIndex";
+/// GSI on `application_status` in the JobApplications table.
+pub const STATUS_INDEX: &str = "StatusIndex";
 
 /// Creates the JobPostings table.
 ///
 /// This table stores all job posting information with the following structure:
 /// - Primary Key: id (String)
 /// - Global Secondary Indexes:
-///   - EmployerIndex: employer_name
+///   - EmployerIdIndex: employer_id
 ///   - JobTypeIndex: job_type
 ///   - LocationIndex: address.city (for location-based queries)
-///   - CreatedAtIndex: created_at (for time-based queries)
+///   - TimelineIndex: listing_partition + created_at (for newest-first listings)
+///   - GeohashIndex: geohash_partition + geohash (for radius searches)
+///
+/// Time to Live on `purge_at` can only be enabled once the table is active, so
+/// it is turned on by `migrations::enable_job_postings_ttl`.
 pub async fn create_job_postings_table(
     tables: &ListTablesOutput,
     client: &Client
@@ -51,13 +105,7 @@ pub async fn create_job_postings_table(
         "Failed to build id attribute definition"
     )?;
 
-    let ad_employer_name = build(
-        AttributeDefinition::builder()
-            .attribute_name("employer_name")
-            .attribute_type(ScalarAttributeType::S)
-            .build(),
-        "Failed to build employer_name attribute definition"
-    )?;
+    let ad_employer_id = employer_id_attribute_definition()?;
 
     let ad_job_type = build(
         AttributeDefinition::builder()
@@ -75,6 +123,8 @@ pub async fn create_job_postings_table(
         "Failed to build city attribute definition"
     )?;
 
+    let ad_listing_partition = listing_partition_attribute_definition()?;
+
     let ad_created_at = build(
         AttributeDefinition::builder()
             .attribute_name("created_at")
@@ -83,6 +133,8 @@ pub async fn create_job_postings_table(
         "Failed to build created_at attribute definition"
     )?;
 
+    let (ad_geohash_partition, ad_geohash) = geohash_attribute_definitions()?;
+
     // Define primary key schema
     let ks_id = build(
         KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
@@ -90,19 +142,7 @@ pub async fn create_job_postings_table(
     )?;
 
     // Define GSI 1: Employer Index - for querying jobs by employer
-    let gsi1_pk = build(
-        KeySchemaElement::builder().attribute_name("employer_name").key_type(KeyType::Hash).build(),
-        "Failed to build Employer GSI PK"
-    )?;
-
-    let gsi1 = build(
-        GlobalSecondaryIndex::builder()
-            .index_name("EmployerIndex")
-            .key_schema(gsi1_pk)
-            .projection(Projection::builder().projection_type(ProjectionType::All).build())
-            .build(),
-        "Failed to build EmployerIndex GSI"
-    )?;
+    let gsi1 = employer_index()?;
 
     // Define GSI 2: Job Type Index - for querying jobs by type (full-time, part-time, etc.)
     let gsi2_pk = build(
@@ -112,7 +152,7 @@ pub async fn create_job_postings_table(
 
     let gsi2 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("JobTypeIndex")
+            .index_name(JOB_TYPE_INDEX)
             .key_schema(gsi2_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
@@ -127,27 +167,18 @@ pub async fn create_job_postings_table(
 
     let gsi3 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("LocationIndex")
+            .index_name(LOCATION_INDEX)
             .key_schema(gsi3_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
         "Failed to build LocationIndex GSI"
     )?;
 
-    // Define GSI 4: Created At Index - for querying jobs by creation time (newest first, etc.)
-    let gsi4_pk = build(
-        KeySchemaElement::builder().attribute_name("created_at").key_type(KeyType::Hash).build(),
-        "Failed to build CreatedAt GSI PK"
-    )?;
+    // Define GSI 4: Timeline Index - for listing jobs by creation time (newest first, etc.)
+    let gsi4 = timeline_index()?;
 
-    let gsi4 = build(
-        GlobalSecondaryIndex::builder()
-            .index_name("CreatedAtIndex")
-            .key_schema(gsi4_pk)
-            .projection(Projection::builder().projection_type(ProjectionType::All).build())
-            .build(),
-        "Failed to build CreatedAtIndex GSI"
-    )?;
+    // Define GSI 5: Geohash Index - for finding jobs within a radius
+    let gsi5 = geohash_index()?;
 
     // Create the table
     let response = client
@@ -155,15 +186,19 @@ pub async fn create_job_postings_table(
         .table_name("JobPostings")
         .billing_mode(BillingMode::PayPerRequest)
         .attribute_definitions(ad_id)
-        .attribute_definitions(ad_employer_name)
+        .attribute_definitions(ad_employer_id)
         .attribute_definitions(ad_job_type)
         .attribute_definitions(ad_city)
+        .attribute_definitions(ad_listing_partition)
         .attribute_definitions(ad_created_at)
+        .attribute_definitions(ad_geohash_partition)
+        .attribute_definitions(ad_geohash)
         .key_schema(ks_id)
         .global_secondary_indexes(gsi1)
         .global_secondary_indexes(gsi2)
         .global_secondary_indexes(gsi3)
         .global_secondary_indexes(gsi4)
+        .global_secondary_indexes(gsi5)
         .send().await
         .map_err(|e|
             AppError::DatabaseError(
@@ -175,6 +210,125 @@ pub async fn create_job_postings_table(
     Ok(())
 }
 
+pub(crate) fn listing_partition_attribute_definition() -> Result<AttributeDefinition, AppError> {
+    build(
+        AttributeDefinition::builder()
+            .attribute_name(LISTING_PARTITION_ATTRIBUTE)
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build listing_partition attribute definition"
+    )
+}
+
+pub(crate) fn employer_id_attribute_definition() -> Result<AttributeDefinition, AppError> {
+    build(
+        AttributeDefinition::builder()
+            .attribute_name("employer_id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build employer_id attribute definition"
+    )
+}
+
+/// Builds the [`EMPLOYER_INDEX`] definition, shared by table creation and the
+/// migration that adds it to existing tables.
+pub(crate) fn employer_index() -> Result<GlobalSecondaryIndex, AppError> {
+    let pk = build(
+        KeySchemaElement::builder().attribute_name("employer_id").key_type(KeyType::Hash).build(),
+        "Failed to build Employer GSI PK"
+    )?;
+
+    build(
+        GlobalSecondaryIndex::builder()
+            .index_name(EMPLOYER_INDEX)
+            .key_schema(pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build EmployerIdIndex GSI"
+    )
+}
+
+/// Builds the [`TIMELINE_INDEX`] definition, shared by table creation and the
+/// migration that adds it to existing tables.
+pub(crate) fn timeline_index() -> Result<GlobalSecondaryIndex, AppError> {
+    let pk = build(
+        KeySchemaElement::builder()
+            .attribute_name(LISTING_PARTITION_ATTRIBUTE)
+            .key_type(KeyType::Hash)
+            .build(),
+        "Failed to build Timeline GSI PK"
+    )?;
+
+    let sk = build(
+        KeySchemaElement::builder().attribute_name("created_at").key_type(KeyType::Range).build(),
+        "Failed to build Timeline GSI SK"
+    )?;
+
+    build(
+        GlobalSecondaryIndex::builder()
+            .index_name(TIMELINE_INDEX)
+            .key_schema(pk)
+            .key_schema(sk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build TimelineIndex GSI"
+    )
+}
+
+/// Attribute definitions of the [`GEOHASH_INDEX`] partition and sort keys.
+pub(crate) fn geohash_attribute_definitions() -> Result<
+    (AttributeDefinition, AttributeDefinition),
+    AppError
+> {
+    let partition = build(
+        AttributeDefinition::builder()
+            .attribute_name(GEOHASH_PARTITION_ATTRIBUTE)
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build geohash_partition attribute definition"
+    )?;
+
+    let geohash = build(
+        AttributeDefinition::builder()
+            .attribute_name(GEOHASH_ATTRIBUTE)
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build geohash attribute definition"
+    )?;
+
+    Ok((partition, geohash))
+}
+
+/// Builds the [`GEOHASH_INDEX`] definition, shared by table creation and the
+/// migration that adds it to existing tables.
+pub(crate) fn geohash_index() -> Result<GlobalSecondaryIndex, AppError> {
+    let pk = build(
+        KeySchemaElement::builder()
+            .attribute_name(GEOHASH_PARTITION_ATTRIBUTE)
+            .key_type(KeyType::Hash)
+            .build(),
+        "Failed to build Geohash GSI PK"
+    )?;
+
+    let sk = build(
+        KeySchemaElement::builder()
+            .attribute_name(GEOHASH_ATTRIBUTE)
+            .key_type(KeyType::Range)
+            .build(),
+        "Failed to build Geohash GSI SK"
+    )?;
+
+    build(
+        GlobalSecondaryIndex::builder()
+            .index_name(GEOHASH_INDEX)
+            .key_schema(pk)
+            .key_schema(sk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build GeohashIndex GSI"
+    )
+}
+
 /// Creates the JobCategories table.
 ///
 /// This table stores job categories and tags for better organization and filtering.
@@ -223,7 +377,7 @@ pub async fn create_job_categories_table(
 
     let gsi1 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("CategoryNameIndex")
+            .index_name(CATEGORY_NAME_INDEX)
             .key_schema(gsi1_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
@@ -319,7 +473,7 @@ pub async fn create_job_applications_table(
 
     let gsi1 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("JobPostingIndex")
+            .index_name(JOB_POSTING_INDEX)
             .key_schema(gsi1_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
@@ -337,7 +491,7 @@ pub async fn create_job_applications_table(
 
     let gsi2 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("ApplicantIndex")
+            .index_name(APPLICANT_INDEX)
             .key_schema(gsi2_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
@@ -355,7 +509,7 @@ pub async fn create_job_applications_table(
 
     let gsi3 = build(
         GlobalSecondaryIndex::builder()
-            .index_name("StatusIndex")
+            .index_name(STATUS_INDEX)
             .key_schema(gsi3_pk)
             .projection(Projection::builder().projection_type(ProjectionType::All).build())
             .build(),
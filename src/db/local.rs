@@ -1,7 +1,7 @@
 use aws_config::{ meta::region::RegionProviderChain, BehaviorVersion };
 use aws_sdk_dynamodb::Client;
 use dotenvy::dotenv;
-use tracing::{ info, warn };
+use tracing::info;
 use std::env;
 
 use crate::error::AppError;
@@ -13,7 +13,7 @@ pub async fn setup_local_client() -> Result<Client, AppError> {
 
     let config = aws_config
         ::from_env()
-        .behavior_version(BehaviorVersion::v2025_01_17())
+        .behavior_version(BehaviorVersion::v2025_08_07())
         .region(region_provider)
         .load().await;
 
@@ -0,0 +1,762 @@
+//! Data migrations for tables that already exist.
+//!
+//! Table creation in `ensure_table_exists` only covers fresh deployments. When a
+//! key schema or stored format changes, the matching migration here brings an
+//! existing table forward. Migrations are idempotent and are run on demand with
+//! `job_board_lambda migrate` rather than on every cold start, because they may
+//! scan whole tables.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::{
+    Client,
+    types::{
+        AttributeDefinition,
+        AttributeValue,
+        CreateGlobalSecondaryIndexAction,
+        DeleteGlobalSecondaryIndexAction,
+        GlobalSecondaryIndexUpdate,
+        IndexStatus,
+        ScalarAttributeType,
+        TimeToLiveSpecification,
+        TimeToLiveStatus,
+    },
+};
+use tracing::{ info, warn };
+
+use crate::{
+    db::{
+        common::build,
+        job_posting_tables::{
+            employer_id_attribute_definition,
+            employer_index,
+            geohash_attribute_definitions,
+            geohash_index,
+            listing_partition_attribute_definition,
+            timeline_index,
+            EMPLOYER_INDEX,
+            GEOHASH_INDEX,
+            LEGACY_CREATED_AT_INDEX,
+            LEGACY_EMPLOYER_NAME_INDEX,
+            LISTING_PARTITION,
+            LISTING_PARTITION_ATTRIBUTE,
+            PURGE_AT_ATTRIBUTE,
+            TIMELINE_INDEX,
+        },
+    },
+    error::AppError,
+    models::{
+        employer::{ is_http_url, Employer },
+        job_posting::{ JobPosting, ANNUAL_PAY_MAX_ATTRIBUTE, WORK_ARRANGEMENT_ATTRIBUTE },
+        timestamp,
+        user::User,
+    },
+    DynamoDbEntity,
+    Repository,
+};
+
+/// Runs every migration in order.
+pub async fn run_all(client: &Client) -> Result<(), AppError> {
+    migrate_job_postings_timeline(client).await?;
+    migrate_job_postings_employers(client).await?;
+    enable_job_postings_ttl(client).await?;
+    migrate_job_postings_pay(client).await?;
+    migrate_job_postings_work_arrangement(client).await?;
+    migrate_job_postings_geohash(client).await?;
+    migrate_user_email_claims(client).await?;
+
+    Ok(())
+}
+
+/// Writes the email claim of every user registered before claims existed, so
+/// registration can rely on them to keep emails unique. An email that more
+/// than one account already shares is claimed by the first one scanned.
+pub async fn migrate_user_email_claims(client: &Client) -> Result<(), AppError> {
+    let table_name = User::table_name();
+    let mut start_key = None;
+    let mut claimed = 0;
+
+    loop {
+        let response = client
+            .scan()
+            .table_name(table_name)
+            .filter_expression("attribute_exists(email)")
+            .set_exclusive_start_key(start_key.take())
+            .send().await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to scan {}: {}", table_name, e)))?;
+
+        for item in response.items.unwrap_or_default() {
+            let Some(user) = User::from_item(&item) else {
+                warn!("Leaving unreadable user {:?}", item.get("id"));
+                continue;
+            };
+
+            let result = client
+                .put_item()
+                .table_name(table_name)
+                .item("id", AttributeValue::S(user.email_claim_id()))
+                .item("claimed_by", AttributeValue::S(user.id.clone()))
+                .condition_expression("attribute_not_exists(id) OR claimed_by = :user_id")
+                .expression_attribute_values(":user_id", AttributeValue::S(user.id.clone()))
+                .send().await;
+
+            match result {
+                Ok(_) => {
+                    claimed += 1;
+                }
+                Err(e) if
+                    e
+                        .as_service_error()
+                        .is_some_and(|se| se.is_conditional_check_failed_exception())
+                => {
+                    warn!("Email of user {} is already claimed by another account", user.id);
+                }
+                Err(e) => {
+                    return Err(AppError::DatabaseError(format!("Failed to claim email: {}", e)));
+                }
+            }
+        }
+
+        start_key = response.last_evaluated_key;
+
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    info!("Claimed the emails of {} Users items", claimed);
+    Ok(())
+}
+
+/// Rewrites pay stored as a whole `min_base_pay` string into the decimal
+/// `min`/`max`/`currency` format, and adds the denormalized annualized pay
+/// attributes that the `minAnnualPay` filter reads. Legacy pay is taken to be
+/// in US dollars.
+pub async fn migrate_job_postings_pay(client: &Client) -> Result<(), AppError> {
+    let rewritten = rewrite_job_postings(
+        client,
+        "attribute_exists(pay) AND attribute_not_exists(#missing)",
+        ANNUAL_PAY_MAX_ATTRIBUTE
+    ).await?;
+
+    info!("Rewrote pay of {} JobPostings items", rewritten);
+    Ok(())
+}
+
+/// Moves postings with the old `REMOTE` job type to a full-time job type with
+/// a remote work arrangement, and stores the arrangement on every other
+/// posting that predates it as on-site.
+pub async fn migrate_job_postings_work_arrangement(client: &Client) -> Result<(), AppError> {
+    let rewritten = rewrite_job_postings(
+        client,
+        "attribute_not_exists(#missing)",
+        WORK_ARRANGEMENT_ATTRIBUTE
+    ).await?;
+
+    info!("Stored the work arrangement of {} JobPostings items", rewritten);
+    Ok(())
+}
+
+/// Adds `GeohashIndex` to JobPostings, waiting for a later run while another
+/// index is still being built. Existing postings have no coordinates to index
+/// until `job_board_lambda geocode` locates them.
+pub async fn migrate_job_postings_geohash(client: &Client) -> Result<(), AppError> {
+    let table_name = "JobPostings";
+
+    let description = client
+        .describe_table()
+        .table_name(table_name)
+        .send().await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to describe {}: {}", table_name, e)))?;
+
+    let indexes = description
+        .table()
+        .map(|table| table.global_secondary_indexes().to_vec())
+        .unwrap_or_default();
+
+    if indexes.iter().any(|index| index.index_name() == Some(GEOHASH_INDEX)) {
+        return Ok(());
+    }
+
+    if indexes.iter().any(|index| index.index_status() != Some(&IndexStatus::Active)) {
+        warn!(
+            "Another index on {} is still being built; run the migration again to add {}",
+            table_name,
+            GEOHASH_INDEX
+        );
+        return Ok(());
+    }
+
+    info!("Adding {} to {}", GEOHASH_INDEX, table_name);
+
+    let index = geohash_index()?;
+    let create = build(
+        CreateGlobalSecondaryIndexAction::builder()
+            .index_name(GEOHASH_INDEX)
+            .set_key_schema(Some(index.key_schema().to_vec()))
+            .set_projection(index.projection().cloned())
+            .build(),
+        "Failed to build GeohashIndex create action"
+    )?;
+
+    let (geohash_partition, geohash) = geohash_attribute_definitions()?;
+
+    client
+        .update_table()
+        .table_name(table_name)
+        .attribute_definitions(geohash_partition)
+        .attribute_definitions(geohash)
+        .global_secondary_index_updates(GlobalSecondaryIndexUpdate::builder().create(create).build())
+        .send().await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to add {}: {}", GEOHASH_INDEX, e)))?;
+
+    Ok(())
+}
+
+/// Reads every job posting matching `filter_expression` through the model and
+/// writes it back in the current format. `#missing` in the expression stands
+/// for `missing_attribute`. Returns the number of postings rewritten.
+async fn rewrite_job_postings(
+    client: &Client,
+    filter_expression: &str,
+    missing_attribute: &str
+) -> Result<usize, AppError> {
+    let table_name = JobPosting::table_name();
+    let mut start_key = None;
+    let mut rewritten = 0;
+
+    loop {
+        let response = client
+            .scan()
+            .table_name(table_name)
+            .filter_expression(filter_expression)
+            .expression_attribute_names("#missing", missing_attribute)
+            .set_exclusive_start_key(start_key.take())
+            .send().await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to scan {}: {}", table_name, e)))?;
+
+        for item in response.items.unwrap_or_default() {
+            let Some(job_posting) = JobPosting::from_item(&item) else {
+                warn!("Leaving unreadable job posting {:?}", item.get("id"));
+                continue;
+            };
+
+            client
+                .put_item()
+                .table_name(table_name)
+                .set_item(Some(job_posting.to_item()))
+                .condition_expression("attribute_exists(id)")
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(format!("Failed to rewrite job posting: {}", e))
+                )?;
+
+            rewritten += 1;
+        }
+
+        start_key = response.last_evaluated_key;
+
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(rewritten)
+}
+
+/// Turns on Time to Live for JobPostings on `purge_at`, so archived postings
+/// are deleted once their retention ends. Postings that predate the lifecycle
+/// need no backfill: they read as published, and the sweep gives them an expiry.
+pub async fn enable_job_postings_ttl(client: &Client) -> Result<(), AppError> {
+    let table_name = "JobPostings";
+
+    let description = client
+        .describe_time_to_live()
+        .table_name(table_name)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to describe Time to Live of {}: {}", table_name, e)
+            )
+        )?;
+
+    let ttl = description.time_to_live_description();
+    let status = ttl.and_then(|ttl| ttl.time_to_live_status());
+    let attribute = ttl.and_then(|ttl| ttl.attribute_name());
+
+    match status {
+        Some(TimeToLiveStatus::Enabled | TimeToLiveStatus::Enabling) => {
+            if attribute != Some(PURGE_AT_ATTRIBUTE) {
+                warn!(
+                    "{} has Time to Live on {:?} rather than {}; leaving it alone",
+                    table_name,
+                    attribute,
+                    PURGE_AT_ATTRIBUTE
+                );
+            }
+            return Ok(());
+        }
+        Some(TimeToLiveStatus::Disabling) => {
+            warn!("Time to Live of {} is being disabled; run the migration again later", table_name);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    info!("Enabling Time to Live on {}.{}", table_name, PURGE_AT_ATTRIBUTE);
+
+    let specification = build(
+        TimeToLiveSpecification::builder()
+            .enabled(true)
+            .attribute_name(PURGE_AT_ATTRIBUTE)
+            .build(),
+        "Failed to build Time to Live specification"
+    )?;
+
+    client
+        .update_time_to_live()
+        .table_name(table_name)
+        .time_to_live_specification(specification)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to enable Time to Live on {}: {}", table_name, e)
+            )
+        )?;
+
+    Ok(())
+}
+
+/// Moves JobPostings from `CreatedAtIndex` to `TimelineIndex`.
+///
+/// 1. Adds `TimelineIndex` if the table does not have it yet.
+/// 2. Rewrites `created_at`/`updated_at` as RFC 3339 and sets `listing_partition`
+///    on every item that predates the new format.
+/// 3. Drops `CreatedAtIndex` once `TimelineIndex` is active. Only one index can
+///    change per `UpdateTable` call, so this may take a second run.
+pub async fn migrate_job_postings_timeline(client: &Client) -> Result<(), AppError> {
+    let table_name = "JobPostings";
+
+    let description = client
+        .describe_table()
+        .table_name(table_name)
+        .send().await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to describe {}: {}", table_name, e)))?;
+
+    let indexes = description
+        .table()
+        .map(|table| table.global_secondary_indexes().to_vec())
+        .unwrap_or_default();
+
+    let timeline_status = indexes
+        .iter()
+        .find(|index| index.index_name() == Some(TIMELINE_INDEX))
+        .map(|index| index.index_status().cloned());
+    let has_legacy_index = indexes
+        .iter()
+        .any(|index| index.index_name() == Some(LEGACY_CREATED_AT_INDEX));
+
+    match timeline_status {
+        None => {
+            info!("Adding {} to {}", TIMELINE_INDEX, table_name);
+
+            let index = timeline_index()?;
+            let create = build(
+                CreateGlobalSecondaryIndexAction::builder()
+                    .index_name(TIMELINE_INDEX)
+                    .set_key_schema(Some(index.key_schema().to_vec()))
+                    .set_projection(index.projection().cloned())
+                    .build(),
+                "Failed to build TimelineIndex create action"
+            )?;
+
+            let created_at = build(
+                AttributeDefinition::builder()
+                    .attribute_name("created_at")
+                    .attribute_type(ScalarAttributeType::S)
+                    .build(),
+                "Failed to build created_at attribute definition"
+            )?;
+
+            client
+                .update_table()
+                .table_name(table_name)
+                .attribute_definitions(listing_partition_attribute_definition()?)
+                .attribute_definitions(created_at)
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder().create(create).build()
+                )
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(format!("Failed to add {}: {}", TIMELINE_INDEX, e))
+                )?;
+        }
+        Some(Some(IndexStatus::Active)) if has_legacy_index => {
+            info!("Dropping {} from {}", LEGACY_CREATED_AT_INDEX, table_name);
+
+            let delete = build(
+                DeleteGlobalSecondaryIndexAction::builder()
+                    .index_name(LEGACY_CREATED_AT_INDEX)
+                    .build(),
+                "Failed to build CreatedAtIndex delete action"
+            )?;
+
+            client
+                .update_table()
+                .table_name(table_name)
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder().delete(delete).build()
+                )
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(
+                        format!("Failed to drop {}: {}", LEGACY_CREATED_AT_INDEX, e)
+                    )
+                )?;
+        }
+        Some(_) if has_legacy_index => {
+            warn!(
+                "{} is not active yet; run the migration again to drop {}",
+                TIMELINE_INDEX,
+                LEGACY_CREATED_AT_INDEX
+            );
+        }
+        Some(_) => {}
+    }
+
+    backfill_timeline_attributes(client, table_name).await
+}
+
+async fn backfill_timeline_attributes(client: &Client, table_name: &str) -> Result<(), AppError> {
+    let mut start_key = None;
+    let mut rewritten = 0;
+
+    loop {
+        let response = client
+            .scan()
+            .table_name(table_name)
+            .set_exclusive_start_key(start_key.take())
+            .send().await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to scan {}: {}", table_name, e)))?;
+
+        for mut item in response.items.unwrap_or_default() {
+            if !normalize_timeline_attributes(&mut item) {
+                continue;
+            }
+
+            client
+                .put_item()
+                .table_name(table_name)
+                .set_item(Some(item))
+                .condition_expression("attribute_exists(id)")
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(format!("Failed to rewrite job posting: {}", e))
+                )?;
+
+            rewritten += 1;
+        }
+
+        start_key = response.last_evaluated_key;
+
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    info!("Rewrote {} {} items for {}", rewritten, table_name, TIMELINE_INDEX);
+    Ok(())
+}
+
+/// Brings a raw item up to the current timeline format. Returns whether
+/// anything changed.
+fn normalize_timeline_attributes(item: &mut HashMap<String, AttributeValue>) -> bool {
+    let mut changed = false;
+
+    for name in ["created_at", "updated_at"] {
+        let Some(current) = item.get(name) else {
+            continue;
+        };
+
+        match timestamp::from_attribute_value(current) {
+            Some(parsed) => {
+                let normalized = timestamp::to_attribute_value(&parsed);
+                if &normalized != current {
+                    item.insert(name.to_string(), normalized);
+                    changed = true;
+                }
+            }
+            None => {
+                warn!("Unparseable {} on item {:?}", name, item.get("id"));
+            }
+        }
+    }
+
+    let partition = AttributeValue::S(LISTING_PARTITION.to_string());
+    if item.get(LISTING_PARTITION_ATTRIBUTE) != Some(&partition) {
+        item.insert(LISTING_PARTITION_ATTRIBUTE.to_string(), partition);
+        changed = true;
+    }
+
+    changed
+}
+
+/// Moves JobPostings from the free-text `EmployerIndex` to `EmployerIdIndex`.
+///
+/// 1. Adds `EmployerIdIndex` if the table does not have it yet. DynamoDB builds
+///    one index at a time, so this waits for a later run while another index
+///    is still being created.
+/// 2. Points every posting that still carries `employer_name` at an Employers
+///    item with exactly that name, creating the employer (with `employer_url`
+///    as its website) when there is none, and removes the legacy attributes.
+///    Postings that already have an `employer_id` keep it.
+/// 3. Drops `EmployerIndex` once `EmployerIdIndex` is active.
+pub async fn migrate_job_postings_employers(client: &Client) -> Result<(), AppError> {
+    let table_name = "JobPostings";
+
+    let description = client
+        .describe_table()
+        .table_name(table_name)
+        .send().await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to describe {}: {}", table_name, e)))?;
+
+    let indexes = description
+        .table()
+        .map(|table| table.global_secondary_indexes().to_vec())
+        .unwrap_or_default();
+
+    let employer_status = indexes
+        .iter()
+        .find(|index| index.index_name() == Some(EMPLOYER_INDEX))
+        .map(|index| index.index_status().cloned());
+    let has_legacy_index = indexes
+        .iter()
+        .any(|index| index.index_name() == Some(LEGACY_EMPLOYER_NAME_INDEX));
+    let building = indexes
+        .iter()
+        .any(|index| index.index_status() != Some(&IndexStatus::Active));
+
+    match employer_status {
+        None if building => {
+            warn!(
+                "Another index on {} is still being built; run the migration again to add {}",
+                table_name,
+                EMPLOYER_INDEX
+            );
+        }
+        None => {
+            info!("Adding {} to {}", EMPLOYER_INDEX, table_name);
+
+            let index = employer_index()?;
+            let create = build(
+                CreateGlobalSecondaryIndexAction::builder()
+                    .index_name(EMPLOYER_INDEX)
+                    .set_key_schema(Some(index.key_schema().to_vec()))
+                    .set_projection(index.projection().cloned())
+                    .build(),
+                "Failed to build EmployerIdIndex create action"
+            )?;
+
+            client
+                .update_table()
+                .table_name(table_name)
+                .attribute_definitions(employer_id_attribute_definition()?)
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder().create(create).build()
+                )
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(format!("Failed to add {}: {}", EMPLOYER_INDEX, e))
+                )?;
+        }
+        Some(Some(IndexStatus::Active)) if has_legacy_index && !building => {
+            info!("Dropping {} from {}", LEGACY_EMPLOYER_NAME_INDEX, table_name);
+
+            let delete = build(
+                DeleteGlobalSecondaryIndexAction::builder()
+                    .index_name(LEGACY_EMPLOYER_NAME_INDEX)
+                    .build(),
+                "Failed to build EmployerIndex delete action"
+            )?;
+
+            client
+                .update_table()
+                .table_name(table_name)
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder().delete(delete).build()
+                )
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(
+                        format!("Failed to drop {}: {}", LEGACY_EMPLOYER_NAME_INDEX, e)
+                    )
+                )?;
+        }
+        Some(_) if has_legacy_index => {
+            warn!(
+                "{} is not active yet; run the migration again to drop {}",
+                EMPLOYER_INDEX,
+                LEGACY_EMPLOYER_NAME_INDEX
+            );
+        }
+        Some(_) => {}
+    }
+
+    backfill_employer_ids(client, table_name).await
+}
+
+async fn backfill_employer_ids(client: &Client, table_name: &str) -> Result<(), AppError> {
+    let repo = Repository::new(client.clone());
+    // Employers created by this run are not visible through EmployerNameIndex
+    // straight away, so remember every name already resolved
+    let mut employer_ids: HashMap<String, String> = HashMap::new();
+    let mut start_key = None;
+    let mut rewritten = 0;
+
+    loop {
+        let response = client
+            .scan()
+            .table_name(table_name)
+            .filter_expression("attribute_exists(employer_name)")
+            .set_exclusive_start_key(start_key.take())
+            .send().await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to scan {}: {}", table_name, e)))?;
+
+        for mut item in response.items.unwrap_or_default() {
+            let string = |name: &str| item.get(name).and_then(|v| v.as_s().ok()).cloned();
+
+            let Some(name) = string("employer_name").map(|name| name.trim().to_string()) else {
+                continue;
+            };
+            // Only URLs an Employer would accept carry over as its website
+            let website_url = string("employer_url").filter(|url| is_http_url(url));
+
+            let employer_id = match string("employer_id") {
+                Some(employer_id) => {
+                    ensure_employer(&repo, Some(&employer_id), &name, website_url).await?
+                }
+                None =>
+                    match employer_ids.get(&name) {
+                        Some(employer_id) => Some(employer_id.clone()),
+                        None => ensure_employer(&repo, None, &name, website_url).await?,
+                    }
+            };
+
+            let Some(employer_id) = employer_id else {
+                warn!("Leaving job posting {:?} with unusable employer name {:?}", item.get("id"), name);
+                continue;
+            };
+
+            employer_ids.insert(name, employer_id.clone());
+
+            item.insert("employer_id".to_string(), AttributeValue::S(employer_id));
+            item.remove("employer_name");
+            item.remove("employer_url");
+
+            client
+                .put_item()
+                .table_name(table_name)
+                .set_item(Some(item))
+                .condition_expression("attribute_exists(id)")
+                .send().await
+                .map_err(|e|
+                    AppError::DatabaseError(format!("Failed to rewrite job posting: {}", e))
+                )?;
+
+            rewritten += 1;
+        }
+
+        start_key = response.last_evaluated_key;
+
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    info!("Rewrote {} {} items for {}", rewritten, table_name, EMPLOYER_INDEX);
+    Ok(())
+}
+
+/// Resolves the employer a legacy posting belongs to, creating it if needed.
+///
+/// With an `id`, an employer under that id is created when missing. Without
+/// one, the employer is matched by exact `name`. Returns `None` when `name`
+/// does not make a valid employer.
+async fn ensure_employer(
+    repo: &Repository,
+    id: Option<&str>,
+    name: &str,
+    website_url: Option<String>
+) -> Result<Option<String>, AppError> {
+    let existing = match id {
+        Some(id) => repo.get::<Employer>(id.to_string()).await?,
+        None => Employer::find_by_name(repo, name).await?,
+    };
+
+    if let Some(existing) = existing {
+        return Ok(Some(existing.id));
+    }
+
+    let id = id.map_or_else(|| format!("employer-{}", uuid::Uuid::new_v4()), str::to_string);
+
+    let employer = match Employer::new(id, name.to_string(), None, website_url, None, None) {
+        Ok(employer) => employer,
+        Err(e) => {
+            warn!("Cannot create employer {:?}: {}", name, e);
+            return Ok(None);
+        }
+    };
+
+    info!("Creating employer {} for {:?}", employer.id, name);
+
+    Ok(Some(repo.create(employer).await?.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(value: &str) -> AttributeValue {
+        AttributeValue::S(value.to_string())
+    }
+
+    #[test]
+    fn legacy_timestamps_are_rewritten_and_the_listing_partition_added() {
+        let mut item = HashMap::from([
+            ("id".to_string(), s("job_posting-1")),
+            ("created_at".to_string(), s("2026-03-01 10:00:00 UTC")),
+            ("updated_at".to_string(), s("2026-03-02T10:00:00.5+02:00")),
+        ]);
+
+        assert!(normalize_timeline_attributes(&mut item));
+
+        assert_eq!(item["created_at"], s("2026-03-01T10:00:00.000000Z"));
+        assert_eq!(item["updated_at"], s("2026-03-02T08:00:00.500000Z"));
+        assert_eq!(item[LISTING_PARTITION_ATTRIBUTE], s(LISTING_PARTITION));
+    }
+
+    #[test]
+    fn normalized_items_are_left_alone() {
+        let mut item = HashMap::from([
+            ("id".to_string(), s("job_posting-1")),
+            ("created_at".to_string(), s("2026-03-01T10:00:00.000000Z")),
+            (LISTING_PARTITION_ATTRIBUTE.to_string(), s(LISTING_PARTITION)),
+        ]);
+        let before = item.clone();
+
+        assert!(!normalize_timeline_attributes(&mut item));
+        assert_eq!(item, before);
+    }
+
+    #[test]
+    fn unparseable_timestamps_are_kept() {
+        let mut item = HashMap::from([
+            ("id".to_string(), s("job_posting-1")),
+            ("created_at".to_string(), s("yesterday")),
+            (LISTING_PARTITION_ATTRIBUTE.to_string(), s(LISTING_PARTITION)),
+        ]);
+
+        assert!(!normalize_timeline_attributes(&mut item));
+        assert_eq!(item["created_at"], s("yesterday"));
+    }
+}
@@ -3,7 +3,11 @@ pub mod local;
 pub mod connect;
 pub mod ensure_table_exists;
 pub mod job_posting_tables;
+pub mod auth_tables;
+pub mod employer_tables;
+pub mod search_tables;
 pub mod common;
+pub mod migrations;
 
 // Re-export commonly used items
 pub use ensure_table_exists::ensure_all_tables_exist;
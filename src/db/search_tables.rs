@@ -0,0 +1,101 @@
+//! Search index table definitions.
+//!
+//! This module contains the table definition for the inverted index that
+//! keyword search over job postings reads.
+
+use aws_sdk_dynamodb::{
+    Client,
+    operation::list_tables::ListTablesOutput,
+    types::{
+        AttributeDefinition,
+        BillingMode,
+        KeySchemaElement,
+        KeyType,
+        GlobalSecondaryIndex,
+        Projection,
+        ProjectionType,
+        ScalarAttributeType,
+    },
+};
+
+use crate::{db::common::build, error::AppError};
+
+/// Table holding the search index of job postings.
+pub const JOB_POSTING_TERMS_TABLE: &str = "JobPostingTerms";
+/// GSI on `term` in the JobPostingTerms table: the postings list of a term.
+pub const TERM_INDEX: &str = "TermIndex";
+
+/// Creates the JobPostingTerms table.
+///
+/// This table stores the inverted index with the following structure:
+/// - Primary Key: id (String)
+/// - Global Secondary Indexes:
+///   - TermIndex: term (for reading every posting that contains a term)
+pub async fn create_job_posting_terms_table(
+    tables: &ListTablesOutput,
+    client: &Client
+) -> Result<(), AppError> {
+    let table_name = JOB_POSTING_TERMS_TABLE;
+
+    if tables.table_names().contains(&table_name.to_string()) {
+        println!("Table '{}' already exists", table_name);
+        return Ok(());
+    }
+
+    // Define attribute definitions
+    let ad_id = build(
+        AttributeDefinition::builder()
+            .attribute_name("id")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build id attribute definition"
+    )?;
+
+    let ad_term = build(
+        AttributeDefinition::builder()
+            .attribute_name("term")
+            .attribute_type(ScalarAttributeType::S)
+            .build(),
+        "Failed to build term attribute definition"
+    )?;
+
+    // Define key schema
+    let ks_id = build(
+        KeySchemaElement::builder().attribute_name("id").key_type(KeyType::Hash).build(),
+        "Failed to build id key schema"
+    )?;
+
+    // Define GSI 1: Term Index
+    let gsi1_pk = build(
+        KeySchemaElement::builder().attribute_name("term").key_type(KeyType::Hash).build(),
+        "Failed to build Term GSI PK"
+    )?;
+
+    let gsi1 = build(
+        GlobalSecondaryIndex::builder()
+            .index_name(TERM_INDEX)
+            .key_schema(gsi1_pk)
+            .projection(Projection::builder().projection_type(ProjectionType::All).build())
+            .build(),
+        "Failed to build TermIndex GSI"
+    )?;
+
+    // Create the table
+    let response = client
+        .create_table()
+        .table_name(table_name)
+        .billing_mode(BillingMode::PayPerRequest)
+        .attribute_definitions(ad_id)
+        .attribute_definitions(ad_term)
+        .key_schema(ks_id)
+        .global_secondary_indexes(gsi1)
+        .send().await
+        .map_err(|e|
+            AppError::DatabaseError(
+                format!("Failed to create {} table: {:?}", table_name, e.to_string())
+            )
+        )?;
+
+    println!("{} table created: {:?}", table_name, response);
+    Ok(())
+}
@@ -0,0 +1,482 @@
+//! Bulk export of job postings or applications as CSV or NDJSON.
+//!
+//! [`Export`] reads the table with a parallel scan, one task per segment, and
+//! passes each page on as a chunk of output as soon as it is rendered. Rows
+//! come out in no particular order.
+//!
+//! Both formats carry the same fields. CSV flattens nested ones into dotted
+//! columns such as `address.city` or `pay.min` and joins lists with `|`, as
+//! [`import`](crate::import) reads them; NDJSON nests them in objects.
+
+use std::collections::HashSet;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use serde::Deserialize;
+use serde_json::{ Map, Value };
+use tokio::sync::mpsc;
+
+use crate::{
+    csv,
+    models::{ timestamp, work_arrangement::format_utc_offset },
+    pagination::MAX_PAGE_SIZE,
+    repository::{ Filter, ItemQuery },
+    AppError,
+    JobApplication,
+    JobPosting,
+    Repository,
+};
+
+/// Scans that read a table at the same time.
+pub const SEGMENTS: i32 = 4;
+
+/// Separates the items of a list field in CSV.
+const LIST_SEPARATOR: &str = "|";
+
+pub const JOB_POSTING_COLUMNS: &[&str] = &[
+    "id",
+    "job_title",
+    "employer_id",
+    "address.street",
+    "address.unit",
+    "address.city",
+    "address.state",
+    "address.country",
+    "address.zip",
+    "coordinates.latitude",
+    "coordinates.longitude",
+    "pay.cadence",
+    "pay.min",
+    "pay.max",
+    "pay.currency",
+    "job_type",
+    "link_to_application",
+    "job_description",
+    "employee_responsibilities",
+    "experience_requirements",
+    "extra_info",
+    "expected_hours.min",
+    "expected_hours.max",
+    "work_arrangement.type",
+    "work_arrangement.countries",
+    "work_arrangement.utc_offsets.earliest",
+    "work_arrangement.utc_offsets.latest",
+    "category_ids",
+    "held_for_moderation",
+    "status",
+    "publish_at",
+    "expires_at",
+    "closed_at",
+    "created_at",
+    "updated_at",
+];
+
+pub const JOB_APPLICATION_COLUMNS: &[&str] = &[
+    "id",
+    "job_posting_id",
+    "employer_id",
+    "applicant_name",
+    "applicant_email",
+    "cover_note",
+    "status",
+    "created_at",
+    "updated_at",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    /// The format of the file at `path`, by its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportKind {
+    #[default]
+    JobPostings,
+    JobApplications,
+}
+
+impl ExportKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportKind::JobPostings => "job_postings",
+            ExportKind::JobApplications => "job_applications",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "job_postings" => Some(Self::JobPostings),
+            "job_applications" => Some(Self::JobApplications),
+            _ => None,
+        }
+    }
+
+    fn columns(self) -> &'static [&'static str] {
+        match self {
+            ExportKind::JobPostings => JOB_POSTING_COLUMNS,
+            ExportKind::JobApplications => JOB_APPLICATION_COLUMNS,
+        }
+    }
+}
+
+/// What to export. Parameters that are left out do not narrow the export.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub kind: ExportKind,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Only postings of this employer, or applications to them
+    pub employer_id: Option<String>,
+    /// Only rows created at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only rows created before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+enum Cell {
+    Text(String),
+    Number(String),
+    Flag(bool),
+    List(Vec<String>),
+}
+
+/// The fields of one row, in column order. Missing values are `None`.
+type Row = Vec<Option<Cell>>;
+
+fn text(value: &str) -> Option<Cell> {
+    Some(Cell::Text(value.to_string()))
+}
+
+fn number(value: impl ToString) -> Option<Cell> {
+    Some(Cell::Number(value.to_string()))
+}
+
+fn date(value: &DateTime<Utc>) -> Option<Cell> {
+    Some(Cell::Text(timestamp::format(value)))
+}
+
+fn job_posting_row(job_posting: &JobPosting) -> Row {
+    let address = &job_posting.address;
+    let pay = job_posting.pay.as_ref();
+    let work_arrangement = &job_posting.work_arrangement;
+
+    vec![
+        text(&job_posting.id),
+        text(&job_posting.job_title),
+        job_posting.employer_id.as_deref().and_then(text),
+        text(&address.street),
+        address.unit.as_deref().and_then(text),
+        text(&address.city),
+        text(&address.state),
+        text(&address.country),
+        text(&address.zip),
+        job_posting.coordinates.and_then(|coordinates| number(coordinates.latitude)),
+        job_posting.coordinates.and_then(|coordinates| number(coordinates.longitude)),
+        pay.and_then(|pay| text(pay.cadence.as_str())),
+        pay.and_then(|pay| number(pay.min)),
+        pay.and_then(|pay| pay.max).and_then(number),
+        pay.and_then(|pay| text(&pay.currency)),
+        text(job_posting.job_type.as_str()),
+        job_posting.link_to_application.as_deref().and_then(text),
+        text(&job_posting.job_description),
+        job_posting.employee_responsibilities.clone().map(Cell::List),
+        job_posting.experience_requirements.clone().map(Cell::List),
+        job_posting.extra_info.as_deref().and_then(text),
+        number(job_posting.expected_hours.min),
+        number(job_posting.expected_hours.max),
+        text(work_arrangement.arrangement_type.as_str()),
+        Some(Cell::List(work_arrangement.countries.clone())),
+        work_arrangement.utc_offsets.map(|range| Cell::Text(format_utc_offset(range.earliest))),
+        work_arrangement.utc_offsets.map(|range| Cell::Text(format_utc_offset(range.latest))),
+        Some(Cell::List(job_posting.category_ids.clone())),
+        Some(Cell::Flag(job_posting.held_for_moderation)),
+        text(job_posting.status.as_str()),
+        job_posting.publish_at.as_ref().and_then(date),
+        job_posting.expires_at.as_ref().and_then(date),
+        job_posting.closed_at.as_ref().and_then(date),
+        date(&job_posting.created_at),
+        date(&job_posting.updated_at),
+    ]
+}
+
+fn job_application_row(application: &JobApplication, employer_id: Option<&str>) -> Row {
+    vec![
+        text(&application.id),
+        text(&application.job_posting_id),
+        employer_id.and_then(text),
+        text(&application.applicant_name),
+        text(&application.applicant_email),
+        application.cover_note.as_deref().and_then(text),
+        text(application.status.as_str()),
+        date(&application.created_at),
+        date(&application.updated_at),
+    ]
+}
+
+/// Text cells that a spreadsheet would read as formulas are escaped; numbers,
+/// which may be negative, are not.
+fn render_csv(row: Row, out: &mut String) {
+    let fields: Vec<String> = row
+        .into_iter()
+        .map(|cell| {
+            match cell {
+                None => String::new(),
+                Some(Cell::Text(value)) => csv::escape_formula(value),
+                Some(Cell::Number(value)) => value,
+                Some(Cell::Flag(value)) => value.to_string(),
+                Some(Cell::List(items)) => csv::escape_formula(items.join(LIST_SEPARATOR)),
+            }
+        })
+        .collect();
+
+    csv::write_record(&fields, out);
+}
+
+/// Sets `value` at the dotted `path` in `object`, creating nested objects.
+fn insert(object: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            let nested = object.entry(head).or_insert_with(|| Value::Object(Map::new()));
+
+            if let Value::Object(nested) = nested {
+                insert(nested, rest, value);
+            }
+        }
+        None => {
+            object.insert(path.to_string(), value);
+        }
+    }
+}
+
+fn render_ndjson(columns: &[&str], row: Row, out: &mut String) {
+    let mut object = Map::new();
+
+    for (column, cell) in columns.iter().zip(row) {
+        let value = match cell {
+            None => {
+                continue;
+            }
+            Some(Cell::Text(value)) => Value::String(value),
+            Some(Cell::Number(value)) =>
+                match serde_json::from_str(&value) {
+                    Ok(number) => Value::Number(number),
+                    Err(_) => Value::String(value),
+                }
+            Some(Cell::Flag(value)) => Value::Bool(value),
+            Some(Cell::List(items)) => Value::Array(items.into_iter().map(Value::String).collect()),
+        };
+
+        insert(&mut object, column, value);
+    }
+
+    out.push_str(&Value::Object(object).to_string());
+    out.push('\n');
+}
+
+fn render(params: &ExportParams, row: Row, out: &mut String) {
+    match params.format {
+        ExportFormat::Csv => render_csv(row, out),
+        ExportFormat::Ndjson => render_ndjson(params.kind.columns(), row, out),
+    }
+}
+
+/// The scan of one segment, narrowed by the creation date range. The
+/// employer of an application is on its posting, so applications are matched
+/// to employers after they are read.
+fn segment_query(params: &ExportParams, segment: i32) -> ItemQuery {
+    let mut query = ItemQuery::scan().segment(segment, SEGMENTS);
+
+    if let Some(from) = &params.from {
+        query = query.filter(Filter::ge("created_at", timestamp::to_attribute_value(from)));
+    }
+    if let Some(to) = &params.to {
+        query = query.filter(Filter::lt("created_at", timestamp::to_attribute_value(to)));
+    }
+
+    if params.kind == ExportKind::JobPostings && let Some(employer_id) = &params.employer_id {
+        query = query.filter(Filter::eq("employer_id", AttributeValue::S(employer_id.clone())));
+    }
+
+    query
+}
+
+type Chunks = mpsc::Sender<Result<String, AppError>>;
+
+/// Renders the pages of one segment into `chunks`, until the segment is
+/// exhausted or the export is dropped.
+async fn export_segment(
+    repo: &Repository,
+    params: &ExportParams,
+    segment: i32,
+    chunks: &Chunks
+) -> Result<(), AppError> {
+    let query = segment_query(params, segment);
+    let mut after = None;
+
+    loop {
+        let mut chunk = String::new();
+
+        match params.kind {
+            ExportKind::JobPostings => {
+                let page = repo.query::<JobPosting>(&query, MAX_PAGE_SIZE, after).await?;
+                after = page.next_cursor.clone();
+
+                for job_posting in page.into_entities() {
+                    render(params, job_posting_row(&job_posting), &mut chunk);
+                }
+            }
+            ExportKind::JobApplications => {
+                let page = repo.query::<JobApplication>(&query, MAX_PAGE_SIZE, after).await?;
+                after = page.next_cursor.clone();
+
+                let applications = page.into_entities();
+                let job_posting_ids: Vec<String> = applications
+                    .iter()
+                    .map(|application| application.job_posting_id.clone())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect();
+                let job_postings = if job_posting_ids.is_empty() {
+                    Default::default()
+                } else {
+                    repo.get_many::<JobPosting>(&job_posting_ids).await?
+                };
+
+                for application in &applications {
+                    let employer_id = job_postings
+                        .get(&application.job_posting_id)
+                        .and_then(|job_posting| job_posting.employer_id.as_deref());
+
+                    if params.employer_id.as_deref().is_some_and(|wanted| employer_id != Some(wanted)) {
+                        continue;
+                    }
+
+                    render(params, job_application_row(application, employer_id), &mut chunk);
+                }
+            }
+        }
+
+        // Sending fails once the export has been dropped
+        if !chunk.is_empty() && chunks.send(Ok(chunk)).await.is_err() {
+            return Ok(());
+        }
+
+        if after.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+/// Streams an export, one chunk at a time, while the segments are scanned in
+/// the background.
+pub struct Export {
+    header: Option<String>,
+    chunks: mpsc::Receiver<Result<String, AppError>>,
+}
+
+impl Export {
+    /// Starts scanning for the rows `params` asks for. Must be called within a
+    /// Tokio runtime.
+    pub fn start(repo: Repository, params: ExportParams) -> Result<Self, AppError> {
+        if let (Some(from), Some(to)) = (&params.from, &params.to) && from >= to {
+            return Err(AppError::ValidationError("from must be before to".to_string()));
+        }
+
+        let (sender, chunks) = mpsc::channel(SEGMENTS as usize);
+
+        for segment in 0..SEGMENTS {
+            let repo = repo.clone();
+            let params = params.clone();
+            let sender = sender.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = export_segment(&repo, &params, segment, &sender).await {
+                    let _ = sender.send(Err(e)).await;
+                }
+            });
+        }
+
+        let header = match params.format {
+            ExportFormat::Csv => {
+                let mut header = String::new();
+                csv::write_record(params.kind.columns(), &mut header);
+                Some(header)
+            }
+            ExportFormat::Ndjson => None,
+        };
+
+        Ok(Self { header, chunks })
+    }
+
+    /// The next chunk of the export, or `None` once every segment is done.
+    /// After an error the export is abandoned.
+    pub async fn next_chunk(&mut self) -> Result<Option<String>, AppError> {
+        if let Some(header) = self.header.take() {
+            return Ok(Some(header));
+        }
+
+        match self.chunks.recv().await {
+            Some(Ok(chunk)) => Ok(Some(chunk)),
+            Some(Err(e)) => {
+                self.chunks.close();
+                Err(e)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Writes the whole export to `out`, returning the number of bytes
+    /// written.
+    pub async fn write_to<W>(mut self, out: &mut W) -> Result<usize, AppError>
+        where W: tokio::io::AsyncWrite + Unpin
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut written = 0;
+
+        while let Some(chunk) = self.next_chunk().await? {
+            out
+                .write_all(chunk.as_bytes()).await
+                .map_err(|e| AppError::InternalServerError(format!("Failed to write export: {}", e)))?;
+            written += chunk.len();
+        }
+
+        out
+            .flush().await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write export: {}", e)))?;
+
+        Ok(written)
+    }
+}
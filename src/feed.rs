@@ -0,0 +1,282 @@
+//! RSS 2.0 and Atom feeds of the latest job postings.
+//!
+//! Both are written straight from [`JobPosting`]s, without a template engine.
+//! [`latest`] reads the postings newest first through `TimelineIndex`, and
+//! [`Feed`] renders them along with the validators for conditional requests.
+
+use std::{ collections::HashMap, fmt::Write };
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+use chrono::{ DateTime, SecondsFormat, Utc };
+use serde::Deserialize;
+use sha2::{ Digest, Sha256 };
+
+use crate::{
+    config::FeedConfig,
+    markup,
+    models::job_posting::JobTypeOption,
+    pagination,
+    repository::Filter,
+    AppError,
+    Employer,
+    JobPosting,
+    Repository,
+};
+
+pub const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+pub const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+/// Query parameters narrowing a feed. Parameters that are left out do not
+/// narrow it.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FeedParams {
+    /// `FULL_TIME`, `PART_TIME`, `CONTRACT`, `TEMPORARY` or `SEASONAL`
+    pub job_type: Option<String>,
+    pub city: Option<String>,
+    pub employer_id: Option<String>,
+    /// Only postings of the employer with exactly this name
+    pub employer_name: Option<String>,
+    /// Number of postings, up to the maximum page size
+    pub limit: Option<i32>,
+}
+
+/// Reads the `limit` newest postings live at `now` that match `params`.
+pub async fn latest(
+    repo: &Repository,
+    params: &FeedParams,
+    now: DateTime<Utc>
+) -> Result<Vec<JobPosting>, AppError> {
+    let mut query = JobPosting::latest_query(None, now);
+
+    if let Some(job_type) = &params.job_type {
+        let job_type = JobTypeOption::from_string(job_type).map_err(|_| {
+            AppError::ValidationError(format!("Unknown job type: {}", job_type))
+        })?;
+        query = query.filter(Filter::eq("job_type", AttributeValue::S(job_type.to_string())));
+    }
+
+    if let Some(city) = &params.city {
+        query = query.filter(Filter::eq("city", AttributeValue::S(city.clone())));
+    }
+
+    let mut employer_id = params.employer_id.clone();
+
+    if let Some(name) = &params.employer_name {
+        match Employer::find_by_name(repo, name).await? {
+            Some(employer) if employer_id.as_ref().is_none_or(|id| *id == employer.id) => {
+                employer_id = Some(employer.id);
+            }
+            // The name and id name different employers, or no employer at all
+            _ => {
+                return Ok(Vec::new());
+            }
+        }
+    }
+
+    if let Some(employer_id) = employer_id {
+        query = query.filter(Filter::eq("employer_id", AttributeValue::S(employer_id)));
+    }
+
+    let limit = pagination::page_size(params.limit) as usize;
+    let mut job_postings = Vec::new();
+    let mut after = None;
+
+    // Filters apply after the read, so a page can come back short
+    loop {
+        let page = repo.query::<JobPosting>(&query, limit as i32, after).await?;
+        after = page.next_cursor.clone();
+        job_postings.extend(page.into_entities());
+
+        if job_postings.len() >= limit || after.is_none() {
+            job_postings.truncate(limit);
+            return Ok(job_postings);
+        }
+    }
+}
+
+/// A feed of postings, ready to render as RSS or Atom.
+pub struct Feed<'a> {
+    pub config: &'a FeedConfig,
+    pub job_postings: Vec<JobPosting>,
+    /// Employers of the postings, by id
+    pub employers: HashMap<String, Employer>,
+}
+
+impl<'a> Feed<'a> {
+    /// Builds the feed, loading the employers of `job_postings`.
+    pub async fn load(
+        repo: &Repository,
+        config: &'a FeedConfig,
+        job_postings: Vec<JobPosting>
+    ) -> Result<Feed<'a>, AppError> {
+        let mut employer_ids: Vec<String> = job_postings
+            .iter()
+            .filter_map(|job_posting| job_posting.employer_id.clone())
+            .collect();
+        employer_ids.sort();
+        employer_ids.dedup();
+
+        let employers = if employer_ids.is_empty() {
+            HashMap::new()
+        } else {
+            repo.get_many::<Employer>(&employer_ids).await?
+        };
+
+        Ok(Self { config, job_postings, employers })
+    }
+
+    /// When the most recently changed posting changed.
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.job_postings
+            .iter()
+            .map(|job_posting| job_posting.updated_at)
+            .max()
+    }
+
+    /// A strong validator over the ids and `updated_at` of the postings, so it
+    /// changes when a posting is edited, added or drops out of the feed.
+    /// `format` keeps the RSS and Atom renderings apart.
+    pub fn etag(&self, format: &str) -> String {
+        let mut hasher = Sha256::new();
+
+        hasher.update(format.as_bytes());
+
+        for job_posting in &self.job_postings {
+            hasher.update(job_posting.id.as_bytes());
+            hasher.update(job_posting.updated_at.timestamp_micros().to_be_bytes());
+        }
+
+        format!("\"{}\"", URL_SAFE_NO_PAD.encode(&hasher.finalize()[..16]))
+    }
+
+    fn link(&self, job_posting: &JobPosting) -> String {
+        format!("{}/jobs/{}", self.config.site_url.trim_end_matches('/'), job_posting.id)
+    }
+
+    fn employer_name(&self, job_posting: &JobPosting) -> Option<&str> {
+        let employer_id = job_posting.employer_id.as_ref()?;
+
+        self.employers.get(employer_id).map(|employer| employer.name.as_str())
+    }
+
+    /// `Title at Employer, City, State`
+    fn entry_title(&self, job_posting: &JobPosting) -> String {
+        let address = &job_posting.address;
+
+        match self.employer_name(job_posting) {
+            Some(employer) =>
+                format!("{} at {}, {}, {}", job_posting.job_title, employer, address.city, address.state),
+            None => format!("{}, {}, {}", job_posting.job_title, address.city, address.state),
+        }
+    }
+
+    pub fn rss(&self) -> String {
+        let mut xml = String::new();
+        let site_url = markup::escape(&self.config.site_url);
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\">\n<channel>\n");
+        let _ = writeln!(xml, "<title>{}</title>", markup::escape(&self.config.title));
+        let _ = writeln!(xml, "<link>{}</link>", site_url);
+        let _ = writeln!(
+            xml,
+            "<description>Latest job postings on {}</description>",
+            markup::escape(&self.config.title)
+        );
+
+        if let Some(last_modified) = self.last_modified() {
+            let _ = writeln!(xml, "<lastBuildDate>{}</lastBuildDate>", last_modified.to_rfc2822());
+        }
+
+        for job_posting in &self.job_postings {
+            let published = job_posting.publish_at.unwrap_or(job_posting.created_at);
+
+            xml.push_str("<item>\n");
+            let _ = writeln!(xml, "<title>{}</title>", markup::escape(&self.entry_title(job_posting)));
+            let _ = writeln!(xml, "<link>{}</link>", markup::escape(&self.link(job_posting)));
+            let _ = writeln!(
+                xml,
+                "<guid isPermaLink=\"false\">{}</guid>",
+                markup::escape(&job_posting.id)
+            );
+            let _ = writeln!(xml, "<pubDate>{}</pubDate>", published.to_rfc2822());
+            let _ = writeln!(xml, "<category>{}</category>", job_posting.job_type);
+            let _ = writeln!(
+                xml,
+                "<description>{}</description>",
+                markup::escape(&job_posting.job_description)
+            );
+            xml.push_str("</item>\n");
+        }
+
+        xml.push_str("</channel>\n</rss>\n");
+
+        xml
+    }
+
+    /// Renders the Atom feed. `id` identifies this feed, filters included,
+    /// and should stay the same across requests.
+    pub fn atom(&self, id: &str) -> String {
+        let mut xml = String::new();
+        let updated = self.last_modified().unwrap_or(DateTime::UNIX_EPOCH);
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        let _ = writeln!(xml, "<id>{}</id>", markup::escape(id));
+        let _ = writeln!(xml, "<title>{}</title>", markup::escape(&self.config.title));
+        let _ = writeln!(xml, "<updated>{}</updated>", atom_date(&updated));
+        let _ = writeln!(
+            xml,
+            "<link rel=\"alternate\" href=\"{}\"/>",
+            markup::escape(&self.config.site_url)
+        );
+        let _ = writeln!(
+            xml,
+            "<author><name>{}</name></author>",
+            markup::escape(&self.config.title)
+        );
+
+        for job_posting in &self.job_postings {
+            let link = markup::escape(&self.link(job_posting));
+            let published = job_posting.publish_at.unwrap_or(job_posting.created_at);
+
+            xml.push_str("<entry>\n");
+            let _ = writeln!(xml, "<id>{}</id>", link);
+            let _ = writeln!(xml, "<title>{}</title>", markup::escape(&self.entry_title(job_posting)));
+            let _ = writeln!(xml, "<link rel=\"alternate\" href=\"{}\"/>", link);
+            let _ = writeln!(xml, "<published>{}</published>", atom_date(&published));
+            let _ = writeln!(xml, "<updated>{}</updated>", atom_date(&job_posting.updated_at));
+
+            if let Some(employer) = self.employer_name(job_posting) {
+                let _ = writeln!(xml, "<author><name>{}</name></author>", markup::escape(employer));
+            }
+
+            let _ = writeln!(xml, "<category term=\"{}\"/>", job_posting.job_type);
+            let _ = writeln!(
+                xml,
+                "<summary>{}</summary>",
+                markup::escape(&job_posting.job_description)
+            );
+            xml.push_str("</entry>\n");
+        }
+
+        xml.push_str("</feed>\n");
+
+        xml
+    }
+}
+
+fn atom_date(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// `timestamp` as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn http_date(timestamp: &DateTime<Utc>) -> String {
+    timestamp.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Parses an HTTP date as sent in `If-Modified-Since`.
+pub fn parse_http_date(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(s.trim()).ok().map(|date| date.with_timezone(&Utc))
+}
@@ -0,0 +1,152 @@
+//! Geohash encoding and radius coverage.
+//!
+//! A geohash interleaves longitude and latitude bisections into base-32
+//! characters, so every prefix names a rectangular cell and nearby points
+//! usually share a prefix. Points just across a cell edge do not, which is
+//! why [`covering_cells`] returns every cell the search circle touches rather
+//! than only the one holding its center.
+
+use crate::models::coordinates::Coordinates;
+
+use super::KM_PER_DEGREE;
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest hash [`covering_cells`] considers; cells are about 150 m across.
+const MAX_COVERING_PRECISION: usize = 7;
+/// Coverings of more cells than this move on to a coarser precision.
+const MAX_COVERING_CELLS: usize = 16;
+
+/// Encodes `point` as a geohash of `precision` characters.
+pub fn encode(point: Coordinates, precision: usize) -> String {
+    let mut latitude = (-90.0, 90.0);
+    let mut longitude = (-180.0, 180.0);
+    let mut hash = String::with_capacity(precision);
+    let mut even_bit = true;
+
+    while hash.len() < precision {
+        let mut index = 0;
+
+        for _ in 0..5 {
+            let (range, value) = if even_bit {
+                (&mut longitude, point.longitude)
+            } else {
+                (&mut latitude, point.latitude)
+            };
+            let middle = (range.0 + range.1) / 2.0;
+
+            index <<= 1;
+            if value >= middle {
+                index |= 1;
+                range.0 = middle;
+            } else {
+                range.1 = middle;
+            }
+
+            even_bit = !even_bit;
+        }
+
+        hash.push(BASE32[index] as char);
+    }
+
+    hash
+}
+
+/// Rows of latitude and columns of longitude cells at `precision`.
+fn grid_size(precision: usize) -> (u64, u64) {
+    let bits = 5 * precision as u32;
+    let latitude_bits = bits / 2;
+    let longitude_bits = bits - latitude_bits;
+
+    (1 << latitude_bits, 1 << longitude_bits)
+}
+
+/// Latitude rows and longitude columns of the cells that overlap the bounding
+/// box of a search circle. Columns are taken modulo the grid width, so the
+/// range may wrap around the antimeridian.
+struct Coverage {
+    rows: (u64, u64),
+    columns: (i64, i64),
+    width: u64,
+}
+
+impl Coverage {
+    fn new(center: Coordinates, radius_km: f64, precision: usize) -> Self {
+        let (height, width) = grid_size(precision);
+        let cell_height = 180.0 / height as f64;
+        let cell_width = 360.0 / width as f64;
+
+        let latitude_delta = radius_km / KM_PER_DEGREE;
+        let south = (center.latitude - latitude_delta).max(-90.0);
+        let north = (center.latitude + latitude_delta).min(90.0);
+
+        let row = |latitude: f64| (((latitude + 90.0) / cell_height) as u64).min(height - 1);
+        let rows = (row(south), row(north));
+
+        // The circle is widest at its poleward edge; around a pole it spans
+        // every longitude
+        let widest = south.abs().max(north.abs()).to_radians().cos();
+        let longitude_delta = if north >= 90.0 || south <= -90.0 || widest <= 0.0 {
+            180.0
+        } else {
+            radius_km / (KM_PER_DEGREE * widest)
+        };
+
+        let columns = if longitude_delta >= 180.0 {
+            (0, width as i64 - 1)
+        } else {
+            let column = |longitude: f64| ((longitude + 180.0) / cell_width).floor() as i64;
+            let west = column(center.longitude - longitude_delta);
+            let east = column(center.longitude + longitude_delta);
+
+            (west, east.min(west + width as i64 - 1))
+        };
+
+        Self { rows, columns, width }
+    }
+
+    fn cell_count(&self) -> usize {
+        let rows = self.rows.1 - self.rows.0 + 1;
+        let columns = (self.columns.1 - self.columns.0 + 1) as u64;
+
+        (rows * columns) as usize
+    }
+
+    fn cells(&self, precision: usize) -> Vec<String> {
+        let (height, _) = grid_size(precision);
+        let cell_height = 180.0 / height as f64;
+        let cell_width = 360.0 / self.width as f64;
+
+        let mut cells = Vec::with_capacity(self.cell_count());
+
+        for row in self.rows.0..=self.rows.1 {
+            for column in self.columns.0..=self.columns.1 {
+                let column = column.rem_euclid(self.width as i64);
+                let middle = Coordinates {
+                    latitude: -90.0 + (row as f64 + 0.5) * cell_height,
+                    longitude: -180.0 + (column as f64 + 0.5) * cell_width,
+                };
+
+                cells.push(encode(middle, precision));
+            }
+        }
+
+        cells
+    }
+}
+
+/// Geohash cells that together contain every point within `radius_km` of
+/// `center`. All cells share one length, no shorter than `min_precision`: the
+/// longest one that needs at most a handful of cells.
+pub fn covering_cells(center: Coordinates, radius_km: f64, min_precision: usize) -> Vec<String> {
+    let min_precision = min_precision.clamp(1, MAX_COVERING_PRECISION);
+
+    let precision = (min_precision + 1..=MAX_COVERING_PRECISION)
+        .rev()
+        .find(|precision| {
+            Coverage::new(center, radius_km, *precision).cell_count() <= MAX_COVERING_CELLS
+        })
+        .unwrap_or(min_precision);
+
+    Coverage::new(center, radius_km, precision).cells(precision)
+}
@@ -0,0 +1,229 @@
+//! Locating job postings on the map.
+//!
+//! Postings carry [`Coordinates`], either supplied by the employer or looked up
+//! from the address by a [`Geocoder`]. Each located posting also stores its
+//! geohash, which `GeohashIndex` keeps sorted within coarse cells, so a radius
+//! search reads only the cells around the center and refines them by
+//! [`distance_km`].
+//!
+//! [`TableGeocoder`] resolves addresses from a table of postal codes and cities
+//! held in memory, so geocoding needs no network access.
+
+use std::{ collections::HashMap, sync::Arc };
+
+use async_trait::async_trait;
+use tracing::{ info, warn };
+
+use crate::{
+    config::GeocodingConfig,
+    models::{ address::Address, coordinates::Coordinates, job_posting::JobPosting, timestamp },
+    pagination::MAX_PAGE_SIZE,
+    repository::ItemQuery,
+    AppError,
+    Repository,
+};
+
+pub mod geohash;
+
+/// Mean radius of the earth.
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+/// Length of one degree of latitude, and of longitude on the equator.
+pub(crate) const KM_PER_DEGREE: f64 = (EARTH_RADIUS_KM * std::f64::consts::PI) / 180.0;
+/// Largest radius a search may cover.
+pub const MAX_RADIUS_KM: f64 = 500.0;
+
+/// Great-circle distance between two points, by the haversine formula.
+pub fn distance_km(a: Coordinates, b: Coordinates) -> f64 {
+    let latitude_a = a.latitude.to_radians();
+    let latitude_b = b.latitude.to_radians();
+    let half_latitude = (latitude_b - latitude_a) / 2.0;
+    let half_longitude = (b.longitude - a.longitude).to_radians() / 2.0;
+
+    let h =
+        half_latitude.sin().powi(2) +
+        latitude_a.cos() * latitude_b.cos() * half_longitude.sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+/// Turns an address into coordinates.
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    /// Where `address` is, or `None` if it cannot be located.
+    async fn geocode(&self, address: &Address) -> Result<Option<Coordinates>, AppError>;
+}
+
+/// How resolvers find the geocoder in the context data.
+pub type SharedGeocoder = Arc<dyn Geocoder>;
+
+fn normalize(s: &str) -> String {
+    s.trim().to_uppercase()
+}
+
+/// [`Geocoder`] backed by a lookup table. Addresses are matched on country and
+/// postal code first, then on country, state and city.
+#[derive(Clone, Debug, Default)]
+pub struct TableGeocoder {
+    postal_codes: HashMap<(String, String), Coordinates>,
+    cities: HashMap<(String, String, String), Coordinates>,
+}
+
+impl TableGeocoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_postal_code(mut self, country: &str, postal_code: &str, point: Coordinates) -> Self {
+        self.postal_codes.insert((normalize(country), normalize(postal_code)), point);
+        self
+    }
+
+    pub fn with_city(mut self, country: &str, state: &str, city: &str, point: Coordinates) -> Self {
+        self.cities.insert((normalize(country), normalize(state), normalize(city)), point);
+        self
+    }
+
+    /// Reads a table with the columns
+    /// `country,postal_code,city,state,latitude,longitude`. Either the postal
+    /// code or the city may be left empty. Fields cannot be quoted; blank lines,
+    /// `#` comments and a header row are skipped.
+    pub fn from_csv(csv: &str) -> Result<Self, AppError> {
+        let mut geocoder = Self::new();
+
+        for (number, line) in csv.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with("country,") {
+                continue;
+            }
+
+            let invalid = |reason: &str| {
+                AppError::ConfigError(format!("Geocoding table line {}: {}", number + 1, reason))
+            };
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+
+            let [country, postal_code, city, state, latitude, longitude] = fields[..] else {
+                return Err(invalid("expected 6 fields"));
+            };
+
+            let latitude = latitude.parse::<f64>().map_err(|_| invalid("invalid latitude"))?;
+            let longitude = longitude.parse::<f64>().map_err(|_| invalid("invalid longitude"))?;
+            let point = Coordinates::new(latitude, longitude).map_err(|e| invalid(&e.to_string()))?;
+
+            if !postal_code.is_empty() {
+                geocoder = geocoder.with_postal_code(country, postal_code, point);
+            }
+            if !city.is_empty() {
+                geocoder = geocoder.with_city(country, state, city, point);
+            }
+        }
+
+        Ok(geocoder)
+    }
+
+    /// Loads the table named by the configuration, or an empty table that
+    /// locates nothing.
+    pub fn from_config(config: &GeocodingConfig) -> Result<Self, AppError> {
+        let Some(path) = &config.table_path else {
+            return Ok(Self::new());
+        };
+
+        let csv = std::fs::read_to_string(path).map_err(|e| {
+            AppError::ConfigError(format!("Failed to read geocoding table {}: {}", path, e))
+        })?;
+
+        Self::from_csv(&csv)
+    }
+
+    fn lookup(&self, address: &Address) -> Option<Coordinates> {
+        let country = normalize(&address.country);
+        let postal_code = normalize(&address.zip);
+
+        // ZIP+4 codes and the like fall back to their first part
+        let base_postal_code = postal_code
+            .split(['-', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        self.postal_codes
+            .get(&(country.clone(), postal_code))
+            .or_else(|| self.postal_codes.get(&(country.clone(), base_postal_code)))
+            .or_else(|| {
+                self.cities.get(&(country, normalize(&address.state), normalize(&address.city)))
+            })
+            .copied()
+    }
+}
+
+#[async_trait]
+impl Geocoder for TableGeocoder {
+    async fn geocode(&self, address: &Address) -> Result<Option<Coordinates>, AppError> {
+        Ok(self.lookup(address))
+    }
+}
+
+/// Looks up the posting's address. A failing geocoder leaves the posting
+/// unlocated rather than failing the write.
+pub async fn locate_or_warn(geocoder: &dyn Geocoder, job_posting: &mut JobPosting) {
+    match geocoder.geocode(&job_posting.address).await {
+        Ok(coordinates) => {
+            job_posting.coordinates = coordinates;
+        }
+        Err(e) => {
+            warn!("Could not geocode job posting {}: {}", job_posting.id, e);
+        }
+    }
+}
+
+/// Geocodes the address of every posting that has no coordinates yet,
+/// returning how many were located. Updates are conditional on `updated_at`,
+/// like the lifecycle sweep, so concurrent edits win.
+pub async fn geocode_job_postings(
+    repo: &Repository,
+    geocoder: &dyn Geocoder
+) -> Result<usize, AppError> {
+    let query = ItemQuery::scan();
+    let mut located = 0;
+    let mut after = None;
+
+    loop {
+        let page = repo.query::<JobPosting>(&query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for mut job_posting in page.into_entities() {
+            if job_posting.coordinates.is_some() {
+                continue;
+            }
+
+            let Some(point) = geocoder.geocode(&job_posting.address).await? else {
+                continue;
+            };
+
+            let id = job_posting.id.clone();
+            let previous_updated_at = timestamp::to_attribute_value(&job_posting.updated_at);
+            job_posting.coordinates = Some(point);
+
+            match repo.update_if(job_posting, "updated_at", previous_updated_at).await {
+                Ok(_) => {
+                    located += 1;
+                }
+                Err(AppError::ValidationError(message)) => {
+                    warn!("Skipping job posting {}: {}", id, message);
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+
+        if after.is_none() {
+            break;
+        }
+    }
+
+    info!("Geocoded {} job postings", located);
+
+    Ok(located)
+}
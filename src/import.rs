@@ -0,0 +1,768 @@
+//! Bulk import of job postings from CSV or NDJSON.
+//!
+//! Every row becomes one posting, with the fields `createJobPosting` takes.
+//! Nested fields are dotted columns, such as `address.city` or `pay.min`; in
+//! NDJSON they can also be nested objects. List fields are JSON arrays, or
+//! `|`-separated in CSV.
+//!
+//! All rows are validated before anything is written, and a single row with
+//! errors rejects the whole import, so a dry run reports exactly the errors a
+//! real import would stop at. Valid imports are written with
+//! [`Repository::put_many`], in batches.
+
+use std::{ collections::HashMap, str::FromStr };
+
+use async_graphql::{ Enum, SimpleObject };
+use chrono::{ DateTime, Duration, Utc };
+use rust_decimal::Decimal;
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    auth::Principal,
+    config::Config,
+    csv,
+    geo::{ self, Geocoder },
+    models::{
+        coordinates::Coordinates,
+        job_posting::{ ExpectedHoursRange, JobTypeOption },
+        pay::CadenceOption,
+        work_arrangement::{
+            UtcOffsetRangeInput,
+            WorkArrangement,
+            WorkArrangementInput,
+            WorkArrangementType,
+        },
+    },
+    search,
+    Address,
+    AppError,
+    Employer,
+    JobCategory,
+    JobPosting,
+    Pay,
+    Repository,
+};
+
+/// Most rows a single import may have.
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+/// Separates the items of a list field in CSV.
+const LIST_SEPARATOR: char = '|';
+
+/// The columns an import may have.
+pub const COLUMNS: &[&str] = &[
+    "job_title",
+    "employer_id",
+    "address.street",
+    "address.unit",
+    "address.city",
+    "address.state",
+    "address.country",
+    "address.zip",
+    "coordinates.latitude",
+    "coordinates.longitude",
+    "pay.cadence",
+    "pay.min",
+    "pay.max",
+    "pay.currency",
+    "job_type",
+    "link_to_application",
+    "job_description",
+    "employee_responsibilities",
+    "experience_requirements",
+    "extra_info",
+    "expected_hours.min",
+    "expected_hours.max",
+    "work_arrangement.type",
+    "work_arrangement.countries",
+    "work_arrangement.utc_offsets.earliest",
+    "work_arrangement.utc_offsets.latest",
+    "category_ids",
+    "draft",
+    "publish_at",
+    "expires_at",
+];
+
+#[derive(Enum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Comma-separated values with a header row naming the columns
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl ImportFormat {
+    /// The format of the file at `path`, by its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with one row of an import.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub struct ImportRowError {
+    /// The row, counting from 1. Row 0 is the CSV header.
+    pub row: usize,
+    /// The column at fault, if the problem is with a single one
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// What an import found, and wrote.
+#[derive(Clone, Debug, Default, PartialEq, Eq, SimpleObject)]
+pub struct ImportReport {
+    /// Rows read
+    pub rows: usize,
+    /// Rows without errors
+    pub valid_rows: usize,
+    /// Postings written; none on a dry run or when any row has errors
+    pub imported: usize,
+    pub dry_run: bool,
+    pub errors: Vec<ImportRowError>,
+}
+
+enum Field {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// The fields of one row, taken out as they are read so that whatever is
+/// left over is an unknown column.
+struct Row {
+    number: usize,
+    // False when the row could not be parsed at all, so it has no fields
+    readable: bool,
+    fields: HashMap<String, Field>,
+    errors: Vec<ImportRowError>,
+}
+
+impl Row {
+    fn new(number: usize) -> Self {
+        Self {
+            number,
+            readable: true,
+            fields: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn unreadable(number: usize, message: impl Into<String>) -> Self {
+        let mut row = Self::new(number);
+        row.readable = false;
+        row.error(None, message);
+
+        row
+    }
+
+    fn error(&mut self, field: Option<&str>, message: impl Into<String>) {
+        self.errors.push(ImportRowError {
+            row: self.number,
+            field: field.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    /// Whether `column` has a value. Blank values count as missing.
+    fn has(&self, column: &str) -> bool {
+        match self.fields.get(column) {
+            Some(Field::Text(text)) => !text.trim().is_empty(),
+            Some(Field::List(_)) => true,
+            None => false,
+        }
+    }
+
+    fn text(&mut self, column: &str) -> Option<String> {
+        match self.fields.remove(column)? {
+            Field::Text(text) => {
+                let text = text.trim();
+                (!text.is_empty()).then(|| text.to_string())
+            }
+            Field::List(_) => {
+                self.error(Some(column), "Expected a single value, not a list");
+                None
+            }
+        }
+    }
+
+    fn list(&mut self, column: &str) -> Option<Vec<String>> {
+        let items: Vec<String> = match self.fields.remove(column)? {
+            Field::Text(text) => text.split(LIST_SEPARATOR).map(str::to_string).collect(),
+            Field::List(items) => items,
+        };
+
+        Some(
+            items
+                .iter()
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        )
+    }
+
+    fn parse<T: FromStr>(&mut self, column: &str) -> Option<T> {
+        let text = self.text(column)?;
+
+        match text.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.error(Some(column), format!("Invalid value: {}", text));
+                None
+            }
+        }
+    }
+
+    fn required<T: FromStr>(&mut self, column: &str) -> Option<T> {
+        if !self.has(column) {
+            self.fields.remove(column);
+            self.error(Some(column), "A value is required");
+            return None;
+        }
+
+        self.parse(column)
+    }
+
+    /// `true`, `false` or missing, in any case.
+    fn flag(&mut self, column: &str) -> Option<bool> {
+        let text = self.text(column)?;
+
+        match text.to_ascii_lowercase().parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.error(Some(column), format!("Invalid value: {}", text));
+                None
+            }
+        }
+    }
+
+    /// The value of `result`, or `None` with its error recorded against `field`.
+    fn check<T>(&mut self, field: &str, result: Result<T, AppError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(Some(field), message(e));
+                None
+            }
+        }
+    }
+
+    /// Rejects the fields that are left over. Known columns left over were
+    /// blank and so not read.
+    fn reject_unknown_columns(&mut self) {
+        let mut columns: Vec<String> = self.fields
+            .drain()
+            .map(|(column, _)| column)
+            .filter(|column| !COLUMNS.contains(&column.as_str()))
+            .collect();
+        columns.sort();
+
+        for column in columns {
+            self.error(Some(&column), "Unknown column");
+        }
+    }
+}
+
+fn message(e: AppError) -> String {
+    match e {
+        | AppError::DatabaseError(message)
+        | AppError::ValidationError(message)
+        | AppError::NotFound(message)
+        | AppError::Unauthorized(message)
+        | AppError::Forbidden(message)
+        | AppError::InternalServerError(message)
+        | AppError::ConfigError(message)
+        | AppError::AuthError(message) => message,
+    }
+}
+
+/// Reads the rows of `data`, along with problems that are not with a single
+/// row.
+fn read(format: ImportFormat, data: &str) -> Result<(Vec<Row>, Vec<ImportRowError>), AppError> {
+    match format {
+        ImportFormat::Csv => read_csv(data),
+        ImportFormat::Ndjson => Ok((read_ndjson(data), Vec::new())),
+    }
+}
+
+fn read_csv(data: &str) -> Result<(Vec<Row>, Vec<ImportRowError>), AppError> {
+    let mut records = csv::parse(data)?.into_iter();
+    let mut errors = Vec::new();
+
+    let Some(header) = records.next() else {
+        return Ok((Vec::new(), errors));
+    };
+    let header: Vec<String> = header
+        .iter()
+        .map(|column| column.trim().to_string())
+        .collect();
+
+    for (i, column) in header.iter().enumerate() {
+        let problem = if !COLUMNS.contains(&column.as_str()) {
+            "Unknown column"
+        } else if header[..i].contains(column) {
+            "Duplicate column"
+        } else {
+            continue;
+        };
+
+        errors.push(ImportRowError {
+            row: 0,
+            field: Some(column.clone()),
+            message: problem.to_string(),
+        });
+    }
+
+    let rows = records
+        .enumerate()
+        .map(|(i, record)| {
+            let mut row = Row::new(i + 1);
+
+            if record.len() != header.len() {
+                row.error(
+                    None,
+                    format!("Expected {} fields, found {}", header.len(), record.len())
+                );
+            }
+
+            // Unknown columns were reported once for the header. Cells
+            // escaped against formulas on export read back as written.
+            for (column, value) in header.iter().zip(record) {
+                if COLUMNS.contains(&column.as_str()) {
+                    row.fields
+                        .entry(column.clone())
+                        .or_insert(Field::Text(csv::unescape_formula(value)));
+                }
+            }
+
+            row
+        })
+        .collect();
+
+    Ok((rows, errors))
+}
+
+fn read_ndjson(data: &str) -> Vec<Row> {
+    data.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            match serde_json::from_str::<Value>(line) {
+                Ok(Value::Object(object)) => {
+                    let mut row = Row::new(i + 1);
+
+                    for (key, value) in object {
+                        flatten(&mut row, key, value);
+                    }
+
+                    row
+                }
+                Ok(_) => Row::unreadable(i + 1, "Expected a JSON object"),
+                Err(e) => Row::unreadable(i + 1, format!("Invalid JSON: {}", e)),
+            }
+        })
+        .collect()
+}
+
+/// Adds `value` to the row under `key`, with nested objects as dotted keys.
+fn flatten(row: &mut Row, key: String, value: Value) {
+    let field = match value {
+        Value::Null => {
+            return;
+        }
+        Value::Object(object) => {
+            for (name, value) in object {
+                flatten(row, format!("{}.{}", key, name), value);
+            }
+            return;
+        }
+        Value::String(text) => Field::Text(text),
+        Value::Number(number) => Field::Text(number.to_string()),
+        Value::Bool(flag) => Field::Text(flag.to_string()),
+        Value::Array(items) => {
+            let items: Option<Vec<String>> = items
+                .into_iter()
+                .map(|item| {
+                    match item {
+                        Value::String(text) => Some(text),
+                        _ => None,
+                    }
+                })
+                .collect();
+
+            match items {
+                Some(items) => Field::List(items),
+                None => {
+                    row.error(Some(&key), "Expected a list of strings");
+                    return;
+                }
+            }
+        }
+    };
+
+    row.fields.insert(key, field);
+}
+
+/// Looks up what rows refer to, once per import.
+struct Lookups<'a> {
+    repo: &'a Repository,
+    principal: &'a Principal,
+    // Employer ids by the id a row asked for, or why it cannot have it
+    employers: HashMap<Option<String>, Result<String, String>>,
+    categories: HashMap<String, bool>,
+    verified: HashMap<String, bool>,
+}
+
+impl<'a> Lookups<'a> {
+    fn new(repo: &'a Repository, principal: &'a Principal) -> Self {
+        Self {
+            repo,
+            principal,
+            employers: HashMap::new(),
+            categories: HashMap::new(),
+            verified: HashMap::new(),
+        }
+    }
+
+    async fn employer_id(&mut self, requested: Option<String>) -> Result<Result<String, String>, AppError> {
+        if let Some(resolved) = self.employers.get(&requested) {
+            return Ok(resolved.clone());
+        }
+
+        let resolved = match self.principal.posting_employer_id(self.repo, requested.as_deref()).await {
+            Ok(employer_id) => Ok(employer_id),
+            Err(AppError::ValidationError(message) | AppError::Forbidden(message)) => Err(message),
+            Err(e) => {
+                return Err(e);
+            }
+        };
+
+        self.employers.insert(requested, resolved.clone());
+
+        Ok(resolved)
+    }
+
+    async fn category_exists(&mut self, id: &str) -> Result<bool, AppError> {
+        if let Some(exists) = self.categories.get(id) {
+            return Ok(*exists);
+        }
+
+        let exists = self.repo.get::<JobCategory>(id.to_string()).await?.is_some();
+        self.categories.insert(id.to_string(), exists);
+
+        Ok(exists)
+    }
+
+    async fn is_verified(&mut self, employer_id: &str) -> Result<bool, AppError> {
+        if let Some(verified) = self.verified.get(employer_id) {
+            return Ok(*verified);
+        }
+
+        let verified = self.repo
+            .get::<Employer>(employer_id.to_string()).await?
+            .is_some_and(|employer| employer.is_domain_verified());
+        self.verified.insert(employer_id.to_string(), verified);
+
+        Ok(verified)
+    }
+}
+
+/// Imports job postings on behalf of a caller, with the same rules as
+/// `createJobPosting`.
+pub struct Importer<'a> {
+    repo: &'a Repository,
+    config: &'a Config,
+    geocoder: &'a dyn Geocoder,
+    principal: &'a Principal,
+}
+
+impl<'a> Importer<'a> {
+    pub fn new(
+        repo: &'a Repository,
+        config: &'a Config,
+        geocoder: &'a dyn Geocoder,
+        principal: &'a Principal
+    ) -> Self {
+        Self { repo, config, geocoder, principal }
+    }
+
+    /// Validates every row of `data` and, unless `dry_run` is set or a row has
+    /// errors, writes them as postings. Rows without an `employer_id` belong to
+    /// `employer_id`, or to the caller's employer.
+    ///
+    /// Problems with rows are in the report; errors are for imports that could
+    /// not be read at all or failed to write.
+    pub async fn import(
+        &self,
+        format: ImportFormat,
+        data: &str,
+        employer_id: Option<&str>,
+        dry_run: bool,
+        now: DateTime<Utc>
+    ) -> Result<ImportReport, AppError> {
+        let (rows, mut errors) = read(format, data)?;
+
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(
+                AppError::ValidationError(
+                    format!("An import can have at most {} rows", MAX_IMPORT_ROWS)
+                )
+            );
+        }
+
+        let mut lookups = Lookups::new(self.repo, self.principal);
+        let mut job_postings = Vec::new();
+        let mut report = ImportReport {
+            rows: rows.len(),
+            dry_run,
+            ..ImportReport::default()
+        };
+
+        for mut row in rows {
+            if !row.readable {
+                errors.append(&mut row.errors);
+                continue;
+            }
+
+            let job_posting = self.job_posting(&mut row, employer_id, &mut lookups, now).await?;
+
+            match job_posting {
+                Some(job_posting) if row.errors.is_empty() => {
+                    report.valid_rows += 1;
+                    job_postings.push(job_posting);
+                }
+                _ => errors.append(&mut row.errors),
+            }
+        }
+
+        report.errors = errors;
+
+        if dry_run || !report.errors.is_empty() {
+            return Ok(report);
+        }
+
+        for job_posting in &mut job_postings {
+            if job_posting.coordinates.is_none() {
+                geo::locate_or_warn(self.geocoder, job_posting).await;
+            }
+
+            if self.config.verification.hold_unverified_postings {
+                let employer_id = job_posting.employer_id.clone().unwrap_or_default();
+                job_posting.held_for_moderation = !lookups.is_verified(&employer_id).await?;
+            }
+        }
+
+        self.repo.put_many(&job_postings).await?;
+
+        for job_posting in &job_postings {
+            search::index_job_posting_or_warn(self.repo, job_posting).await;
+        }
+
+        report.imported = job_postings.len();
+        info!("Imported {} job postings", report.imported);
+
+        Ok(report)
+    }
+
+    /// The posting described by `row`, or `None` with the row's errors
+    /// recorded on it.
+    async fn job_posting(
+        &self,
+        row: &mut Row,
+        default_employer_id: Option<&str>,
+        lookups: &mut Lookups<'_>,
+        now: DateTime<Utc>
+    ) -> Result<Option<JobPosting>, AppError> {
+        let job_title = row.required::<String>("job_title");
+
+        let requested = row.text("employer_id").or_else(|| default_employer_id.map(str::to_string));
+        let employer_id = match lookups.employer_id(requested).await? {
+            Ok(employer_id) => Some(employer_id),
+            Err(message) => {
+                row.error(Some("employer_id"), message);
+                None
+            }
+        };
+
+        let address = Address::new(
+            row.text("address.street").unwrap_or_default(),
+            row.text("address.unit"),
+            row.text("address.city").unwrap_or_default(),
+            row.text("address.state").unwrap_or_default(),
+            row.text("address.country").unwrap_or_default(),
+            row.text("address.zip").unwrap_or_default()
+        );
+
+        if let Err(message) = address.validate() {
+            row.error(Some("address"), message);
+        }
+
+        let coordinates = if row.has("coordinates.latitude") || row.has("coordinates.longitude") {
+            let latitude = row.required::<f64>("coordinates.latitude");
+            let longitude = row.required::<f64>("coordinates.longitude");
+
+            match (latitude, longitude) {
+                (Some(latitude), Some(longitude)) =>
+                    row.check("coordinates", Coordinates::new(latitude, longitude)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let pay = if ["pay.cadence", "pay.min", "pay.max", "pay.currency"].iter().any(|c| row.has(c)) {
+            let cadence = row.required::<String>("pay.cadence").and_then(|cadence| {
+                let parsed = CadenceOption::from_string(&cadence.to_ascii_uppercase()).map_err(|_| {
+                    AppError::ValidationError(format!("Unknown pay cadence: {}", cadence))
+                });
+                row.check("pay.cadence", parsed)
+            });
+            let min = row.required::<Decimal>("pay.min");
+            let max = row.parse::<Decimal>("pay.max");
+            let currency = row.text("pay.currency").unwrap_or_else(|| "USD".to_string());
+
+            match (cadence, min) {
+                (Some(cadence), Some(min)) => row.check("pay", Pay::new(cadence, min, max, &currency)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let job_type = row.required::<String>("job_type").and_then(|job_type| {
+            let job_type = job_type.to_ascii_uppercase();
+
+            if JobTypeOption::from_string(&job_type).is_err() {
+                row.error(Some("job_type"), format!("Unknown job type: {}", job_type));
+                return None;
+            }
+
+            Some(job_type)
+        });
+
+        let link_to_application = row.text("link_to_application");
+        let job_description = row.required::<String>("job_description");
+        let employee_responsibilities = row.list("employee_responsibilities");
+        let experience_requirements = row.list("experience_requirements");
+        let extra_info = row.text("extra_info");
+
+        let expected_hours_min = row.required::<u8>("expected_hours.min");
+        let expected_hours_max = row.required::<u8>("expected_hours.max");
+
+        if let (Some(min), Some(max)) = (expected_hours_min, expected_hours_max) {
+            row.check("expected_hours", ExpectedHoursRange::new(min, max).validate());
+        }
+
+        let arrangement_type = match row.text("work_arrangement.type") {
+            Some(text) => {
+                let parsed = WorkArrangementType::from_string(&text.to_ascii_uppercase()).map_err(|_| {
+                    AppError::ValidationError(format!("Unknown work arrangement: {}", text))
+                });
+                row.check("work_arrangement.type", parsed)
+            }
+            None => Some(WorkArrangementType::default()),
+        };
+        let countries = row.list("work_arrangement.countries");
+        let utc_offsets = if
+            row.has("work_arrangement.utc_offsets.earliest") ||
+            row.has("work_arrangement.utc_offsets.latest")
+        {
+            let earliest = row.required::<String>("work_arrangement.utc_offsets.earliest");
+            let latest = row.required::<String>("work_arrangement.utc_offsets.latest");
+            earliest.zip(latest).map(|(earliest, latest)| UtcOffsetRangeInput { earliest, latest })
+        } else {
+            None
+        };
+        let work_arrangement = arrangement_type.and_then(|arrangement_type| {
+            let work_arrangement = WorkArrangement::try_from(WorkArrangementInput {
+                arrangement_type,
+                countries,
+                utc_offsets,
+            });
+            row.check("work_arrangement", work_arrangement)
+        });
+
+        let category_ids = row.list("category_ids").unwrap_or_default();
+
+        for category_id in &category_ids {
+            if !lookups.category_exists(category_id).await? {
+                row.error(
+                    Some("category_ids"),
+                    format!("Job category {} does not exist", category_id)
+                );
+            }
+        }
+
+        let draft = row.flag("draft").unwrap_or(false);
+        let publish_at = row.parse::<DateTime<Utc>>("publish_at");
+        let expires_at = row.parse::<DateTime<Utc>>("expires_at");
+
+        if draft && (publish_at.is_some() || expires_at.is_some()) {
+            row.error(Some("draft"), "Drafts are scheduled when they are published");
+        }
+
+        row.reject_unknown_columns();
+
+        let (
+            Some(job_title),
+            Some(employer_id),
+            Some(job_type),
+            Some(job_description),
+            Some(expected_hours_min),
+            Some(expected_hours_max),
+            Some(work_arrangement),
+        ) = (
+            job_title,
+            employer_id,
+            job_type,
+            job_description,
+            expected_hours_min,
+            expected_hours_max,
+            work_arrangement,
+        ) else {
+            return Ok(None);
+        };
+
+        if !row.errors.is_empty() {
+            return Ok(None);
+        }
+
+        let job_posting = JobPosting::new(
+            format!("job_posting-{}", Uuid::new_v4()),
+            job_title,
+            employer_id,
+            address,
+            pay,
+            job_type,
+            link_to_application,
+            job_description,
+            employee_responsibilities,
+            experience_requirements,
+            extra_info,
+            ExpectedHoursRange::new(expected_hours_min, expected_hours_max),
+            work_arrangement,
+            category_ids
+        );
+        let Some(mut job_posting) = row.check("job_type", job_posting) else {
+            return Ok(None);
+        };
+
+        job_posting.coordinates = coordinates;
+
+        if !draft {
+            let lifetime = Duration::seconds(self.config.lifecycle.default_posting_lifetime as i64);
+            let published = job_posting.publish(publish_at, expires_at, lifetime, now);
+
+            row.check("expires_at", published);
+        }
+
+        Ok(Some(job_posting))
+    }
+}
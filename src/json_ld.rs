@@ -0,0 +1,216 @@
+//! schema.org `JobPosting` structured data.
+//!
+//! Search engines such as Google for Jobs read a JSON-LD document embedded in
+//! the listing page. [`job_posting`] builds that document from a posting and
+//! its employer, and [`serialize`] writes it out for the `jsonLd` GraphQL field
+//! and the `/jobs/{id}.jsonld` route.
+
+use chrono::{ DateTime, SecondsFormat, Utc };
+use rust_decimal::{ prelude::ToPrimitive, Decimal };
+use serde_json::{ json, Map, Value };
+
+use crate::{
+    markup,
+    models::{
+        job_posting::JobTypeOption,
+        pay::CadenceOption,
+        work_arrangement::WorkArrangementType,
+    },
+    Employer,
+    JobPosting,
+    Pay,
+};
+
+/// Media type of the document served by the REST route.
+pub const CONTENT_TYPE: &str = "application/ld+json";
+
+/// The `employmentType` value for a job type. Google has no seasonal type, so
+/// seasonal work is temporary.
+fn employment_type(job_type: JobTypeOption) -> &'static str {
+    match job_type {
+        JobTypeOption::FullTime => "FULL_TIME",
+        JobTypeOption::PartTime => "PART_TIME",
+        JobTypeOption::Contract => "CONTRACTOR",
+        JobTypeOption::Temporary | JobTypeOption::Seasonal => "TEMPORARY",
+    }
+}
+
+/// The `unitText` of a salary paid per `cadence`.
+fn unit_text(cadence: CadenceOption) -> &'static str {
+    match cadence {
+        CadenceOption::Hour => "HOUR",
+        CadenceOption::Day => "DAY",
+        CadenceOption::Week => "WEEK",
+        CadenceOption::Month => "MONTH",
+        CadenceOption::Year => "YEAR",
+    }
+}
+
+/// An ISO 8601 date and time, to the second.
+fn date_time(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// A JSON number for `amount`; whole amounts become integers.
+fn number(amount: Decimal) -> Value {
+    let amount = amount.normalize();
+
+    if amount.scale() == 0 && let Some(whole) = amount.to_i64() {
+        return json!(whole);
+    }
+
+    amount.to_f64().map(|amount| json!(amount)).unwrap_or(Value::Null)
+}
+
+fn base_salary(pay: &Pay) -> Value {
+    let mut value = Map::new();
+
+    value.insert("@type".to_string(), json!("QuantitativeValue"));
+
+    match pay.max {
+        Some(max) if max != pay.min => {
+            value.insert("minValue".to_string(), number(pay.min));
+            value.insert("maxValue".to_string(), number(max));
+        }
+        _ => {
+            value.insert("value".to_string(), number(pay.min));
+        }
+    }
+
+    value.insert("unitText".to_string(), json!(unit_text(pay.cadence)));
+
+    json!({
+        "@type": "MonetaryAmount",
+        "currency": pay.currency,
+        "value": value,
+    })
+}
+
+fn job_location(job_posting: &JobPosting) -> Value {
+    let address = &job_posting.address;
+
+    let street_address = match &address.unit {
+        Some(unit) if !unit.trim().is_empty() => format!("{} {}", address.street, unit.trim()),
+        _ => address.street.clone(),
+    };
+
+    let mut place = json!({
+        "@type": "Place",
+        "address": {
+            "@type": "PostalAddress",
+            "streetAddress": street_address,
+            "addressLocality": address.city,
+            "addressRegion": address.state,
+            "postalCode": address.zip,
+            "addressCountry": address.country,
+        },
+    });
+
+    if let Some(coordinates) = &job_posting.coordinates {
+        place["geo"] = json!({
+            "@type": "GeoCoordinates",
+            "latitude": coordinates.latitude,
+            "longitude": coordinates.longitude,
+        });
+    }
+
+    place
+}
+
+fn hiring_organization(employer: &Employer) -> Value {
+    let mut organization = json!({
+        "@type": "Organization",
+        "name": employer.name,
+    });
+
+    if let Some(website_url) = &employer.website_url {
+        organization["sameAs"] = json!(website_url);
+    }
+    if let Some(logo_url) = &employer.logo_url {
+        organization["logo"] = json!(logo_url);
+    }
+
+    organization
+}
+
+/// The schema.org `JobPosting` document for `job_posting`, offered by
+/// `employer`.
+///
+/// Remote postings are marked `TELECOMMUTE`, with their allowed countries as
+/// the applicant location requirements, and leave out the office address;
+/// on-site and hybrid postings give the address as the job location.
+pub fn job_posting(job_posting: &JobPosting, employer: Option<&Employer>) -> Value {
+    let mut document = json!({
+        "@context": "https://schema.org/",
+        "@type": "JobPosting",
+        "title": job_posting.job_title,
+        "description": markup::job_posting_description(job_posting),
+        "identifier": {
+            "@type": "PropertyValue",
+            "value": job_posting.id,
+        },
+        "datePosted": date_time(&job_posting.publish_at.unwrap_or(job_posting.created_at)),
+        "employmentType": employment_type(job_posting.job_type),
+    });
+
+    if let Some(expires_at) = &job_posting.expires_at {
+        document["validThrough"] = json!(date_time(expires_at));
+    }
+
+    if let Some(employer) = employer {
+        document["hiringOrganization"] = hiring_organization(employer);
+        document["identifier"]["name"] = json!(employer.name);
+    }
+
+    let work_arrangement = &job_posting.work_arrangement;
+
+    if work_arrangement.arrangement_type == WorkArrangementType::Remote {
+        document["jobLocationType"] = json!("TELECOMMUTE");
+
+        if !work_arrangement.countries.is_empty() {
+            document["applicantLocationRequirements"] = work_arrangement.countries
+                .iter()
+                .map(|country| json!({ "@type": "Country", "name": country }))
+                .collect();
+        }
+    } else {
+        document["jobLocation"] = job_location(job_posting);
+    }
+
+    if let Some(pay) = &job_posting.pay {
+        document["baseSalary"] = base_salary(pay);
+    }
+
+    let hours = &job_posting.expected_hours;
+    document["workHours"] = if hours.min == hours.max {
+        json!(format!("{} hours per week", hours.min))
+    } else {
+        json!(format!("{}-{} hours per week", hours.min, hours.max))
+    };
+
+    // Postings without a link of their own take applications through
+    // `applyToJob`, right on the listing
+    document["directApply"] = json!(job_posting.link_to_application.is_none());
+
+    document
+}
+
+/// Serializes `document` so it can be embedded in a `<script>` element as is.
+///
+/// `<`, `>` and `&` only occur inside JSON strings, where their `\u` escapes
+/// mean the same, so posting text cannot close the element.
+pub fn serialize(document: &Value) -> String {
+    let json = document.to_string();
+    let mut escaped = String::with_capacity(json.len());
+
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -1,19 +1,44 @@
 // src/lib.rs
+pub mod aggregator;
+pub mod auth;
 pub mod error;
 pub mod models;
 pub mod schema;
 pub mod db;
+pub mod export;
+pub mod feed;
+pub mod geo;
+pub mod import;
 pub mod repository;
+pub mod json_ld;
+pub mod lifecycle;
+pub mod markup;
+pub mod pagination;
+pub mod search;
+pub mod storage;
+pub mod verification;
 pub mod config;
 pub mod context;
+pub mod csv;
+pub mod server;
 
-use async_graphql::{ EmptySubscription, SchemaBuilder };
+use std::sync::Arc;
+
+use async_graphql::{ dataloader::DataLoader, EmptySubscription, SchemaBuilder };
 // Re-exports
 pub use error::{ AppError, AppResult };
 pub use models::prelude::*;
 pub use repository::{ Repository, DynamoDbEntity };
+pub use pagination::{ Cursor, Page };
+pub use storage::{ StorageBackend, DynamoDbBackend, InMemoryBackend };
 
-use crate::schema::resolver::{ MutationRoot, QueryRoot };
+use crate::{
+    config::Config,
+    context::AppContext,
+    geo::{ SharedGeocoder, TableGeocoder },
+    schema::{ loader::EmployerLoader, resolver::{ MutationRoot, QueryRoot } },
+    verification::DomainVerifier,
+};
 
 // Type aliases
 pub type DbClient = aws_sdk_dynamodb::Client;
@@ -32,3 +57,34 @@ pub fn create_schema() -> SchemaBuilder<QueryRoot, MutationRoot, EmptySubscripti
         async_graphql::EmptySubscription
     )
 }
+
+/// Starts a schema with the repository, configuration, batched loaders and the
+/// configured geocoding table in the context data. Add a [`DomainVerifier`] and
+/// finish it, or use [`build_schema`] for the network-backed one. Adding a
+/// [`SharedGeocoder`] replaces the geocoder.
+pub fn schema_builder(
+    repository: Repository,
+    config: Config
+) -> SchemaBuilder<QueryRoot, MutationRoot, EmptySubscription> {
+    let employer_loader = DataLoader::new(EmployerLoader::new(repository.clone()), tokio::spawn);
+
+    // A broken table should not take the API down; postings just go unlocated
+    let geocoder = TableGeocoder::from_config(&config.geocoding).unwrap_or_else(|e| {
+        tracing::error!("Geocoding disabled: {}", e);
+        TableGeocoder::new()
+    });
+
+    create_schema()
+        .data(AppContext::new(repository.clone(), config.clone()))
+        .data(employer_loader)
+        .data::<SharedGeocoder>(Arc::new(geocoder))
+        .data(repository)
+        .data(config)
+}
+
+/// Builds the schema with everything resolvers expect in the context data.
+pub fn build_schema(repository: Repository, config: Config) -> GraphQLSchema {
+    let verifier = DomainVerifier::from_config(&config.verification);
+
+    schema_builder(repository, config).data(verifier).finish()
+}
@@ -0,0 +1,98 @@
+//! Job posting lifecycle sweep.
+//!
+//! Listings stop showing a posting the moment it expires, because expiry is
+//! checked at read time. The sweep records what has happened since: published
+//! postings past their expiry become `Expired`, and closed or expired postings
+//! become `Archived` once `archive_after` has passed. Archived postings carry
+//! a Time to Live attribute, so DynamoDB deletes them `purge_after` later.
+//!
+//! The sweep is idempotent. Run it on a schedule with `job_board_lambda sweep`
+//! or the `sweepJobPostings` mutation.
+
+use async_graphql::SimpleObject;
+use chrono::{ DateTime, Utc };
+use tracing::{ info, warn };
+
+use crate::{
+    config::LifecycleConfig,
+    models::{ job_posting::{ JobPosting, PostingStatus }, timestamp },
+    pagination::MAX_PAGE_SIZE,
+    AppError,
+    Repository,
+};
+
+/// What a sweep changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, SimpleObject)]
+pub struct SweepReport {
+    /// Postings that were checked
+    pub scanned: usize,
+    /// Postings moved to `EXPIRED`
+    pub expired: usize,
+    /// Postings moved to `ARCHIVED`
+    pub archived: usize,
+}
+
+/// Advances every unarchived posting to the state it should be in at `now`.
+///
+/// Each update is conditional on `updated_at`, so an edit that lands while
+/// the sweep runs wins; the posting is picked up again by the next sweep.
+pub async fn sweep_job_postings(
+    repo: &Repository,
+    config: &LifecycleConfig,
+    now: DateTime<Utc>
+) -> Result<SweepReport, AppError> {
+    let query = JobPosting::unarchived_query();
+    let mut report = SweepReport::default();
+    let mut after = None;
+
+    loop {
+        let page = repo.query::<JobPosting>(&query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for mut job_posting in page.into_entities() {
+            report.scanned += 1;
+
+            let previous_status = job_posting.status;
+            let previous_updated_at = timestamp::to_attribute_value(&job_posting.updated_at);
+
+            if !job_posting.advance_lifecycle(config, now) {
+                continue;
+            }
+
+            let status = job_posting.status;
+            let id = job_posting.id.clone();
+
+            match repo.update_if(job_posting, "updated_at", previous_updated_at).await {
+                Ok(_) => {}
+                // Modified concurrently; the next sweep sees the new version
+                Err(AppError::ValidationError(message)) => {
+                    warn!("Skipping job posting {}: {}", id, message);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+
+            if status != previous_status {
+                info!("Job posting {} moved from {} to {}", id, previous_status, status);
+
+                match status {
+                    PostingStatus::Expired => {
+                        report.expired += 1;
+                    }
+                    PostingStatus::Archived => {
+                        report.archived += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if after.is_none() {
+            break;
+        }
+    }
+
+    Ok(report)
+}
@@ -1,31 +1,24 @@
 use aws_config::Region;
-use axum::{ extract::Extension, http::{ HeaderValue, Method }, routing::get, Router };
+use chrono::Utc;
 use job_board_lambda::{
-    config::Config,
-    context::AppContext,
-    create_schema,
+    aggregator::IndeedExport,
+    auth::{ self, Principal },
+    config::{ Config, RunMode, StorageBackendKind },
+    build_schema,
     db,
+    export::{ Export, ExportFormat, ExportKind, ExportParams },
+    geo::{ self, TableGeocoder },
+    import::{ ImportFormat, Importer },
+    lifecycle,
+    models::user::Role,
+    search,
+    server,
+    AppError,
     DbClient,
-    GraphQLSchema,
+    Repository,
 };
-use tower::ServiceBuilder;
-use tower_http::{ compression::CompressionLayer, cors::{ Any, CorsLayer } };
-use async_graphql_axum::{ GraphQLRequest, GraphQLResponse };
 use tracing::{ info, error };
 
-// Handler for GraphQL requests
-async fn graphql_handler(
-    Extension(schema): Extension<GraphQLSchema>,
-    req: GraphQLRequest
-) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
-}
-
-// Handler for GraphQL playground
-async fn graphql_playground() -> impl axum::response::IntoResponse {
-    axum::response::Html(async_graphql::http::GraphiQLSource::build().endpoint("/graphql").finish())
-}
-
 #[tokio::main]
 async fn main() {
     // Initialize tracing
@@ -48,109 +41,311 @@ async fn main() {
 
     info!("Configuration loaded: {:?}", config);
 
-    // Create database client
-    let db_client = match setup_database_client(&config).await {
-        Ok(client) => client,
-        Err(e) => {
-            error!("Fatal error creating database client: {}", e);
-            std::process::exit(1);
+    // Create the repository over the configured storage backend
+    let repository = match config.database.backend {
+        StorageBackendKind::Memory => {
+            info!("Using in-memory storage backend; data will not persist");
+            Repository::in_memory()
         }
-    };
+        StorageBackendKind::DynamoDb => {
+            // Create database client
+            let db_client = match setup_database_client(&config).await {
+                Ok(client) => client,
+                Err(e) => {
+                    error!("Fatal error creating database client: {}", e);
+                    std::process::exit(1);
+                }
+            };
 
-    // Ensure all tables exist
-    if let Err(e) = db::init::ensure_tables_exist(&db_client).await {
-        error!("Fatal error ensuring tables exist: {}", e);
-        std::process::exit(1);
-    }
+            // Ensure all tables exist
+            if let Err(e) = db::init::ensure_tables_exist(&db_client).await {
+                error!("Fatal error ensuring tables exist: {}", e);
+                std::process::exit(1);
+            }
 
-    info!("Database tables verified/created successfully");
+            info!("Database tables verified/created successfully");
 
-    // Create application context
-    let app_context = AppContext::new(db_client.clone(), config.clone());
+            // `job_board_lambda migrate` brings existing tables forward and exits
+            if std::env::args().nth(1).as_deref() == Some("migrate") {
+                if let Err(e) = db::migrations::run_all(&db_client).await {
+                    error!("Fatal error running migrations: {}", e);
+                    std::process::exit(1);
+                }
 
-    // Create GraphQL schema with all necessary data
-    let schema = create_schema()
-        .data(db_client.clone()) // For backward compatibility with existing resolvers
-        .data(config.clone())
-        .data(app_context)
-        .finish();
+                info!("Migrations completed successfully");
+                return;
+            }
 
-    info!("GraphQL schema created successfully");
+            Repository::new(db_client)
+        }
+    };
 
-    // Configure CORS based on environment
-    let cors = if config.graphql.playground {
-        // Development mode - allow all origins
-        CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-            .allow_headers(Any)
-    } else {
-        // Production mode - restrict origins (you'd configure this based on your needs)
-        let allowed_origins: Vec<String> = config
-            .clone()
-            .allow_origins.split(",")
-            .map(|s| s.to_string())
-            .collect();
-
-        let mut cors_layer = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
-            .allow_headers(Any);
-
-        for origin in &allowed_origins {
-            let header_value = origin
-                .parse::<HeaderValue>()
-                .map_err(|e| {
-                    error!("Could not configure cors layer: {:?}", e);
-                    std::process::exit(1);
-                })
-                .unwrap();
-            cors_layer = cors_layer.allow_origin(header_value);
+    // `job_board_lambda sweep` advances posting lifecycles once and exits; run
+    // it on a schedule
+    if std::env::args().nth(1).as_deref() == Some("sweep") {
+        match lifecycle::sweep_job_postings(&repository, &config.lifecycle, Utc::now()).await {
+            Ok(report) => {
+                info!(
+                    "Sweep completed: {} scanned, {} expired, {} archived",
+                    report.scanned,
+                    report.expired,
+                    report.archived
+                );
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error sweeping job postings: {}", e);
+                std::process::exit(1);
+            }
         }
+    }
 
-        cors_layer
-    };
+    // `job_board_lambda geocode` locates postings that have no coordinates yet,
+    // using the configured geocoding table, and exits
+    if std::env::args().nth(1).as_deref() == Some("geocode") {
+        let result = match TableGeocoder::from_config(&config.geocoding) {
+            Ok(geocoder) => geo::geocode_job_postings(&repository, &geocoder).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(located) => {
+                info!("Geocoding completed: {} job postings located", located);
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error geocoding job postings: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    // Build router
-    let mut router = Router::new();
+    // `job_board_lambda reindex` rebuilds the search index from the postings and
+    // exits
+    if std::env::args().nth(1).as_deref() == Some("reindex") {
+        match search::reindex_job_postings(&repository).await {
+            Ok(indexed) => {
+                info!("Reindex completed: {} job postings indexed", indexed);
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error reindexing job postings: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    // Add GraphQL endpoint
-    router = router.route("/graphql", get(graphql_playground).post(graphql_handler));
+    // `job_board_lambda create-admin EMAIL` makes the account registered under
+    // EMAIL a site admin and exits
+    if std::env::args().nth(1).as_deref() == Some("create-admin") {
+        let Some(email) = std::env::args().nth(2) else {
+            error!("Usage: job_board_lambda create-admin EMAIL");
+            std::process::exit(2);
+        };
+
+        match auth::grant_site_admin(&repository, &email).await {
+            Ok(user) => {
+                info!("User {} is now a site admin", user.id);
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error creating site admin: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    // Add health check endpoint
-    router = router.route("/health", get(health_check));
+    // `job_board_lambda export-indeed FILE` writes every live posting as
+    // aggregator XML to FILE and exits. Logs go to standard output, so the
+    // export cannot.
+    if std::env::args().nth(1).as_deref() == Some("export-indeed") {
+        let Some(path) = std::env::args().nth(2) else {
+            error!("Usage: job_board_lambda export-indeed FILE");
+            std::process::exit(2);
+        };
+
+        let export = IndeedExport::new(repository.clone(), config.feeds.clone(), Utc::now());
+
+        let result = match tokio::fs::File::create(&path).await {
+            Ok(mut file) => export.write_to(&mut file).await,
+            Err(e) => Err(AppError::InternalServerError(format!("Failed to create {}: {}", path, e))),
+        };
+
+        match result {
+            Ok(written) => {
+                info!("Export completed: {} bytes written to {}", written, path);
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error exporting job postings: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    // Add middleware layers
-    let app = router.layer(
-        ServiceBuilder::new()
-            .layer(CompressionLayer::new().gzip(true).deflate(true).br(true))
-            .layer(Extension(db_client))
-            .layer(Extension(schema))
-            .layer(Extension(config.clone()))
-            .layer(cors)
-    );
+    // `job_board_lambda export FILE [--kind=job_applications] [--employer-id=ID]
+    // [--from=TIME] [--to=TIME]` writes postings or applications to a .csv or
+    // .ndjson file and exits. Times are RFC 3339.
+    if std::env::args().nth(1).as_deref() == Some("export") {
+        let usage = || -> ! {
+            error!(
+                "Usage: job_board_lambda export FILE [--kind=job_postings|job_applications] [--employer-id=ID] [--from=TIME] [--to=TIME]"
+            );
+            std::process::exit(2);
+        };
+
+        let Some(path) = std::env::args().nth(2) else {
+            usage();
+        };
+
+        let Some(format) = ExportFormat::from_path(&path) else {
+            error!("Cannot export to {}: expected a .csv or .ndjson file", path);
+            std::process::exit(2);
+        };
+
+        let mut params = ExportParams { format, ..ExportParams::default() };
+
+        for arg in std::env::args().skip(3) {
+            let Some((name, value)) = arg.split_once('=') else {
+                usage();
+            };
+
+            match name {
+                "--kind" => {
+                    params.kind = ExportKind::from_string(value).unwrap_or_else(|| usage());
+                }
+                "--employer-id" => {
+                    params.employer_id = Some(value.to_string());
+                }
+                "--from" => {
+                    params.from = Some(value.parse().unwrap_or_else(|_| usage()));
+                }
+                "--to" => {
+                    params.to = Some(value.parse().unwrap_or_else(|_| usage()));
+                }
+                _ => usage(),
+            }
+        }
 
-    // Determine port from environment or use default
-    let port = std::env
-        ::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse::<u16>()
-        .unwrap_or(3000);
+        let result = match Export::start(repository.clone(), params) {
+            Ok(export) =>
+                match tokio::fs::File::create(&path).await {
+                    Ok(mut file) => export.write_to(&mut file).await,
+                    Err(e) => Err(AppError::InternalServerError(format!("Failed to create {}: {}", path, e))),
+                }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(written) => {
+                info!("Export completed: {} bytes written to {}", written, path);
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error exporting: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    let bind_address = format!("0.0.0.0:{}", port);
+    // `job_board_lambda import-postings FILE [EMPLOYER_ID] [--dry-run]` imports
+    // the postings in a .csv or .ndjson file as a site admin and exits. Rows
+    // without an employer_id belong to EMPLOYER_ID.
+    if std::env::args().nth(1).as_deref() == Some("import-postings") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        let dry_run = args.iter().any(|arg| arg == "--dry-run");
+        let mut positional = args.iter().filter(|arg| *arg != "--dry-run");
+
+        let (Some(path), employer_id) = (positional.next(), positional.next()) else {
+            error!("Usage: job_board_lambda import-postings FILE [EMPLOYER_ID] [--dry-run]");
+            std::process::exit(2);
+        };
+
+        let Some(format) = ImportFormat::from_path(path) else {
+            error!("Cannot import {}: expected a .csv or .ndjson file", path);
+            std::process::exit(2);
+        };
+
+        let principal = Principal {
+            subject: "cli".to_string(),
+            email: String::new(),
+            role: Role::SiteAdmin,
+            employer_id: None,
+        };
+        let geocoder = TableGeocoder::from_config(&config.geocoding).unwrap_or_else(|e| {
+            error!("Geocoding disabled: {}", e);
+            TableGeocoder::new()
+        });
+        let importer = Importer::new(&repository, &config, &geocoder, &principal);
+
+        let result = match tokio::fs::read_to_string(path).await {
+            Ok(data) =>
+                importer.import(format, &data, employer_id.map(String::as_str), dry_run, Utc::now()).await,
+            Err(e) => Err(AppError::InternalServerError(format!("Failed to read {}: {}", path, e))),
+        };
+
+        match result {
+            Ok(report) => {
+                for row_error in &report.errors {
+                    error!(
+                        "Row {}{}: {}",
+                        row_error.row,
+                        row_error.field.as_ref().map(|field| format!(" ({})", field)).unwrap_or_default(),
+                        row_error.message
+                    );
+                }
+
+                info!(
+                    "Import {}: {} rows, {} valid, {} imported",
+                    if report.dry_run { "checked" } else { "completed" },
+                    report.rows,
+                    report.valid_rows,
+                    report.imported
+                );
+
+                if !report.errors.is_empty() {
+                    std::process::exit(1);
+                }
+                return;
+            }
+            Err(e) => {
+                error!("Fatal error importing job postings: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Create GraphQL schema with all necessary data
+    let schema = build_schema(repository.clone(), config.clone());
+
+    info!("GraphQL schema created successfully");
 
-    // Start server
-    let listener = match tokio::net::TcpListener::bind(&bind_address).await {
-        Ok(l) => l,
+    let router = match server::build_router(schema, repository, &config) {
+        Ok(router) => router,
         Err(e) => {
-            error!("Fatal error binding to {}: {}", bind_address, e);
+            error!("Fatal error building router: {}", e);
             std::process::exit(1);
         }
     };
 
-    info!("Server running on http://localhost:{}", port);
-    info!("GraphQL Playground available at http://localhost:{}/graphql", port);
+    let result = match config.run_mode {
+        RunMode::Lambda => {
+            info!("Serving requests from the Lambda runtime API");
+            server::lambda::run(router).await
+        }
+        RunMode::Http => {
+            // Determine port from environment or use default
+            let port = std::env
+                ::var("PORT")
+                .unwrap_or_else(|_| "3000".to_string())
+                .parse::<u16>()
+                .unwrap_or(3000);
+
+            server::serve_http(router, port).await
+        }
+    };
 
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = result {
         error!("Fatal error running server: {}", e);
         std::process::exit(1);
     }
@@ -161,11 +356,11 @@ async fn setup_database_client(config: &Config) -> Result<DbClient, Box<dyn std:
     if let Some(endpoint) = &config.database.endpoint {
         // Local DynamoDB setup
         info!("Setting up local DynamoDB client with endpoint: {}", endpoint);
-        setup_local_client_with_config(&config).await
+        setup_local_client_with_config(config).await
     } else {
         // AWS DynamoDB setup
         info!("Setting up AWS DynamoDB client for region: {}", config.database.region);
-        setup_aws_client_with_config(&config).await
+        setup_aws_client_with_config(config).await
     }
 }
 
@@ -233,8 +428,3 @@ async fn setup_aws_client_with_config(
     let aws_config = aws_config_builder.load().await;
     Ok(aws_sdk_dynamodb::Client::new(&aws_config))
 }
-
-// Health check endpoint
-async fn health_check() -> &'static str {
-    "OK"
-}
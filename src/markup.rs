@@ -0,0 +1,93 @@
+//! Escaping for text placed in HTML and XML documents, and the HTML
+//! description of a posting that structured data and aggregator exports share.
+
+use crate::JobPosting;
+
+/// Whether XML 1.0 allows `c` in a document at all. Of the C0 control
+/// characters only tab, line feed and carriage return are allowed, and not
+/// even a character reference can stand in for the others.
+fn is_xml_char(c: char) -> bool {
+    !matches!(c, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}')
+}
+
+/// Appends `text` to `out` with the characters that are special in HTML and
+/// XML, in text and in quoted attributes alike, replaced by entities.
+/// Characters XML does not allow are dropped.
+pub fn escape_into(text: &str, out: &mut String) {
+    for c in text.chars().filter(|c| is_xml_char(*c)) {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// `text` escaped for HTML or XML.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    escape_into(text, &mut out);
+
+    out
+}
+
+/// Appends `text` to `out` as an XML CDATA section. A `]]>` in the text would
+/// end the section early, so it is split across two sections. Characters XML
+/// does not allow are dropped.
+pub fn cdata_into(text: &str, out: &mut String) {
+    let text: String = text.chars().filter(|c| is_xml_char(*c)).collect();
+
+    out.push_str("<![CDATA[");
+    out.push_str(&text.replace("]]>", "]]]]><![CDATA[>"));
+    out.push_str("]]>");
+}
+
+fn push_list(html: &mut String, heading: &str, items: Option<&Vec<String>>) {
+    let Some(items) = items.filter(|items| !items.is_empty()) else {
+        return;
+    };
+
+    html.push_str("<h3>");
+    html.push_str(heading);
+    html.push_str("</h3><ul>");
+
+    for item in items {
+        html.push_str("<li>");
+        escape_into(item, html);
+        html.push_str("</li>");
+    }
+
+    html.push_str("</ul>");
+}
+
+/// A posting as an HTML fragment: the job description, one paragraph per
+/// line, followed by the responsibilities, requirements and extra information.
+/// All of the posting's text is escaped, so markup typed into it shows as text.
+pub fn job_posting_description(job_posting: &JobPosting) -> String {
+    let mut html = String::new();
+
+    let paragraphs = job_posting.job_description
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    for paragraph in paragraphs {
+        html.push_str("<p>");
+        escape_into(paragraph, &mut html);
+        html.push_str("</p>");
+    }
+
+    push_list(&mut html, "Responsibilities", job_posting.employee_responsibilities.as_ref());
+    push_list(&mut html, "Experience", job_posting.experience_requirements.as_ref());
+
+    if let Some(extra_info) = job_posting.extra_info.as_deref().filter(|s| !s.trim().is_empty()) {
+        html.push_str("<p>");
+        escape_into(extra_info.trim(), &mut html);
+        html.push_str("</p>");
+    }
+
+    html
+}
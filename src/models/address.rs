@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use async_graphql::InputObject;
+use async_graphql::{ InputObject, MaybeUndefined };
 use aws_sdk_dynamodb::types::AttributeValue;
 use regex::Regex;
 use serde::{ Deserialize, Serialize };
@@ -27,6 +27,18 @@ pub struct AddressInput {
     pub zip: String,
 }
 
+/// Partial update for an address. Omitted fields are left untouched, and
+/// `unit` can be cleared with an explicit `null`.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct AddressPatchInput {
+    pub street: Option<String>,
+    pub unit: MaybeUndefined<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+    pub zip: Option<String>,
+}
+
 impl From<AddressInput> for Address {
     fn from(input: AddressInput) -> Self {
         Self {
@@ -72,16 +84,35 @@ impl Address {
         }
     }
 
-    pub(crate) fn validate(&self) -> Result<(), String> {
+    /// Merges the supplied fields of `patch` into this address. The result is
+    /// not validated.
+    pub fn apply_patch(&mut self, patch: AddressPatchInput) {
+        if let Some(street) = patch.street {
+            self.street = street;
+        }
+        patch.unit.update_to(&mut self.unit);
+        if let Some(city) = patch.city {
+            self.city = city;
+        }
+        if let Some(state) = patch.state {
+            self.state = state;
+        }
+        if let Some(country) = patch.country {
+            self.country = country;
+        }
+        if let Some(zip) = patch.zip {
+            self.zip = zip;
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
         let po_box_regex = Regex::new(
             r"(?i)^P\.?O\.?\s*Box\s+\d+|Post\s*Office\s*Box\s+\d+|Postal\s*Box\s+\d+"
-        ).map_err(|e| {
-            return AppError::InternalServerError(e.to_string()).to_string();
-        })?;
+        ).map_err(|e| AppError::InternalServerError(e.to_string()).to_string())?;
 
-        let street_addr_regex = Regex::new(r"^\d+\s+\w+.*").map_err(|e| {
-            return AppError::InternalServerError(e.to_string()).to_string();
-        })?;
+        let street_addr_regex = Regex::new(r"^\d+\s+\w+.*").map_err(|e|
+            AppError::InternalServerError(e.to_string()).to_string()
+        )?;
 
         if self.street.trim().is_empty() {
             return Err("Street field cannot be empty".to_string());
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use async_graphql::InputObject;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{ Deserialize, Serialize };
+
+use crate::AppError;
+
+/// A point on the earth in decimal degrees (WGS 84).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, InputObject)]
+pub struct CoordinatesInput {
+    /// Degrees north of the equator, from -90 to 90
+    pub latitude: f64,
+    /// Degrees east of Greenwich, from -180 to 180
+    pub longitude: f64,
+}
+
+impl TryFrom<CoordinatesInput> for Coordinates {
+    type Error = AppError;
+
+    fn try_from(input: CoordinatesInput) -> Result<Self, Self::Error> {
+        Self::new(input.latitude, input.longitude)
+    }
+}
+
+impl Coordinates {
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, AppError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Latitude must be between -90 and 90, not {}", latitude)
+                )
+            );
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Longitude must be between -180 and 180, not {}", longitude)
+                )
+            );
+        }
+
+        Ok(Self { latitude, longitude })
+    }
+
+    pub(crate) fn to_attribute_value(self) -> AttributeValue {
+        let mut item = HashMap::new();
+
+        item.insert("latitude".to_string(), AttributeValue::N(self.latitude.to_string()));
+        item.insert("longitude".to_string(), AttributeValue::N(self.longitude.to_string()));
+
+        AttributeValue::M(item)
+    }
+
+    pub(crate) fn from_attribute_value(av: &AttributeValue) -> Option<Self> {
+        if let AttributeValue::M(item) = av {
+            let latitude = item.get("latitude")?.as_n().ok()?.parse::<f64>().ok()?;
+            let longitude = item.get("longitude")?.as_n().ok()?.parse::<f64>().ok()?;
+
+            Self::new(latitude, longitude).ok()
+        } else {
+            None
+        }
+    }
+}
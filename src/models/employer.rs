@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use async_graphql::{ InputObject, MaybeUndefined };
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::employer_tables::EMPLOYER_NAME_INDEX,
+    models::timestamp,
+    repository::ItemQuery,
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+const MAX_EMPLOYER_NAME_LENGTH: usize = 200;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Employer {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub website_url: Option<String>,
+    pub logo_url: Option<String>,
+    // Domain the employer claims, e.g. "acme.example"
+    pub domain: Option<String>,
+    // Set once ownership of `domain` has been proven; cleared when it changes
+    pub domain_verified_at: Option<DateTime<Utc>>,
+    // Outstanding challenge token for proving ownership of `domain`
+    pub domain_challenge: Option<String>,
+    pub domain_challenge_expires_at: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Employer {
+    pub fn new(
+        id: String,
+        name: String,
+        description: Option<String>,
+        website_url: Option<String>,
+        logo_url: Option<String>,
+        domain: Option<String>
+    ) -> Result<Self, AppError> {
+        let now = Utc::now();
+
+        let employer = Self {
+            id,
+            name: name.trim().to_string(),
+            description,
+            website_url,
+            logo_url,
+            domain: domain.map(|domain| normalize_domain(&domain)),
+            domain_verified_at: None,
+            domain_challenge: None,
+            domain_challenge_expires_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        employer.validate().map_err(AppError::ValidationError)?;
+
+        Ok(employer)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Employer name cannot be empty".to_string());
+        }
+        if self.name.chars().count() > MAX_EMPLOYER_NAME_LENGTH {
+            return Err(
+                format!("Employer name cannot exceed {} characters", MAX_EMPLOYER_NAME_LENGTH)
+            );
+        }
+
+        for (field, url) in [("Website URL", &self.website_url), ("Logo URL", &self.logo_url)] {
+            if url.as_ref().is_some_and(|url| !is_http_url(url)) {
+                return Err(format!("{} must be an http(s) URL", field));
+            }
+        }
+
+        if let Some(domain) = &self.domain {
+            let labels_valid = domain.split('.').all(|label| {
+                !label.is_empty() &&
+                    !label.starts_with('-') &&
+                    !label.ends_with('-') &&
+                    label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+            // A numeric last label would make this an IP address
+            let tld_valid = domain
+                .rsplit('.')
+                .next()
+                .is_some_and(|tld| tld.chars().any(|c| c.is_ascii_alphabetic()));
+
+            let valid = domain.contains('.') && labels_valid && tld_valid;
+
+            if !valid {
+                return Err(format!("{} is not a valid domain name", domain));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_domain_verified(&self) -> bool {
+        self.domain.is_some() && self.domain_verified_at.is_some()
+    }
+
+    /// The domain to verify: the claimed `domain`, or else the host of
+    /// `website_url`.
+    pub fn verification_domain(&self) -> Option<String> {
+        self.domain.clone().or_else(|| self.website_url.as_deref().map(normalize_domain))
+    }
+
+    /// Starts a new domain challenge, replacing any outstanding one, and
+    /// returns when it expires.
+    pub fn issue_domain_challenge(
+        &mut self,
+        domain: String,
+        token: String,
+        lifetime: Duration
+    ) -> DateTime<Utc> {
+        let now = Utc::now();
+        let expires_at = now + lifetime;
+
+        if self.domain.as_deref() != Some(domain.as_str()) {
+            self.domain = Some(domain);
+            self.domain_verified_at = None;
+        }
+
+        self.domain_challenge = Some(token);
+        self.domain_challenge_expires_at = Some(expires_at);
+        self.updated_at = now;
+
+        expires_at
+    }
+
+    /// The outstanding challenge token, unless it has expired.
+    pub fn active_domain_challenge(&self) -> Option<&str> {
+        match self.domain_challenge_expires_at {
+            Some(expires_at) if expires_at > Utc::now() => self.domain_challenge.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Records that the challenge was met and retires it.
+    pub fn mark_domain_verified(&mut self) {
+        let now = Utc::now();
+
+        self.domain_verified_at = Some(now);
+        self.domain_challenge = None;
+        self.domain_challenge_expires_at = None;
+        self.updated_at = now;
+    }
+
+    /// Employers with exactly this name, through `EmployerNameIndex`.
+    pub fn by_name_query(name: &str) -> ItemQuery {
+        ItemQuery::index(
+            EMPLOYER_NAME_INDEX,
+            "employer_name",
+            AttributeValue::S(name.trim().to_string())
+        )
+    }
+
+    pub async fn find_by_name(repo: &Repository, name: &str) -> Result<Option<Self>, AppError> {
+        let page = repo.query::<Self>(&Self::by_name_query(name), 1, None).await?;
+
+        Ok(page.into_entities().into_iter().next())
+    }
+
+    /// Fails with a `ValidationError` if `id` does not refer to an existing
+    /// employer.
+    pub async fn ensure_exists(repo: &Repository, id: &str) -> Result<(), AppError> {
+        if repo.get::<Self>(id.to_string()).await?.is_none() {
+            return Err(AppError::ValidationError(format!("Employer {} does not exist", id)));
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Lowercases a domain and strips any scheme, port, path or trailing dot.
+pub(crate) fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim();
+    let domain = domain.split_once("://").map_or(domain, |(_, rest)| rest);
+    let domain = domain.split(['/', ':']).next().unwrap_or_default();
+
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Partial update for an employer profile.
+///
+/// Omitted fields are left untouched; nullable fields can be cleared with an
+/// explicit `null`. Changing the domain resets its verification.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct EmployerPatchInput {
+    pub name: Option<String>,
+    pub description: MaybeUndefined<String>,
+    pub website_url: MaybeUndefined<String>,
+    pub logo_url: MaybeUndefined<String>,
+    pub domain: MaybeUndefined<String>,
+}
+
+impl Employer {
+    /// Merges the supplied fields of `patch` into this employer and bumps
+    /// `updated_at`.
+    pub fn apply_patch(&mut self, patch: EmployerPatchInput) -> Result<(), AppError> {
+        if let Some(name) = patch.name {
+            self.name = name.trim().to_string();
+        }
+
+        patch.description.update_to(&mut self.description);
+        patch.website_url.update_to(&mut self.website_url);
+        patch.logo_url.update_to(&mut self.logo_url);
+
+        let previous_domain = self.domain.clone();
+        patch.domain.map_value(|domain| normalize_domain(&domain)).update_to(&mut self.domain);
+
+        if self.domain != previous_domain {
+            self.domain_verified_at = None;
+            self.domain_challenge = None;
+            self.domain_challenge_expires_at = None;
+        }
+
+        self.validate().map_err(AppError::ValidationError)?;
+        self.updated_at = Utc::now();
+
+        Ok(())
+    }
+}
+
+impl DynamoDbEntity for Employer {
+    fn table_name() -> &'static str {
+        "Employers"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let string = |name: &str| {
+            item.get(name)
+                .and_then(|v| v.as_s().ok())
+                .map(|s| s.to_string())
+        };
+
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let name = item.get("employer_name")?.as_s().ok()?.to_string();
+
+        let domain_verified_at = item
+            .get("domain_verified_at")
+            .and_then(timestamp::from_attribute_value);
+
+        let domain_challenge_expires_at = item
+            .get("domain_challenge_expires_at")
+            .and_then(timestamp::from_attribute_value);
+
+        let created_at = item
+            .get("created_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        Some(Self {
+            id,
+            name,
+            description: string("description"),
+            website_url: string("website_url"),
+            logo_url: string("logo_url"),
+            domain: string("domain"),
+            domain_verified_at,
+            domain_challenge: string("domain_challenge"),
+            domain_challenge_expires_at,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        // EmployerNameIndex is keyed on employer_name
+        item.insert("employer_name".to_string(), AttributeValue::S(self.name.clone()));
+
+        for (name, value) in [
+            ("description", &self.description),
+            ("website_url", &self.website_url),
+            ("logo_url", &self.logo_url),
+            ("domain", &self.domain),
+            ("domain_challenge", &self.domain_challenge),
+        ] {
+            if let Some(value) = value {
+                item.insert(name.to_string(), AttributeValue::S(value.clone()));
+            }
+        }
+
+        for (name, value) in [
+            ("domain_verified_at", &self.domain_verified_at),
+            ("domain_challenge_expires_at", &self.domain_challenge_expires_at),
+        ] {
+            if let Some(value) = value {
+                item.insert(name.to_string(), timestamp::to_attribute_value(value));
+            }
+        }
+
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        item
+    }
+}
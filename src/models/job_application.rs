@@ -0,0 +1,559 @@
+use std::{ collections::HashMap, fmt };
+
+use async_graphql::Enum;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::job_posting_tables::{ APPLICANT_INDEX, JOB_POSTING_INDEX, STATUS_INDEX },
+    models::timestamp,
+    repository::ItemQuery,
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+const MAX_APPLICANT_NAME_LENGTH: usize = 200;
+const MAX_COVER_NOTE_LENGTH: usize = 5000;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationStatus {
+    Submitted,
+    Reviewed,
+    Interviewing,
+    Offered,
+    Rejected,
+    Withdrawn,
+}
+
+impl fmt::Display for ApplicationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ApplicationStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ApplicationStatus::Submitted => "SUBMITTED",
+            ApplicationStatus::Reviewed => "REVIEWED",
+            ApplicationStatus::Interviewing => "INTERVIEWING",
+            ApplicationStatus::Offered => "OFFERED",
+            ApplicationStatus::Rejected => "REJECTED",
+            ApplicationStatus::Withdrawn => "WITHDRAWN",
+        }
+    }
+
+    /// Whether an application may move from `self` to `next`.
+    ///
+    /// Applications advance submitted → reviewed → interviewing → offered. They
+    /// can be rejected by the employer or withdrawn by the applicant at any open
+    /// stage. Rejected and withdrawn are final.
+    pub fn can_transition_to(self, next: ApplicationStatus) -> bool {
+        use ApplicationStatus::*;
+
+        matches!(
+            (self, next),
+            (Submitted, Reviewed) |
+                (Reviewed, Interviewing) |
+                (Interviewing, Offered) |
+                (Submitted | Reviewed | Interviewing | Offered, Rejected | Withdrawn)
+        )
+    }
+
+    pub(crate) fn from_string(s: &str) -> Result<ApplicationStatus, AppError> {
+        match s {
+            "SUBMITTED" => Ok(Self::Submitted),
+            "REVIEWED" => Ok(Self::Reviewed),
+            "INTERVIEWING" => Ok(Self::Interviewing),
+            "OFFERED" => Ok(Self::Offered),
+            "REJECTED" => Ok(Self::Rejected),
+            "WITHDRAWN" => Ok(Self::Withdrawn),
+            _ =>
+                Err(
+                    AppError::DatabaseError(
+                        "Cannot perform from_string on ApplicationStatus input".to_string()
+                    )
+                ),
+        }
+    }
+}
+
+/// One entry in an application's status history.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatusChange {
+    // None for the initial submission
+    pub from: Option<ApplicationStatus>,
+    pub to: ApplicationStatus,
+    pub changed_by: String,
+    pub changed_at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+impl StatusChange {
+    fn to_attribute_value(&self) -> AttributeValue {
+        let mut item = HashMap::new();
+
+        if let Some(from) = &self.from {
+            item.insert("from".to_string(), AttributeValue::S(from.to_string()));
+        }
+
+        item.insert("to".to_string(), AttributeValue::S(self.to.to_string()));
+        item.insert("changed_by".to_string(), AttributeValue::S(self.changed_by.clone()));
+        item.insert("changed_at".to_string(), timestamp::to_attribute_value(&self.changed_at));
+
+        if let Some(note) = &self.note {
+            item.insert("note".to_string(), AttributeValue::S(note.clone()));
+        }
+
+        AttributeValue::M(item)
+    }
+
+    fn from_attribute_value(av: &AttributeValue) -> Option<Self> {
+        if let AttributeValue::M(item) = av {
+            let from = item
+                .get("from")
+                .and_then(|v| v.as_s().ok())
+                .and_then(|s| ApplicationStatus::from_string(s).ok());
+            let to = ApplicationStatus::from_string(item.get("to")?.as_s().ok()?).ok()?;
+            let changed_by = item.get("changed_by")?.as_s().ok()?.to_string();
+            let changed_at = item.get("changed_at").and_then(timestamp::from_attribute_value)?;
+            let note = item
+                .get("note")
+                .and_then(|v| v.as_s().ok())
+                .map(|s| s.to_string());
+
+            Some(Self {
+                from,
+                to,
+                changed_by,
+                changed_at,
+                note,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobApplication {
+    pub id: String,
+    // The job posting applied to
+    pub job_posting_id: String,
+    pub applicant_name: String,
+    // Stored lowercased so ApplicantIndex lookups are case-insensitive
+    pub applicant_email: String,
+    // The account that applied; None for anonymous applications
+    pub applicant_id: Option<String>,
+    pub cover_note: Option<String>,
+    pub status: ApplicationStatus,
+    // Append-only; the last entry always matches `status`
+    pub status_history: Vec<StatusChange>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobApplication {
+    pub fn new(
+        id: String,
+        job_posting_id: String,
+        applicant_name: String,
+        applicant_email: String,
+        applicant_id: Option<String>,
+        cover_note: Option<String>
+    ) -> Result<Self, AppError> {
+        let now = Utc::now();
+        let applicant_email = normalize_email(&applicant_email);
+
+        let application = Self {
+            id,
+            job_posting_id,
+            applicant_name: applicant_name.trim().to_string(),
+            status_history: vec![StatusChange {
+                from: None,
+                to: ApplicationStatus::Submitted,
+                changed_by: applicant_id.clone().unwrap_or_else(|| applicant_email.clone()),
+                changed_at: now,
+                note: None,
+            }],
+            applicant_email,
+            applicant_id,
+            cover_note: cover_note
+                .map(|note| note.trim().to_string())
+                .filter(|note| !note.is_empty()),
+            status: ApplicationStatus::Submitted,
+            created_at: now,
+            updated_at: now,
+        };
+
+        application.validate().map_err(AppError::ValidationError)?;
+
+        Ok(application)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.applicant_name.is_empty() {
+            return Err("Applicant name cannot be empty".to_string());
+        }
+        if self.applicant_name.chars().count() > MAX_APPLICANT_NAME_LENGTH {
+            return Err(
+                format!("Applicant name cannot exceed {} characters", MAX_APPLICANT_NAME_LENGTH)
+            );
+        }
+
+        validate_email(&self.applicant_email)?;
+
+        if
+            self.cover_note
+                .as_ref()
+                .is_some_and(|note| note.chars().count() > MAX_COVER_NOTE_LENGTH)
+        {
+            return Err(format!("Cover note cannot exceed {} characters", MAX_COVER_NOTE_LENGTH));
+        }
+
+        Ok(())
+    }
+
+    /// Moves the application to `next`, recording who made the change.
+    ///
+    /// Illegal transitions are rejected with a `ValidationError`. The caller
+    /// should persist the result with [`Repository::update_if`] conditioned on
+    /// the previous status, so concurrent transitions cannot interleave.
+    ///
+    /// [`Repository::update_if`]: crate::Repository::update_if
+    pub fn transition(
+        &mut self,
+        next: ApplicationStatus,
+        changed_by: String,
+        note: Option<String>
+    ) -> Result<(), AppError> {
+        if !self.status.can_transition_to(next) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Cannot move application from {} to {}", self.status, next)
+                )
+            );
+        }
+
+        let now = Utc::now();
+
+        self.status_history.push(StatusChange {
+            from: Some(self.status),
+            to: next,
+            changed_by,
+            changed_at: now,
+            note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        });
+        self.status = next;
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    /// Applications submitted for a job posting, through `JobPostingIndex`.
+    pub fn by_job_posting_query(job_posting_id: &str) -> ItemQuery {
+        ItemQuery::index(
+            JOB_POSTING_INDEX,
+            "job_posting_id",
+            AttributeValue::S(job_posting_id.to_string())
+        )
+    }
+
+    /// Deletes every application submitted for a job posting, returning how
+    /// many there were.
+    pub async fn delete_for_posting(
+        repo: &Repository,
+        job_posting_id: &str
+    ) -> Result<usize, AppError> {
+        let ids: Vec<String> = repo
+            .query_all::<Self>(&Self::by_job_posting_query(job_posting_id)).await?
+            .into_iter()
+            .map(|application| application.id)
+            .collect();
+        let count = ids.len();
+
+        repo.delete_many::<Self>(ids).await?;
+
+        Ok(count)
+    }
+
+    /// Applications submitted by an applicant, through `ApplicantIndex`.
+    pub fn by_applicant_query(applicant_email: &str) -> ItemQuery {
+        ItemQuery::index(
+            APPLICANT_INDEX,
+            "applicant_email",
+            AttributeValue::S(normalize_email(applicant_email))
+        )
+    }
+
+    /// Applications currently in `status`, through `StatusIndex`.
+    pub fn by_status_query(status: ApplicationStatus) -> ItemQuery {
+        ItemQuery::index(STATUS_INDEX, "application_status", AttributeValue::S(status.to_string()))
+    }
+}
+
+pub(crate) fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+pub(crate) fn validate_email(email: &str) -> Result<(), String> {
+    let email_regex = Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").map_err(|e|
+        AppError::InternalServerError(e.to_string()).to_string()
+    )?;
+
+    if email.is_empty() {
+        return Err("Email cannot be empty".to_string());
+    }
+    if !email_regex.is_match(email) {
+        return Err("Email value invalid".to_string());
+    }
+
+    Ok(())
+}
+
+impl DynamoDbEntity for JobApplication {
+    fn table_name() -> &'static str {
+        "JobApplications"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let job_posting_id = item.get("job_posting_id")?.as_s().ok()?.to_string();
+        let applicant_name = item.get("applicant_name")?.as_s().ok()?.to_string();
+        let applicant_email = item.get("applicant_email")?.as_s().ok()?.to_string();
+
+        let applicant_id = item
+            .get("applicant_id")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let cover_note = item
+            .get("cover_note")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let status_string = item.get("application_status")?.as_s().ok()?;
+        let status = ApplicationStatus::from_string(status_string).ok()?;
+
+        let status_history = item
+            .get("status_history")
+            .and_then(|v| v.as_l().ok())
+            .map(|list| list.iter().filter_map(StatusChange::from_attribute_value).collect())
+            .unwrap_or_default();
+
+        let created_at = item
+            .get("created_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        Some(Self {
+            id,
+            job_posting_id,
+            applicant_name,
+            applicant_email,
+            applicant_id,
+            cover_note,
+            status,
+            status_history,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("job_posting_id".to_string(), AttributeValue::S(self.job_posting_id.clone()));
+        item.insert("applicant_name".to_string(), AttributeValue::S(self.applicant_name.clone()));
+        item.insert(
+            "applicant_email".to_string(),
+            AttributeValue::S(self.applicant_email.clone())
+        );
+
+        if let Some(applicant_id) = &self.applicant_id {
+            item.insert("applicant_id".to_string(), AttributeValue::S(applicant_id.clone()));
+        }
+
+        if let Some(cover_note) = &self.cover_note {
+            item.insert("cover_note".to_string(), AttributeValue::S(cover_note.clone()));
+        }
+
+        // StatusIndex is keyed on application_status
+        item.insert("application_status".to_string(), AttributeValue::S(self.status.to_string()));
+        item.insert(
+            "status_history".to_string(),
+            AttributeValue::L(
+                self.status_history
+                    .iter()
+                    .map(|change| change.to_attribute_value())
+                    .collect()
+            )
+        );
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn application() -> JobApplication {
+        JobApplication::new(
+            "job_application-1".to_string(),
+            "job_posting-1".to_string(),
+            "  Sam Doe ".to_string(),
+            " Sam@Example.COM ".to_string(),
+            Some("user-sam".to_string()),
+            Some("   ".to_string())
+        ).unwrap()
+    }
+
+    #[test]
+    fn new_applications_are_normalized_and_submitted() {
+        let application = application();
+
+        assert_eq!(application.applicant_name, "Sam Doe");
+        assert_eq!(application.applicant_email, "sam@example.com");
+        assert_eq!(application.cover_note, None);
+        assert_eq!(application.status, ApplicationStatus::Submitted);
+        assert_eq!(application.status_history.len(), 1);
+        assert_eq!(application.status_history[0].to, ApplicationStatus::Submitted);
+    }
+
+    #[test]
+    fn invalid_applications_are_rejected() {
+        let new = |name: &str, email: &str, cover_note: Option<String>| {
+            JobApplication::new(
+                "job_application-1".to_string(),
+                "job_posting-1".to_string(),
+                name.to_string(),
+                email.to_string(),
+                None,
+                cover_note
+            )
+        };
+
+        assert!(new(" ", "sam@example.com", None).is_err());
+        assert!(new(&"x".repeat(MAX_APPLICANT_NAME_LENGTH + 1), "sam@example.com", None).is_err());
+        assert!(new("Sam", "", None).is_err());
+        assert!(new("Sam", "sam@example", None).is_err());
+        assert!(new("Sam", "sam @example.com", None).is_err());
+        assert!(new("Sam", "sam@example.com", Some("x".repeat(MAX_COVER_NOTE_LENGTH + 1))).is_err());
+        assert!(new("Sam", "sam@example.com", Some("x".repeat(MAX_COVER_NOTE_LENGTH))).is_ok());
+    }
+
+    #[test]
+    fn items_round_trip() {
+        let mut original = application();
+        original.cover_note = Some("Hello".to_string());
+
+        let restored = JobApplication::from_item(&original.to_item()).unwrap();
+
+        assert_eq!(restored.id, original.id);
+        assert_eq!(restored.applicant_email, original.applicant_email);
+        assert_eq!(restored.applicant_id, original.applicant_id);
+        assert_eq!(restored.cover_note, original.cover_note);
+        assert_eq!(restored.status, original.status);
+        assert_eq!(restored.status_history.len(), 1);
+        // Stored with microsecond precision
+        assert_eq!(timestamp::format(&restored.created_at), timestamp::format(&original.created_at));
+    }
+
+    #[test]
+    fn applicant_lookups_are_case_insensitive() {
+        let query = JobApplication::by_applicant_query(" Sam@Example.com");
+        let key_condition = query.key_condition.unwrap();
+
+        assert_eq!(key_condition.index_name, APPLICANT_INDEX);
+        assert_eq!(key_condition.value, AttributeValue::S("sam@example.com".to_string()));
+    }
+
+    #[test]
+    fn applications_only_advance_or_close() {
+        use ApplicationStatus::*;
+
+        let all = [Submitted, Reviewed, Interviewing, Offered, Rejected, Withdrawn];
+        let allowed = [
+            (Submitted, Reviewed),
+            (Reviewed, Interviewing),
+            (Interviewing, Offered),
+            (Submitted, Rejected),
+            (Reviewed, Rejected),
+            (Interviewing, Rejected),
+            (Offered, Rejected),
+            (Submitted, Withdrawn),
+            (Reviewed, Withdrawn),
+            (Interviewing, Withdrawn),
+            (Offered, Withdrawn),
+        ];
+
+        for from in all {
+            for to in all {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{} -> {}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transitions_append_to_the_history() {
+        let mut application = application();
+
+        let employer = || "user-employer".to_string();
+
+        application
+            .transition(ApplicationStatus::Reviewed, employer(), Some(" Looks good ".to_string()))
+            .unwrap();
+
+        let result = application.transition(ApplicationStatus::Offered, employer(), None);
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+
+        assert_eq!(application.status, ApplicationStatus::Reviewed);
+        assert_eq!(application.status_history.len(), 2);
+
+        let change = &application.status_history[1];
+        assert_eq!(change.from, Some(ApplicationStatus::Submitted));
+        assert_eq!(change.to, ApplicationStatus::Reviewed);
+        assert_eq!(change.changed_by, "user-employer");
+        assert_eq!(change.note.as_deref(), Some("Looks good"));
+    }
+
+    #[test]
+    fn statuses_round_trip_through_their_names() {
+        for status in [
+            ApplicationStatus::Submitted,
+            ApplicationStatus::Reviewed,
+            ApplicationStatus::Interviewing,
+            ApplicationStatus::Offered,
+            ApplicationStatus::Rejected,
+            ApplicationStatus::Withdrawn,
+        ] {
+            assert_eq!(ApplicationStatus::from_string(status.as_str()).unwrap(), status);
+        }
+
+        assert!(ApplicationStatus::from_string("submitted").is_err());
+    }
+}
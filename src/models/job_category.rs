@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use regex::Regex;
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::job_posting_tables::CATEGORY_NAME_INDEX,
+    models::timestamp,
+    pagination::MAX_PAGE_SIZE,
+    repository::{ Filter, ItemQuery },
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+const MAX_CATEGORY_NAME_LENGTH: usize = 100;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobCategory {
+    pub id: String,
+    pub name: String,
+    // URL-safe identifier used by category landing pages
+    pub slug: String,
+    // Enclosing category, if any; categories form a tree
+    pub parent_id: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobCategory {
+    /// Creates a category. When `slug` is not supplied it is derived from `name`.
+    pub fn new(
+        id: String,
+        name: String,
+        slug: Option<String>,
+        parent_id: Option<String>
+    ) -> Result<Self, AppError> {
+        let now = Utc::now();
+        let name = name.trim().to_string();
+        let slug = slug.unwrap_or_else(|| slugify(&name));
+
+        let category = Self {
+            id,
+            name,
+            slug,
+            parent_id,
+            created_at: now,
+            updated_at: now,
+        };
+
+        category.validate().map_err(AppError::ValidationError)?;
+
+        Ok(category)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let slug_regex = Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").map_err(|e|
+            AppError::InternalServerError(e.to_string()).to_string()
+        )?;
+
+        if self.name.is_empty() {
+            return Err("Category name cannot be empty".to_string());
+        }
+        if self.name.chars().count() > MAX_CATEGORY_NAME_LENGTH {
+            return Err(
+                format!("Category name cannot exceed {} characters", MAX_CATEGORY_NAME_LENGTH)
+            );
+        }
+        if !slug_regex.is_match(&self.slug) {
+            return Err(
+                "Slug must be lowercase letters and digits separated by single hyphens".to_string()
+            );
+        }
+        if self.parent_id.as_deref() == Some(self.id.as_str()) {
+            return Err("Category cannot be its own parent".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Categories with exactly this name, through `CategoryNameIndex`.
+    pub fn by_name_query(name: &str) -> ItemQuery {
+        ItemQuery::index(
+            CATEGORY_NAME_INDEX,
+            "category_name",
+            AttributeValue::S(name.trim().to_string())
+        )
+    }
+
+    /// Categories with this slug. The categories table is small, so this is a
+    /// filtered scan rather than a dedicated index.
+    pub fn by_slug_query(slug: &str) -> ItemQuery {
+        ItemQuery::scan().filter(Filter::eq("slug", AttributeValue::S(slug.to_string())))
+    }
+
+    pub async fn find_by_slug(repo: &Repository, slug: &str) -> Result<Option<Self>, AppError> {
+        // Scans evaluate `limit` items per request, so ask for a full page
+        // rather than one item at a time
+        let page = repo.query::<Self>(&Self::by_slug_query(slug), MAX_PAGE_SIZE, None).await?;
+
+        Ok(page.into_entities().into_iter().next())
+    }
+
+    pub async fn find_by_name(repo: &Repository, name: &str) -> Result<Option<Self>, AppError> {
+        let page = repo.query::<Self>(&Self::by_name_query(name), 1, None).await?;
+
+        Ok(page.into_entities().into_iter().next())
+    }
+
+    /// Fails with a `ValidationError` naming the first id that does not refer
+    /// to an existing category.
+    pub async fn ensure_exist(repo: &Repository, ids: &[String]) -> Result<(), AppError> {
+        for id in ids {
+            if repo.get::<Self>(id.clone()).await?.is_none() {
+                return Err(AppError::ValidationError(format!("Job category {} does not exist", id)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Direct children of a category.
+    pub fn children_query(parent_id: &str) -> ItemQuery {
+        ItemQuery::scan().filter(Filter::eq("parent_id", AttributeValue::S(parent_id.to_string())))
+    }
+}
+
+/// Lowercases `name` and joins its alphanumeric runs with hyphens.
+pub(crate) fn slugify(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl DynamoDbEntity for JobCategory {
+    fn table_name() -> &'static str {
+        "JobCategories"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let name = item.get("category_name")?.as_s().ok()?.to_string();
+        let slug = item.get("slug")?.as_s().ok()?.to_string();
+
+        let parent_id = item
+            .get("parent_id")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let created_at = item
+            .get("created_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        Some(Self {
+            id,
+            name,
+            slug,
+            parent_id,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        // CategoryNameIndex is keyed on category_name
+        item.insert("category_name".to_string(), AttributeValue::S(self.name.clone()));
+        item.insert("slug".to_string(), AttributeValue::S(self.slug.clone()));
+
+        if let Some(parent_id) = &self.parent_id {
+            item.insert("parent_id".to_string(), AttributeValue::S(parent_id.clone()));
+        }
+
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(name: &str, slug: Option<&str>) -> Result<JobCategory, AppError> {
+        JobCategory::new("category-1".to_string(), name.to_string(), slug.map(str::to_string), None)
+    }
+
+    #[test]
+    fn slugify_joins_alphanumeric_runs() {
+        assert_eq!(slugify("Skilled Trades"), "skilled-trades");
+        assert_eq!(slugify("  Health & Safety -- Ops "), "health-safety-ops");
+        assert_eq!(slugify("C++ / Rust"), "c-rust");
+        assert_eq!(slugify("Café"), "caf");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn new_derives_the_slug_from_the_trimmed_name() {
+        let category = build("  Skilled Trades ", None).unwrap();
+
+        assert_eq!(category.name, "Skilled Trades");
+        assert_eq!(category.slug, "skilled-trades");
+        assert_eq!(build("Trades", Some("custom-slug")).unwrap().slug, "custom-slug");
+    }
+
+    #[test]
+    fn invalid_categories_are_rejected() {
+        let long_name = "x".repeat(MAX_CATEGORY_NAME_LENGTH + 1);
+
+        for (name, slug) in [
+            ("   ", None),
+            (long_name.as_str(), None),
+            ("!!!", None),
+            ("Trades", Some("Trades")),
+            ("Trades", Some("trades--ops")),
+            ("Trades", Some("-trades")),
+            ("Trades", Some("trades_ops")),
+        ] {
+            assert!(
+                matches!(build(name, slug), Err(AppError::ValidationError(_))),
+                "{:?} {:?}",
+                name,
+                slug
+            );
+        }
+
+        let max_name = "é".repeat(MAX_CATEGORY_NAME_LENGTH);
+        assert!(build(&max_name, Some("accented")).is_ok());
+    }
+
+    #[test]
+    fn categories_cannot_be_their_own_parent() {
+        let result = JobCategory::new(
+            "category-1".to_string(),
+            "Trades".to_string(),
+            None,
+            Some("category-1".to_string())
+        );
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+
+    #[test]
+    fn items_round_trip() {
+        let mut category = build("Trades", None).unwrap();
+        category.parent_id = Some("category-0".to_string());
+
+        let item = category.to_item();
+        assert_eq!(item["category_name"], AttributeValue::S("Trades".to_string()));
+
+        let read = JobCategory::from_item(&item).unwrap();
+        assert_eq!(read.id, category.id);
+        assert_eq!(read.name, category.name);
+        assert_eq!(read.slug, category.slug);
+        assert_eq!(read.parent_id, category.parent_id);
+        assert_eq!(timestamp::format(&read.created_at), timestamp::format(&category.created_at));
+
+        let root = JobCategory { parent_id: None, ..category };
+        assert!(!root.to_item().contains_key("parent_id"));
+        assert_eq!(JobCategory::from_item(&root.to_item()).unwrap().parent_id, None);
+    }
+}
@@ -1,45 +1,77 @@
-use std::collections::HashMap;
+use std::{ collections::HashMap, fmt };
 
-use async_graphql::{ Enum, InputObject };
+use async_graphql::{ Enum, ID, InputObject, MaybeUndefined, SimpleObject };
 use aws_sdk_dynamodb::types::AttributeValue;
 
-use chrono::{ offset::LocalResult, DateTime, TimeZone, Utc };
+use chrono::{ DateTime, Duration, Utc };
 use rust_decimal::Decimal;
 use serde::{ Deserialize, Serialize };
 use tracing::info;
 
-use crate::{ models::{ address::Address, pay::Pay }, AppError, DynamoDbEntity };
+use crate::{
+    db::job_posting_tables::{
+        EMPLOYER_INDEX,
+        GEOHASH_ATTRIBUTE,
+        GEOHASH_INDEX,
+        GEOHASH_PARTITION_ATTRIBUTE,
+        GEOHASH_PARTITION_PRECISION,
+        GEOHASH_PRECISION,
+        JOB_TYPE_INDEX,
+        LISTING_PARTITION,
+        LISTING_PARTITION_ATTRIBUTE,
+        LOCATION_INDEX,
+        PURGE_AT_ATTRIBUTE,
+        TIMELINE_INDEX,
+    },
+    config::LifecycleConfig,
+    geo::{ self, geohash },
+    models::{
+        address::{ Address, AddressPatchInput },
+        coordinates::{ Coordinates, CoordinatesInput },
+        pay::{ normalize_currency, Pay, PayPatchInput, DEFAULT_CURRENCY },
+        timestamp,
+        work_arrangement::{
+            parse_utc_offset,
+            WorkArrangement,
+            WorkArrangementInput,
+            WorkArrangementType,
+        },
+    },
+    pagination::MAX_PAGE_SIZE,
+    repository::{ Filter, ItemQuery, SortKeyOp },
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum JobTypeOption {
+pub enum JobTypeOption {
     FullTime,
     PartTime,
     Contract,
     Temporary,
     Seasonal,
-    Remote,
 }
 
-impl JobTypeOption {
-    pub(crate) fn to_string(&self) -> String {
-        match self {
-            &JobTypeOption::FullTime => "FULL_TIME".to_string(),
-            &JobTypeOption::PartTime => "PART_TIME".to_string(),
-            &JobTypeOption::Contract => "CONTRACT".to_string(),
-            &JobTypeOption::Temporary => "TEMPORARY".to_string(),
-            &JobTypeOption::Seasonal => "SEASONAL".to_string(),
-            &JobTypeOption::Remote => "REMOTE".to_string(),
-        }
+/// Job type stored before remote work became a [`WorkArrangement`]. Such
+/// postings read as full-time and remote.
+pub(crate) const LEGACY_REMOTE_JOB_TYPE: &str = "REMOTE";
+
+impl fmt::Display for JobTypeOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
-    pub(crate) fn to_str(&self) -> &str {
+}
+
+impl JobTypeOption {
+    pub(crate) fn as_str(self) -> &'static str {
         match self {
-            &JobTypeOption::FullTime => "FULL_TIME",
-            &JobTypeOption::PartTime => "PART_TIME",
-            &JobTypeOption::Contract => "CONTRACT",
-            &JobTypeOption::Temporary => "TEMPORARY",
-            &JobTypeOption::Seasonal => "SEASONAL",
-            &JobTypeOption::Remote => "REMOTE",
+            JobTypeOption::FullTime => "FULL_TIME",
+            JobTypeOption::PartTime => "PART_TIME",
+            JobTypeOption::Contract => "CONTRACT",
+            JobTypeOption::Temporary => "TEMPORARY",
+            JobTypeOption::Seasonal => "SEASONAL",
         }
     }
     pub(crate) fn from_string(s: &str) -> Result<JobTypeOption, AppError> {
@@ -49,7 +81,6 @@ impl JobTypeOption {
             "CONTRACT" => Ok(Self::Contract),
             "TEMPORARY" => Ok(Self::Temporary),
             "SEASONAL" => Ok(Self::Seasonal),
-            "REMOTE" => Ok(Self::Remote),
             _ =>
                 Err(
                     AppError::DatabaseError(
@@ -60,18 +91,75 @@ impl JobTypeOption {
     }
 }
 
+/// Where a posting is in its lifecycle.
+///
+/// Only `Published` postings are listed, and only between their `publish_at`
+/// and `expires_at`. The sweep moves published postings past their expiry to
+/// `Expired`, and closed or expired postings to `Archived` after a while.
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PostingStatus {
+    Draft,
+    // Postings stored before statuses existed count as published
+    #[default]
+    Published,
+    Closed,
+    Expired,
+    Archived,
+}
+
+impl fmt::Display for PostingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PostingStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            PostingStatus::Draft => "DRAFT",
+            PostingStatus::Published => "PUBLISHED",
+            PostingStatus::Closed => "CLOSED",
+            PostingStatus::Expired => "EXPIRED",
+            PostingStatus::Archived => "ARCHIVED",
+        }
+    }
+    pub(crate) fn from_string(s: &str) -> Result<PostingStatus, AppError> {
+        match s {
+            "DRAFT" => Ok(Self::Draft),
+            "PUBLISHED" => Ok(Self::Published),
+            "CLOSED" => Ok(Self::Closed),
+            "EXPIRED" => Ok(Self::Expired),
+            "ARCHIVED" => Ok(Self::Archived),
+            _ =>
+                Err(
+                    AppError::DatabaseError(
+                        "Cannot perform from_string on PostingStatus input".to_string()
+                    )
+                ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub(crate) struct ExpectedHoursRange {
+pub struct ExpectedHoursRange {
     pub min: u8,
     pub max: u8,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, InputObject)]
-pub(crate) struct ExpectedHoursRangeInput {
+pub struct ExpectedHoursRangeInput {
     min: u8,
     max: u8,
 }
 
+/// Partial update for expected hours. Omitted bounds are left untouched.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct ExpectedHoursRangePatchInput {
+    pub min: Option<u8>,
+    pub max: Option<u8>,
+}
+
 impl From<ExpectedHoursRangeInput> for ExpectedHoursRange {
     fn from(input: ExpectedHoursRangeInput) -> Self {
         Self {
@@ -90,11 +178,43 @@ impl From<ExpectedHoursRange> for ExpectedHoursRangeInput {
     }
 }
 
+/// Hours in a week, the most a posting can expect.
+const HOURS_PER_WEEK: u8 = 168;
+
 impl ExpectedHoursRange {
-    pub(crate) fn new(min: u8, max: u8) -> Self {
+    pub fn new(min: u8, max: u8) -> Self {
         Self { min, max }
     }
 
+    /// Checks that the range is ordered and fits in a week.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.min > self.max {
+            return Err(
+                AppError::ValidationError(
+                    "Minimum expected hours cannot exceed maximum expected hours".to_string()
+                )
+            );
+        }
+        if self.max > HOURS_PER_WEEK {
+            return Err(
+                AppError::ValidationError(
+                    format!("Expected hours cannot exceed {} a week", HOURS_PER_WEEK)
+                )
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_patch(&mut self, patch: ExpectedHoursRangePatchInput) {
+        if let Some(min) = patch.min {
+            self.min = min;
+        }
+        if let Some(max) = patch.max {
+            self.max = max;
+        }
+    }
+
     fn to_attribute_value(&self) -> AttributeValue {
         let mut item = HashMap::new();
 
@@ -118,16 +238,64 @@ impl ExpectedHoursRange {
         }
     }
 }
+/// Attribute marking a posting held for moderation. Absent on postings that
+/// are not held, so legacy items count as approved.
+pub(crate) const MODERATION_STATUS_ATTRIBUTE: &str = "moderation_status";
+pub(crate) const MODERATION_HELD: &str = "HELD";
+
+/// Denormalized bounds of the annualized pay range, and its currency, so
+/// listings can filter on pay.
+pub(crate) const ANNUAL_PAY_MIN_ATTRIBUTE: &str = "annual_pay_min";
+pub(crate) const ANNUAL_PAY_MAX_ATTRIBUTE: &str = "annual_pay_max";
+pub(crate) const PAY_CURRENCY_ATTRIBUTE: &str = "pay_currency";
+
+/// Denormalized parts of the work arrangement, so listings can filter on them.
+/// The country and offset attributes are only present on remote postings that
+/// restrict them.
+pub(crate) const WORK_ARRANGEMENT_ATTRIBUTE: &str = "work_arrangement_type";
+pub(crate) const REMOTE_COUNTRIES_ATTRIBUTE: &str = "remote_countries";
+pub(crate) const REMOTE_UTC_OFFSET_EARLIEST_ATTRIBUTE: &str = "remote_utc_offset_earliest";
+pub(crate) const REMOTE_UTC_OFFSET_LATEST_ATTRIBUTE: &str = "remote_utc_offset_latest";
+
+/// Attribute holding a posting's [`PostingStatus`].
+pub(crate) const STATUS_ATTRIBUTE: &str = "posting_status";
+
+/// Narrows a public listing to postings that are live at `now`: published,
+/// past `publish_at`, before `expires_at` and not held for moderation.
+///
+/// Expiry is enforced here, at read time, so a posting drops out of listings
+/// the moment it expires rather than whenever the sweep next runs. Legacy
+/// postings without a status or timestamps pass the respective filters.
+fn live_at(query: ItemQuery, now: DateTime<Utc>) -> ItemQuery {
+    let now = timestamp::to_attribute_value(&now);
+
+    query
+        .filter(
+            Filter::eq(
+                STATUS_ATTRIBUTE,
+                AttributeValue::S(PostingStatus::Published.to_string())
+            ).or_missing()
+        )
+        .filter(Filter::le("publish_at", now.clone()).or_missing())
+        .filter(Filter::gt("expires_at", now).or_missing())
+        .filter(
+            Filter::ne(MODERATION_STATUS_ATTRIBUTE, AttributeValue::S(MODERATION_HELD.to_string()))
+        )
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JobPosting {
     pub id: String,
     // Job Title
     pub job_title: String,
-    // Employer - linked to employer - for now just link to employer website
-    pub employer_name: String,
-    pub employer_url: String,
+    // Employer that owns the posting; None only for legacy postings the
+    // employer backfill has not reached, which only site admins can edit
+    pub employer_id: Option<String>,
     // City, state zip
     pub address: Address,
+    // Where the job is, supplied by the employer or geocoded from the
+    // address; None when it could not be located
+    pub coordinates: Option<Coordinates>,
     // hourly concat with job type (part-time, etc.)
     pub pay: Option<Pay>,
     pub job_type: JobTypeOption,
@@ -149,18 +317,35 @@ pub struct JobPosting {
     pub extra_info: Option<String>,
     // expected hours - enum
     pub expected_hours: ExpectedHoursRange,
-    // work location - enum
+    // On-site, hybrid or remote, independent of the job type
+    pub work_arrangement: WorkArrangement,
+
+    // JobCategories this posting is listed under
+    pub category_ids: Vec<String>,
+    // Kept out of public listings until a site admin approves it or the
+    // employer verifies its domain
+    pub held_for_moderation: bool,
+
+    pub status: PostingStatus,
+    // Listed from this point on; None on drafts and legacy postings
+    pub publish_at: Option<DateTime<Utc>>,
+    // No longer listed from this point on
+    pub expires_at: Option<DateTime<Utc>>,
+    // When the posting was closed or expired, the start of its archive delay
+    pub closed_at: Option<DateTime<Utc>>,
+    // When Time to Live deletes an archived posting
+    pub purge_at: Option<DateTime<Utc>>,
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl JobPosting {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         job_title: String,
-        employer_name: String,
-        employer_url: String,
+        employer_id: String,
         address: Address,
         pay: Option<Pay>,
         job_type_string: String,
@@ -170,17 +355,21 @@ impl JobPosting {
         employee_responsibilities: Option<Vec<String>>,
         experience_requirements: Option<Vec<String>>,
         extra_info: Option<String>,
-        expected_hours: ExpectedHoursRange
+        expected_hours: ExpectedHoursRange,
+        work_arrangement: WorkArrangement,
+        category_ids: Vec<String>
     ) -> Result<Self, AppError> {
         let now = Utc::now();
-        let job_type = JobTypeOption::from_string(&job_type_string)?;
+        let job_type = JobTypeOption::from_string(&job_type_string).map_err(|_| {
+            AppError::ValidationError(format!("Unknown job type: {}", job_type_string))
+        })?;
 
         Ok(Self {
             id,
             job_title,
-            employer_name,
-            employer_url,
+            employer_id: Some(employer_id),
             address,
+            coordinates: None,
             pay,
             job_type,
             link_to_application,
@@ -189,10 +378,532 @@ impl JobPosting {
             experience_requirements,
             extra_info,
             expected_hours,
+            work_arrangement,
+            category_ids,
+            held_for_moderation: false,
+            status: PostingStatus::Draft,
+            publish_at: None,
+            expires_at: None,
+            closed_at: None,
+            purge_at: None,
             created_at: now,
             updated_at: now,
         })
     }
+
+    /// The status as of `now`. A published posting past its expiry reads as
+    /// expired even before the sweep has recorded that.
+    pub fn status_at(&self, now: DateTime<Utc>) -> PostingStatus {
+        let expired = self.expires_at.is_some_and(|expires_at| expires_at <= now);
+
+        match self.status {
+            PostingStatus::Published if expired => PostingStatus::Expired,
+            status => status,
+        }
+    }
+
+    /// Whether the posting belongs in public listings at `now`.
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        !self.held_for_moderation &&
+            self.status_at(now) == PostingStatus::Published &&
+            self.publish_at.is_none_or(|publish_at| publish_at <= now)
+    }
+
+    /// Whether anyone may look the posting up by id at `now`. Closed and
+    /// expired postings stay reachable so existing links keep working; drafts,
+    /// scheduled, held and archived postings are only visible to their employer.
+    pub fn is_public(&self, now: DateTime<Utc>) -> bool {
+        !self.held_for_moderation &&
+            matches!(
+                self.status_at(now),
+                PostingStatus::Published | PostingStatus::Closed | PostingStatus::Expired
+            ) &&
+            self.publish_at.is_none_or(|publish_at| publish_at <= now)
+    }
+
+    /// Publishes the posting, or reschedules an already published one.
+    ///
+    /// `publish_at` defaults to `now`, and `expires_at` to `lifetime` after
+    /// `publish_at`. Archived postings cannot be published again.
+    pub fn publish(
+        &mut self,
+        publish_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+        lifetime: Duration,
+        now: DateTime<Utc>
+    ) -> Result<(), AppError> {
+        if self.status == PostingStatus::Archived {
+            return Err(
+                AppError::ValidationError("Archived job postings cannot be published".to_string())
+            );
+        }
+
+        let publish_at = publish_at.unwrap_or(now);
+        let expires_at = expires_at.unwrap_or(publish_at + lifetime);
+
+        if expires_at <= publish_at || expires_at <= now {
+            return Err(
+                AppError::ValidationError(
+                    "expiresAt must be in the future and after publishAt".to_string()
+                )
+            );
+        }
+
+        self.status = PostingStatus::Published;
+        self.publish_at = Some(publish_at);
+        self.expires_at = Some(expires_at);
+        self.closed_at = None;
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    /// Takes a published posting out of the listings before it expires.
+    pub fn close(&mut self, now: DateTime<Utc>) -> Result<(), AppError> {
+        if self.status_at(now) != PostingStatus::Published {
+            return Err(
+                AppError::ValidationError(
+                    format!("Only published job postings can be closed, not {}", self.status_at(now))
+                )
+            );
+        }
+
+        self.status = PostingStatus::Closed;
+        self.closed_at = Some(now);
+        self.updated_at = now;
+
+        Ok(())
+    }
+
+    /// Applies the transitions that are due at `now`, returning whether
+    /// anything changed:
+    ///
+    /// - published postings without an expiry get the default lifetime,
+    ///   counted from when they were published
+    /// - published postings past `expires_at` become expired
+    /// - closed and expired postings become archived `archive_after` their
+    ///   closing, and are scheduled for deletion `purge_after` later
+    pub fn advance_lifecycle(&mut self, config: &LifecycleConfig, now: DateTime<Utc>) -> bool {
+        let mut changed = false;
+
+        if self.status == PostingStatus::Published && self.expires_at.is_none() {
+            let published_at = self.publish_at.unwrap_or(self.created_at);
+            self.expires_at = Some(
+                published_at + Duration::seconds(config.default_posting_lifetime as i64)
+            );
+            changed = true;
+        }
+
+        if self.status_at(now) == PostingStatus::Expired && self.status == PostingStatus::Published {
+            self.status = PostingStatus::Expired;
+            self.closed_at = self.expires_at;
+            changed = true;
+        }
+
+        if matches!(self.status, PostingStatus::Closed | PostingStatus::Expired) {
+            let closed_at = self.closed_at.unwrap_or(self.updated_at);
+
+            if closed_at + Duration::seconds(config.archive_after as i64) <= now {
+                self.status = PostingStatus::Archived;
+                self.purge_at = Some(now + Duration::seconds(config.purge_after as i64));
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.updated_at = now;
+        }
+
+        changed
+    }
+}
+
+/// A posting found by a radius search.
+#[derive(Clone, Debug, SimpleObject)]
+pub struct NearbyJobPosting {
+    pub job_posting: JobPosting,
+    /// Great-circle distance from the search center, in kilometres
+    pub distance_km: f64,
+}
+
+/// Partial update for a job posting.
+///
+/// Omitted fields are left untouched. Nullable fields can be cleared by passing
+/// an explicit `null`. Nested inputs are merged field by field, except
+/// `workArrangement`, which replaces the stored arrangement as a whole.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct JobPostingPatchInput {
+    pub job_title: Option<String>,
+    /// A changed address clears the coordinates, unless `coordinates` is given
+    /// too, and the posting is geocoded again.
+    pub address: Option<AddressPatchInput>,
+    pub coordinates: MaybeUndefined<CoordinatesInput>,
+    pub pay: MaybeUndefined<PayPatchInput>,
+    pub job_type: Option<String>,
+    pub link_to_application: MaybeUndefined<String>,
+    pub job_description: Option<String>,
+    pub employee_responsibilities: MaybeUndefined<Vec<String>>,
+    pub experience_requirements: MaybeUndefined<Vec<String>>,
+    pub extra_info: MaybeUndefined<String>,
+    pub expected_hours: Option<ExpectedHoursRangePatchInput>,
+    pub work_arrangement: Option<WorkArrangementInput>,
+    /// Replaces the posting's categories; an empty list removes them all.
+    pub category_ids: Option<Vec<String>>,
+}
+
+impl JobPosting {
+    /// Merges the supplied fields of `patch` into this posting and bumps
+    /// `updated_at`. `created_at` is never touched. Fails if the merged
+    /// posting is invalid.
+    pub fn apply_patch(&mut self, patch: JobPostingPatchInput) -> Result<(), AppError> {
+        if let Some(job_title) = patch.job_title {
+            self.job_title = job_title;
+        }
+        if let Some(address) = patch.address {
+            self.address.apply_patch(address);
+
+            if patch.coordinates.is_undefined() {
+                self.coordinates = None;
+            }
+        }
+        if let Some(job_type) = patch.job_type {
+            self.job_type = JobTypeOption::from_string(&job_type).map_err(|_| {
+                AppError::ValidationError(format!("Unknown job type: {}", job_type))
+            })?;
+        }
+        if let Some(job_description) = patch.job_description {
+            self.job_description = job_description;
+        }
+        if let Some(expected_hours) = patch.expected_hours {
+            self.expected_hours.apply_patch(expected_hours);
+        }
+        if let Some(work_arrangement) = patch.work_arrangement {
+            self.work_arrangement = WorkArrangement::try_from(work_arrangement)?;
+        }
+        if let Some(category_ids) = patch.category_ids {
+            self.category_ids = category_ids;
+        }
+
+        match patch.pay {
+            MaybeUndefined::Value(pay) => {
+                self.pay = Some(Pay::patched(self.pay.take(), pay)?);
+            }
+            MaybeUndefined::Null => {
+                self.pay = None;
+            }
+            MaybeUndefined::Undefined => {}
+        }
+
+        match patch.coordinates {
+            MaybeUndefined::Value(coordinates) => {
+                self.coordinates = Some(Coordinates::try_from(coordinates)?);
+            }
+            MaybeUndefined::Null => {
+                self.coordinates = None;
+            }
+            MaybeUndefined::Undefined => {}
+        }
+
+        patch.link_to_application.update_to(&mut self.link_to_application);
+        patch.employee_responsibilities.update_to(&mut self.employee_responsibilities);
+        patch.experience_requirements.update_to(&mut self.experience_requirements);
+        patch.extra_info.update_to(&mut self.extra_info);
+
+        self.validate()?;
+
+        self.updated_at = Utc::now();
+
+        Ok(())
+    }
+
+    /// Checks the fields that the constructor takes on trust: a title and
+    /// description, a valid address, valid pay and expected hours.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.job_title.trim().is_empty() {
+            return Err(AppError::ValidationError("Job title cannot be empty".to_string()));
+        }
+        if self.job_description.trim().is_empty() {
+            return Err(AppError::ValidationError("Job description cannot be empty".to_string()));
+        }
+
+        self.address.validate().map_err(AppError::ValidationError)?;
+
+        if let Some(pay) = &self.pay {
+            pay.validate()?;
+        }
+
+        self.expected_hours.validate()
+    }
+}
+
+/// Criteria for listing job postings.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct JobPostingFilter {
+    pub employer_id: Option<ID>,
+    /// Only postings of the employer with exactly this name. Resolved to an
+    /// employer id before the read.
+    pub employer_name: Option<String>,
+    pub job_type: Option<JobTypeOption>,
+    pub city: Option<String>,
+    /// Only postings listed under the category with this slug. Not backed by an
+    /// index, so on its own it results in a filtered scan.
+    pub category_slug: Option<String>,
+    /// Only postings whose annualized pay range reaches this amount, in
+    /// `payCurrency`. Postings without pay are left out.
+    pub min_annual_pay: Option<Decimal>,
+    /// Currency of `minAnnualPay`; defaults to USD. Postings paying in other
+    /// currencies are left out rather than converted.
+    pub pay_currency: Option<String>,
+    pub work_arrangement: Option<WorkArrangementType>,
+    /// Only remote postings open to staff living in this country, given as an
+    /// ISO 3166-1 alpha-2 code. Includes postings open to any country.
+    pub remote_country: Option<String>,
+    /// Only remote postings open to staff working at this UTC offset, such as
+    /// `-05:00`. Includes postings open to any time zone.
+    pub utc_offset: Option<String>,
+}
+
+impl JobPostingFilter {
+    /// Plans the public listing read for this filter, which only includes
+    /// postings that are live at `now`.
+    ///
+    /// The most selective supplied field is served by its GSI with a `Query`,
+    /// and the remaining fields become filter expressions on that query. Only
+    /// combinations that no index covers fall back to a filtered scan.
+    ///
+    /// `category_slug` has to be resolved to a category id by the caller, which
+    /// passes it in as `category_id`. Likewise `employer_name` is ignored here;
+    /// the caller resolves it into `employer_id`.
+    pub fn to_query(
+        &self,
+        category_id: Option<&str>,
+        now: DateTime<Utc>
+    ) -> Result<ItemQuery, AppError> {
+        let mut conditions = Vec::new();
+
+        if let Some(employer_id) = &self.employer_id {
+            conditions.push((EMPLOYER_INDEX, "employer_id", AttributeValue::S(employer_id.to_string())));
+        }
+        if let Some(city) = &self.city {
+            conditions.push((LOCATION_INDEX, "city", AttributeValue::S(city.clone())));
+        }
+        if let Some(job_type) = &self.job_type {
+            conditions.push((JOB_TYPE_INDEX, "job_type", AttributeValue::S(job_type.to_string())));
+        }
+
+        let mut conditions = conditions.into_iter();
+
+        let query = match conditions.next() {
+            Some((index_name, attribute, value)) => ItemQuery::index(index_name, attribute, value),
+            None => ItemQuery::scan(),
+        };
+
+        let query = conditions.fold(live_at(query, now), |query, (_, attribute, value)| {
+            query.filter(Filter::eq(attribute, value))
+        });
+
+        let query = match category_id {
+            Some(category_id) =>
+                query.filter(
+                    Filter::contains("category_ids", AttributeValue::S(category_id.to_string()))
+                ),
+            None => query,
+        };
+
+        let currency = self.pay_currency
+            .as_deref()
+            .map(normalize_currency)
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+
+        let query = match &self.min_annual_pay {
+            Some(min_annual_pay) =>
+                query
+                    .filter(Filter::eq(PAY_CURRENCY_ATTRIBUTE, AttributeValue::S(currency)))
+                    .filter(
+                        Filter::ge(
+                            ANNUAL_PAY_MAX_ATTRIBUTE,
+                            AttributeValue::N(min_annual_pay.normalize().to_string())
+                        )
+                    ),
+            None => query,
+        };
+
+        self.work_arrangement_filters(query)
+    }
+
+    /// Adds the work arrangement criteria. A remote country or UTC offset
+    /// implies a remote arrangement.
+    fn work_arrangement_filters(&self, query: ItemQuery) -> Result<ItemQuery, AppError> {
+        let remote_only = self.remote_country.is_some() || self.utc_offset.is_some();
+
+        let arrangement_type = match (self.work_arrangement, remote_only) {
+            (Some(arrangement_type), false) => Some(arrangement_type),
+            (None | Some(WorkArrangementType::Remote), true) => Some(WorkArrangementType::Remote),
+            (Some(_), true) => {
+                return Err(
+                    AppError::ValidationError(
+                        "remoteCountry and utcOffset only apply to remote work".to_string()
+                    )
+                );
+            }
+            (None, false) => None,
+        };
+
+        let mut query = match arrangement_type {
+            Some(arrangement_type) => {
+                let filter = Filter::eq(
+                    WORK_ARRANGEMENT_ATTRIBUTE,
+                    AttributeValue::S(arrangement_type.to_string())
+                );
+
+                // Postings stored before arrangements existed are on-site
+                if arrangement_type == WorkArrangementType::OnSite {
+                    query.filter(filter.or_missing())
+                } else {
+                    query.filter(filter)
+                }
+            }
+            None => query,
+        };
+
+        if let Some(country) = &self.remote_country {
+            query = query.filter(
+                Filter::contains(
+                    REMOTE_COUNTRIES_ATTRIBUTE,
+                    AttributeValue::S(country.trim().to_ascii_uppercase())
+                ).or_missing()
+            );
+        }
+
+        if let Some(utc_offset) = &self.utc_offset {
+            let offset = AttributeValue::N(parse_utc_offset(utc_offset)?.to_string());
+
+            let earliest = Filter::le(REMOTE_UTC_OFFSET_EARLIEST_ATTRIBUTE, offset.clone());
+            let latest = Filter::ge(REMOTE_UTC_OFFSET_LATEST_ATTRIBUTE, offset);
+
+            query = query.filter(earliest.or_missing()).filter(latest.or_missing());
+        }
+
+        Ok(query)
+    }
+}
+
+impl JobPosting {
+    /// Postings owned by an employer, through `EmployerIdIndex`, whatever their
+    /// status. Includes postings held for moderation.
+    pub fn by_employer_query(employer_id: &str) -> ItemQuery {
+        ItemQuery::index(EMPLOYER_INDEX, "employer_id", AttributeValue::S(employer_id.to_string()))
+    }
+
+    /// Postings the lifecycle sweep may still have to advance, which is every
+    /// posting that is not archived yet.
+    pub fn unarchived_query() -> ItemQuery {
+        ItemQuery::scan().filter(
+            Filter::ne(STATUS_ATTRIBUTE, AttributeValue::S(PostingStatus::Archived.to_string()))
+        )
+    }
+
+    /// An employer's postings that are held for moderation.
+    pub fn held_by_employer_query(employer_id: &str) -> ItemQuery {
+        Self::by_employer_query(employer_id).filter(
+            Filter::eq(MODERATION_STATUS_ATTRIBUTE, AttributeValue::S(MODERATION_HELD.to_string()))
+        )
+    }
+
+    /// Newest-first listing through `TimelineIndex` of the postings live at
+    /// `now`, optionally limited to postings created at or after `since`.
+    pub fn latest_query(since: Option<DateTime<Utc>>, now: DateTime<Utc>) -> ItemQuery {
+        let query = ItemQuery::index(
+            TIMELINE_INDEX,
+            LISTING_PARTITION_ATTRIBUTE,
+            AttributeValue::S(LISTING_PARTITION.to_string())
+        )
+            .sorted_by("created_at")
+            .descending();
+        let query = live_at(query, now);
+
+        match since {
+            Some(since) =>
+                query.sort_key("created_at", SortKeyOp::Ge, timestamp::to_attribute_value(&since)),
+            None => query,
+        }
+    }
+}
+
+impl JobPosting {
+    /// Live postings at `now` whose geohash starts with `cell`, through
+    /// `GeohashIndex`. `cell` must be at least as long as the partition prefix.
+    pub fn geohash_query(cell: &str, now: DateTime<Utc>) -> ItemQuery {
+        let partition = &cell[..GEOHASH_PARTITION_PRECISION.min(cell.len())];
+
+        let query = ItemQuery::index(
+            GEOHASH_INDEX,
+            GEOHASH_PARTITION_ATTRIBUTE,
+            AttributeValue::S(partition.to_string())
+        ).sorted_by(GEOHASH_ATTRIBUTE);
+
+        let query = if cell.len() > partition.len() {
+            query.sort_key(
+                GEOHASH_ATTRIBUTE,
+                SortKeyOp::BeginsWith,
+                AttributeValue::S(cell.to_string())
+            )
+        } else {
+            query
+        };
+
+        live_at(query, now)
+    }
+
+    /// Postings live at `now` within `radius_km` of `center`, nearest first.
+    ///
+    /// Reads the geohash cells covering the circle, which also hold postings
+    /// around it, and keeps those whose distance is within the radius.
+    pub async fn find_near(
+        repo: &Repository,
+        center: Coordinates,
+        radius_km: f64,
+        now: DateTime<Utc>
+    ) -> Result<Vec<NearbyJobPosting>, AppError> {
+        if !(radius_km > 0.0 && radius_km <= geo::MAX_RADIUS_KM) {
+            return Err(
+                AppError::ValidationError(
+                    format!("radiusKm must be above 0 and at most {}", geo::MAX_RADIUS_KM)
+                )
+            );
+        }
+
+        let mut nearby = Vec::new();
+
+        for cell in geohash::covering_cells(center, radius_km, GEOHASH_PARTITION_PRECISION) {
+            let query = Self::geohash_query(&cell, now);
+            let mut after = None;
+
+            loop {
+                let page = repo.query::<JobPosting>(&query, MAX_PAGE_SIZE, after).await?;
+                after = page.next_cursor.clone();
+
+                for job_posting in page.into_entities() {
+                    let Some(point) = job_posting.coordinates else {
+                        continue;
+                    };
+                    let distance_km = geo::distance_km(center, point);
+
+                    if distance_km <= radius_km {
+                        nearby.push(NearbyJobPosting { job_posting, distance_km });
+                    }
+                }
+
+                if after.is_none() {
+                    break;
+                }
+            }
+        }
+
+        nearby.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+
+        Ok(nearby)
+    }
 }
 
 impl DynamoDbEntity for JobPosting {
@@ -210,17 +921,25 @@ impl DynamoDbEntity for JobPosting {
         let id = item.get("id")?.as_s().ok()?.to_string();
         let job_title = item.get("job_title")?.as_s().ok()?.to_string();
 
-        let employer_name = item.get("employer_name")?.as_s().ok()?.to_string();
-        let employer_url = item.get("employer_url")?.as_s().ok()?.to_string();
+        let address = item.get("address").and_then(Address::from_attribute_value)?;
 
-        let address = item.get("address").and_then(|av| Address::from_attribute_value(av))?;
+        let coordinates = item.get("coordinates").and_then(Coordinates::from_attribute_value);
 
-        let pay = item.get("pay").and_then(|av| Pay::from_attribute_value(av));
+        let pay = item.get("pay").and_then(Pay::from_attribute_value);
 
         let job_type_string = item.get("job_type")?.as_s().ok()?;
-        let job_type = JobTypeOption::from_string(&job_type_string)
-            .map_err(|e| e)
-            .ok()?;
+        let legacy_remote = job_type_string == LEGACY_REMOTE_JOB_TYPE;
+        let job_type = if legacy_remote {
+            JobTypeOption::FullTime
+        } else {
+            JobTypeOption::from_string(job_type_string).ok()?
+        };
+
+        let work_arrangement = match item.get("work_arrangement") {
+            Some(av) => WorkArrangement::from_attribute_value(av)?,
+            None if legacy_remote => WorkArrangement::remote(),
+            None => WorkArrangement::default(),
+        };
 
         let link_to_application = item
             .get("link_to_application")
@@ -264,25 +983,64 @@ impl DynamoDbEntity for JobPosting {
 
         let expected_hours = item
             .get("expected_hours")
-            .and_then(|av| ExpectedHoursRange::from_attribute_value(av))?;
+            .and_then(ExpectedHoursRange::from_attribute_value)?;
+
+        let employer_id = item
+            .get("employer_id")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let category_ids = item
+            .get("category_ids")
+            .and_then(|v| v.as_l().ok())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|av|
+                        av
+                            .as_s()
+                            .ok()
+                            .map(|s| s.to_string())
+                    )
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let held_for_moderation = item
+            .get(MODERATION_STATUS_ATTRIBUTE)
+            .and_then(|v| v.as_s().ok())
+            .is_some_and(|status| status == MODERATION_HELD);
+
+        let status = item
+            .get(STATUS_ATTRIBUTE)
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| PostingStatus::from_string(s).ok())
+            .unwrap_or_default();
+
+        let publish_at = item.get("publish_at").and_then(timestamp::from_attribute_value);
+        let expires_at = item.get("expires_at").and_then(timestamp::from_attribute_value);
+        let closed_at = item.get("closed_at").and_then(timestamp::from_attribute_value);
+
+        let purge_at = item
+            .get(PURGE_AT_ATTRIBUTE)
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse::<i64>().ok())
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
 
         let created_at = item
             .get("created_at")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
-            .unwrap_or_else(|| Utc::now());
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
 
         let updated_at = item
             .get("updated_at")
-            .and_then(|v| v.as_s().ok())
-            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
-            .unwrap_or_else(|| Utc::now());
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
 
         Some(Self {
             id,
             job_title,
-            employer_name,
-            employer_url,
+            employer_id,
+            coordinates,
             pay,
             job_type,
             link_to_application,
@@ -291,6 +1049,14 @@ impl DynamoDbEntity for JobPosting {
             experience_requirements,
             extra_info,
             expected_hours,
+            work_arrangement,
+            category_ids,
+            held_for_moderation,
+            status,
+            publish_at,
+            expires_at,
+            closed_at,
+            purge_at,
             address,
             created_at,
             updated_at,
@@ -302,17 +1068,39 @@ impl DynamoDbEntity for JobPosting {
 
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
         item.insert("job_title".to_string(), AttributeValue::S(self.job_title.clone()));
-        item.insert("employer_name".to_string(), AttributeValue::S(self.employer_name.clone()));
-        item.insert("employer_url".to_string(), AttributeValue::S(self.employer_url.clone()));
         item.insert("address".to_string(), self.address.to_attribute_value());
 
         // Add city as a separate field for GSI querying
         item.insert("city".to_string(), AttributeValue::S(self.address.city.clone()));
 
+        // The geohash and its prefix are the keys of GeohashIndex
+        if let Some(coordinates) = &self.coordinates {
+            item.insert("coordinates".to_string(), coordinates.to_attribute_value());
+
+            let hash = geohash::encode(*coordinates, GEOHASH_PRECISION);
+            item.insert(
+                GEOHASH_PARTITION_ATTRIBUTE.to_string(),
+                AttributeValue::S(hash[..GEOHASH_PARTITION_PRECISION].to_string())
+            );
+            item.insert(GEOHASH_ATTRIBUTE.to_string(), AttributeValue::S(hash));
+        }
+
         if let Some(pay) = &self.pay {
             item.insert("pay".to_string(), pay.to_attribute_value());
         }
 
+        if let Some(annualized) = self.pay.as_ref().and_then(|pay| pay.annualized(&self.expected_hours)) {
+            item.insert(
+                ANNUAL_PAY_MIN_ATTRIBUTE.to_string(),
+                AttributeValue::N(annualized.min.normalize().to_string())
+            );
+            item.insert(
+                ANNUAL_PAY_MAX_ATTRIBUTE.to_string(),
+                AttributeValue::N(annualized.max.normalize().to_string())
+            );
+            item.insert(PAY_CURRENCY_ATTRIBUTE.to_string(), AttributeValue::S(annualized.currency));
+        }
+
         item.insert("job_type".to_string(), AttributeValue::S(self.job_type.to_string()));
 
         if let Some(link_to_application) = &self.link_to_application {
@@ -352,9 +1140,282 @@ impl DynamoDbEntity for JobPosting {
         }
 
         item.insert("expected_hours".to_string(), self.expected_hours.to_attribute_value());
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.to_string()));
-        item.insert("updated_at".to_string(), AttributeValue::S(self.updated_at.to_string()));
+
+        item.insert("work_arrangement".to_string(), self.work_arrangement.to_attribute_value());
+        item.insert(
+            WORK_ARRANGEMENT_ATTRIBUTE.to_string(),
+            AttributeValue::S(self.work_arrangement.arrangement_type.to_string())
+        );
+
+        if !self.work_arrangement.countries.is_empty() {
+            item.insert(
+                REMOTE_COUNTRIES_ATTRIBUTE.to_string(),
+                AttributeValue::L(
+                    self.work_arrangement.countries
+                        .iter()
+                        .map(|country| AttributeValue::S(country.clone()))
+                        .collect()
+                )
+            );
+        }
+        if let Some(utc_offsets) = &self.work_arrangement.utc_offsets {
+            item.insert(
+                REMOTE_UTC_OFFSET_EARLIEST_ATTRIBUTE.to_string(),
+                AttributeValue::N(utc_offsets.earliest.to_string())
+            );
+            item.insert(
+                REMOTE_UTC_OFFSET_LATEST_ATTRIBUTE.to_string(),
+                AttributeValue::N(utc_offsets.latest.to_string())
+            );
+        }
+
+        if let Some(employer_id) = &self.employer_id {
+            item.insert("employer_id".to_string(), AttributeValue::S(employer_id.clone()));
+        }
+
+        if !self.category_ids.is_empty() {
+            item.insert(
+                "category_ids".to_string(),
+                AttributeValue::L(
+                    self.category_ids
+                        .iter()
+                        .map(|id| AttributeValue::S(id.clone()))
+                        .collect()
+                )
+            );
+        }
+        if self.held_for_moderation {
+            item.insert(
+                MODERATION_STATUS_ATTRIBUTE.to_string(),
+                AttributeValue::S(MODERATION_HELD.to_string())
+            );
+        }
+
+        item.insert(STATUS_ATTRIBUTE.to_string(), AttributeValue::S(self.status.to_string()));
+
+        if let Some(publish_at) = &self.publish_at {
+            item.insert("publish_at".to_string(), timestamp::to_attribute_value(publish_at));
+        }
+        if let Some(expires_at) = &self.expires_at {
+            item.insert("expires_at".to_string(), timestamp::to_attribute_value(expires_at));
+        }
+        if let Some(closed_at) = &self.closed_at {
+            item.insert("closed_at".to_string(), timestamp::to_attribute_value(closed_at));
+        }
+        // Time to Live requires epoch seconds
+        if let Some(purge_at) = &self.purge_at {
+            item.insert(
+                PURGE_AT_ATTRIBUTE.to_string(),
+                AttributeValue::N(purge_at.timestamp().to_string())
+            );
+        }
+
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        // Constant partition key for TimelineIndex
+        item.insert(
+            LISTING_PARTITION_ATTRIBUTE.to_string(),
+            AttributeValue::S(LISTING_PARTITION.to_string())
+        );
 
         item
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::models::{ address::AddressPatchInput, pay::{ CadenceOption, PayPatchInput } };
+
+    fn welder() -> JobPosting {
+        let address = Address::new(
+            "1 Main St".to_string(),
+            Some("Suite 4".to_string()),
+            "Marquette".to_string(),
+            "MI".to_string(),
+            "US".to_string(),
+            "49855".to_string()
+        );
+        let pay = Pay::new(CadenceOption::Hour, Decimal::from(20), Some(Decimal::from(25)), "USD");
+
+        JobPosting::new(
+            "job_posting-1".to_string(),
+            "Welder".to_string(),
+            "employer-1".to_string(),
+            address,
+            Some(pay.unwrap()),
+            "FULL_TIME".to_string(),
+            Some("https://example.com/apply".to_string()),
+            "Weld pipe.".to_string(),
+            Some(vec!["Weld".to_string()]),
+            None,
+            Some("Bring boots".to_string()),
+            ExpectedHoursRange::new(30, 40),
+            WorkArrangement::default(),
+            Vec::new()
+        ).unwrap()
+    }
+
+    #[test]
+    fn omitted_fields_are_left_untouched() {
+        let mut job_posting = welder();
+        let created_at = job_posting.created_at;
+
+        job_posting.apply_patch(JobPostingPatchInput::default()).unwrap();
+
+        assert_eq!(job_posting.job_title, "Welder");
+        assert_eq!(job_posting.link_to_application.as_deref(), Some("https://example.com/apply"));
+        assert_eq!(job_posting.extra_info.as_deref(), Some("Bring boots"));
+        assert_eq!(job_posting.address.unit.as_deref(), Some("Suite 4"));
+        assert!(job_posting.pay.is_some());
+        assert_eq!(job_posting.created_at, created_at);
+    }
+
+    #[test]
+    fn explicit_nulls_clear_nullable_fields() {
+        let mut job_posting = welder();
+
+        job_posting.apply_patch(JobPostingPatchInput {
+            link_to_application: MaybeUndefined::Null,
+            extra_info: MaybeUndefined::Value("Steel toes".to_string()),
+            employee_responsibilities: MaybeUndefined::Null,
+            pay: MaybeUndefined::Null,
+            address: Some(AddressPatchInput { unit: MaybeUndefined::Null, ..Default::default() }),
+            ..Default::default()
+        }).unwrap();
+
+        assert_eq!(job_posting.link_to_application, None);
+        assert_eq!(job_posting.extra_info.as_deref(), Some("Steel toes"));
+        assert_eq!(job_posting.employee_responsibilities, None);
+        assert!(job_posting.pay.is_none());
+        assert_eq!(job_posting.address.unit, None);
+        assert_eq!(job_posting.address.street, "1 Main St");
+    }
+
+    #[test]
+    fn nested_patches_merge_field_by_field() {
+        let mut job_posting = welder();
+
+        job_posting.apply_patch(JobPostingPatchInput {
+            address: Some(AddressPatchInput { city: Some("Ishpeming".to_string()), ..Default::default() }),
+            pay: MaybeUndefined::Value(PayPatchInput { max: MaybeUndefined::Null, ..Default::default() }),
+            expected_hours: Some(ExpectedHoursRangePatchInput { max: Some(45), ..Default::default() }),
+            ..Default::default()
+        }).unwrap();
+
+        assert_eq!(job_posting.address.city, "Ishpeming");
+        assert_eq!(job_posting.address.zip, "49855");
+
+        let pay = job_posting.pay.unwrap();
+        assert_eq!(pay.min, Decimal::from(20));
+        assert_eq!(pay.max, None);
+        assert_eq!(pay.cadence, CadenceOption::Hour);
+
+        assert_eq!((job_posting.expected_hours.min, job_posting.expected_hours.max), (30, 45));
+    }
+
+    #[test]
+    fn invalid_merged_postings_are_rejected() {
+        for patch in [
+            JobPostingPatchInput { job_title: Some("  ".to_string()), ..Default::default() },
+            JobPostingPatchInput { job_type: Some("GIG".to_string()), ..Default::default() },
+            JobPostingPatchInput {
+                pay: MaybeUndefined::Value(PayPatchInput {
+                    min: Some(Decimal::from(30)),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            JobPostingPatchInput {
+                expected_hours: Some(ExpectedHoursRangePatchInput { min: Some(50), ..Default::default() }),
+                ..Default::default()
+            },
+        ] {
+            let mut job_posting = welder();
+
+            assert!(
+                matches!(job_posting.apply_patch(patch.clone()), Err(AppError::ValidationError(_))),
+                "{:?}",
+                patch
+            );
+        }
+    }
+
+    #[test]
+    fn new_pay_needs_cadence_minimum_and_currency() {
+        let mut job_posting = JobPosting { pay: None, ..welder() };
+
+        let partial = PayPatchInput { min: Some(Decimal::from(20)), ..Default::default() };
+        let patch = JobPostingPatchInput { pay: MaybeUndefined::Value(partial), ..Default::default() };
+        assert!(job_posting.apply_patch(patch).is_err());
+
+        let complete = PayPatchInput {
+            cadence: Some(CadenceOption::Year),
+            min: Some(Decimal::from(50000)),
+            max: MaybeUndefined::Undefined,
+            currency: Some("usd".to_string()),
+        };
+        let patch = JobPostingPatchInput { pay: MaybeUndefined::Value(complete), ..Default::default() };
+        job_posting.apply_patch(patch).unwrap();
+
+        assert_eq!(job_posting.pay.unwrap().currency, "USD");
+    }
+
+    fn index_of(query: &ItemQuery) -> Option<(&str, &str)> {
+        query.key_condition
+            .as_ref()
+            .map(|key| (key.index_name.as_str(), key.partition_key.as_str()))
+    }
+
+    fn filtered_attributes(query: &ItemQuery) -> Vec<&str> {
+        query.filters
+            .iter()
+            .map(|filter| filter.attribute.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn the_most_selective_filter_picks_the_index() {
+        let now = Utc::now();
+        let employer_id = Some(ID("employer-1".to_string()));
+        let city = Some("Marquette".to_string());
+        let job_type = Some(JobTypeOption::FullTime);
+
+        for (filter, index) in [
+            (JobPostingFilter::default(), None),
+            (JobPostingFilter { job_type, ..Default::default() }, Some((JOB_TYPE_INDEX, "job_type"))),
+            (
+                JobPostingFilter { city: city.clone(), job_type, ..Default::default() },
+                Some((LOCATION_INDEX, "city")),
+            ),
+            (
+                JobPostingFilter { employer_id, city, job_type, ..Default::default() },
+                Some((EMPLOYER_INDEX, "employer_id")),
+            ),
+        ] {
+            let query = filter.to_query(None, now).unwrap();
+            assert_eq!(index_of(&query), index, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn fields_not_served_by_the_index_become_filters() {
+        let filter = JobPostingFilter {
+            employer_id: Some(ID("employer-1".to_string())),
+            city: Some("Marquette".to_string()),
+            job_type: Some(JobTypeOption::PartTime),
+            ..Default::default()
+        };
+
+        let query = filter.to_query(Some("job_category-1"), Utc::now()).unwrap();
+        let attributes = filtered_attributes(&query);
+
+        assert!(attributes.contains(&"city"));
+        assert!(attributes.contains(&"job_type"));
+        assert!(attributes.contains(&"category_ids"));
+        assert!(!attributes.contains(&"employer_id"));
+        assert!(attributes.contains(&STATUS_ATTRIBUTE), "listings only include live postings");
+    }
+}
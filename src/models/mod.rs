@@ -1,5 +1,13 @@
 pub mod address;
+pub mod coordinates;
+pub mod employer;
+pub mod job_application;
+pub mod job_category;
 pub mod job_posting;
 pub mod pay;
+pub mod refresh_token;
+pub mod timestamp;
+pub mod user;
+pub mod work_arrangement;
 
 pub mod prelude;
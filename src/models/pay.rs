@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::{ collections::HashMap, fmt, str::FromStr };
 
-use async_graphql::{ Enum, InputObject };
+use async_graphql::{ Enum, InputObject, MaybeUndefined, SimpleObject };
 use aws_sdk_dynamodb::types::AttributeValue;
+use rust_decimal::Decimal;
 use serde::{ Deserialize, Serialize };
 
-use crate::AppError;
+use crate::{ models::job_posting::ExpectedHoursRange, AppError };
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -16,23 +17,20 @@ pub enum CadenceOption {
     Year,
 }
 
-impl CadenceOption {
-    pub(crate) fn to_string(&self) -> String {
-        match self {
-            &CadenceOption::Hour => "HOUR".to_string(),
-            &CadenceOption::Day => "DAY".to_string(),
-            &CadenceOption::Week => "WEEK".to_string(),
-            &CadenceOption::Month => "MONTH".to_string(),
-            &CadenceOption::Year => "YEAR".to_string(),
-        }
+impl fmt::Display for CadenceOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
-    pub(crate) fn to_str(&self) -> &str {
+}
+
+impl CadenceOption {
+    pub(crate) fn as_str(self) -> &'static str {
         match self {
-            &CadenceOption::Hour => "HOUR",
-            &CadenceOption::Day => "DAY",
-            &CadenceOption::Week => "WEEK",
-            &CadenceOption::Month => "MONTH",
-            &CadenceOption::Year => "YEAR",
+            CadenceOption::Hour => "HOUR",
+            CadenceOption::Day => "DAY",
+            CadenceOption::Week => "WEEK",
+            CadenceOption::Month => "MONTH",
+            CadenceOption::Year => "YEAR",
         }
     }
     pub(crate) fn from_string(s: &str) -> Result<CadenceOption, AppError> {
@@ -52,23 +50,76 @@ impl CadenceOption {
     }
 }
 
+/// Active ISO 4217 currency codes.
+const CURRENCY_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VED", "VES", "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+/// Currency assumed for pay stored before currencies were recorded.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// Working weeks in a year, used to annualize hourly, daily and weekly pay.
+const WEEKS_PER_YEAR: u32 = 52;
+/// Length of a working day, used to turn weekly hours into days.
+const HOURS_PER_DAY: u32 = 8;
+
+/// Largest pay amount accepted, per cadence. Keeps annualized pay well within
+/// the range of `Decimal`.
+const MAX_AMOUNT: i64 = 1_000_000_000_000;
+
+/// Upper-cases a currency code, so `usd` and `USD` are stored alike.
+pub fn normalize_currency(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+/// Pay offered for a job, per `cadence`. `max` is absent for a single amount.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Pay {
     pub cadence: CadenceOption,
-    pub min_base_pay: u32,
+    pub min: Decimal,
+    pub max: Option<Decimal>,
+    // ISO 4217 code
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, InputObject)]
 pub struct PayInput {
     pub cadence: CadenceOption,
-    pub min_base_pay: u32,
+    pub min: Decimal,
+    pub max: Option<Decimal>,
+    /// ISO 4217 code, e.g. `USD`
+    pub currency: String,
+}
+
+/// Partial update for pay. Omitted fields are left untouched, and `max` can
+/// be cleared with an explicit `null`. Adding pay to a posting without any
+/// needs `cadence`, `min` and `currency`.
+#[derive(Clone, Debug, Default, InputObject)]
+pub struct PayPatchInput {
+    pub cadence: Option<CadenceOption>,
+    pub min: Option<Decimal>,
+    pub max: MaybeUndefined<Decimal>,
+    pub currency: Option<String>,
 }
 
 impl From<PayInput> for Pay {
     fn from(input: PayInput) -> Self {
         Self {
             cadence: input.cadence,
-            min_base_pay: input.min_base_pay,
+            min: input.min,
+            max: input.max,
+            currency: normalize_currency(&input.currency),
         }
     }
 }
@@ -77,34 +128,170 @@ impl From<Pay> for PayInput {
     fn from(pay: Pay) -> Self {
         Self {
             cadence: pay.cadence,
-            min_base_pay: pay.min_base_pay,
+            min: pay.min,
+            max: pay.max,
+            currency: pay.currency,
         }
     }
 }
 
+/// A pay range per year, as computed by [`Pay::annualized`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct AnnualPayRange {
+    pub min: Decimal,
+    pub max: Decimal,
+    pub currency: String,
+}
+
 impl Pay {
-    pub fn new(cadence: String, min_base_pay: u32) -> Result<Self, AppError> {
-        let cadence_option = CadenceOption::from_string(&cadence)?;
+    pub fn new(
+        cadence: CadenceOption,
+        min: Decimal,
+        max: Option<Decimal>,
+        currency: &str
+    ) -> Result<Self, AppError> {
+        let pay = Self {
+            cadence,
+            min,
+            max,
+            currency: normalize_currency(currency),
+        };
+        pay.validate()?;
+
+        Ok(pay)
+    }
+
+    /// Merges `patch` into `current`, or builds new pay from it when there is
+    /// none. The result is not validated.
+    pub fn patched(current: Option<Pay>, patch: PayPatchInput) -> Result<Self, AppError> {
+        let mut pay = match current {
+            Some(pay) => pay,
+            None => {
+                let (Some(cadence), Some(min), Some(currency)) = (
+                    patch.cadence,
+                    patch.min,
+                    &patch.currency,
+                ) else {
+                    return Err(
+                        AppError::ValidationError(
+                            "New pay needs a cadence, a minimum and a currency".to_string()
+                        )
+                    );
+                };
+
+                Self {
+                    cadence,
+                    min,
+                    max: None,
+                    currency: normalize_currency(currency),
+                }
+            }
+        };
+
+        if let Some(cadence) = patch.cadence {
+            pay.cadence = cadence;
+        }
+        if let Some(min) = patch.min {
+            pay.min = min;
+        }
+        patch.max.update_to(&mut pay.max);
+        if let Some(currency) = patch.currency {
+            pay.currency = normalize_currency(&currency);
+        }
+
+        Ok(pay)
+    }
+
+    /// Checks the amounts and that the currency is an ISO 4217 code.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.min.is_sign_negative() {
+            return Err(AppError::ValidationError("Pay cannot be negative".to_string()));
+        }
+        if self.max.unwrap_or(self.min) > Decimal::from(MAX_AMOUNT) {
+            return Err(
+                AppError::ValidationError(format!("Pay cannot exceed {}", MAX_AMOUNT))
+            );
+        }
+        if self.max.is_some_and(|max| max < self.min) {
+            return Err(
+                AppError::ValidationError("Maximum pay cannot be below minimum pay".to_string())
+            );
+        }
+
+        if !CURRENCY_CODES.contains(&self.currency.as_str()) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Unknown ISO 4217 currency code: {}", self.currency)
+                )
+            );
+        }
 
-        Ok(Self {
-            cadence: cadence_option,
-            min_base_pay,
+        Ok(())
+    }
+
+    /// Converts the pay to a yearly range.
+    ///
+    /// Hourly and daily pay are scaled by the posting's expected weekly hours,
+    /// with an eight-hour day, so the range spans the fewest to the most hours
+    /// worked. Weekly, monthly and yearly pay do not depend on hours. `None` if
+    /// the amounts are too large to annualize, which [`Pay::validate`] rules
+    /// out.
+    pub fn annualized(&self, expected_hours: &ExpectedHoursRange) -> Option<AnnualPayRange> {
+        let per_year = |hours_per_week: u8| -> Decimal {
+            let hours_per_week = Decimal::from(hours_per_week);
+
+            match self.cadence {
+                CadenceOption::Hour => hours_per_week * Decimal::from(WEEKS_PER_YEAR),
+                CadenceOption::Day => {
+                    (hours_per_week / Decimal::from(HOURS_PER_DAY)) * Decimal::from(WEEKS_PER_YEAR)
+                }
+                CadenceOption::Week => Decimal::from(WEEKS_PER_YEAR),
+                CadenceOption::Month => Decimal::from(12),
+                CadenceOption::Year => Decimal::ONE,
+            }
+        };
+
+        let max = self.max.unwrap_or(self.min);
+
+        Some(AnnualPayRange {
+            min: self.min.checked_mul(per_year(expected_hours.min))?.round_dp(2).normalize(),
+            max: max.checked_mul(per_year(expected_hours.max))?.round_dp(2).normalize(),
+            currency: self.currency.clone(),
         })
     }
 
     pub(crate) fn from_attribute_value(av: &AttributeValue) -> Option<Self> {
         if let AttributeValue::M(item) = av {
             let cadence_string = item.get("cadence")?.as_s().ok()?;
-            let cadence = CadenceOption::from_string(&cadence_string)
-                .map_err(|e| e)
-                .ok()?;
+            let cadence = CadenceOption::from_string(cadence_string).ok()?;
 
-            let min_base_pay_string = item.get("min_base_pay")?.as_s().ok()?;
-            let min_base_pay = min_base_pay_string.parse::<u32>().ok()?;
+            let decimal = |name: &str| {
+                item.get(name)
+                    .and_then(|v| v.as_n().ok())
+                    .and_then(|n| Decimal::from_str(n).ok())
+            };
+
+            // Pay stored before ranges existed only has a whole `min_base_pay`
+            let min = decimal("min").or_else(|| {
+                item.get("min_base_pay")?
+                    .as_s()
+                    .ok()?
+                    .parse::<u32>()
+                    .ok()
+                    .map(Decimal::from)
+            })?;
+
+            let currency = item
+                .get("currency")
+                .and_then(|v| v.as_s().ok())
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
 
             Some(Self {
                 cadence,
-                min_base_pay,
+                min,
+                max: decimal("max"),
+                currency,
             })
         } else {
             None
@@ -115,7 +302,13 @@ impl Pay {
         let mut item = HashMap::new();
 
         item.insert("cadence".to_string(), AttributeValue::S(self.cadence.to_string()));
-        item.insert("min_base_pay".to_string(), AttributeValue::S(self.min_base_pay.to_string()));
+        item.insert("min".to_string(), AttributeValue::N(self.min.normalize().to_string()));
+
+        if let Some(max) = &self.max {
+            item.insert("max".to_string(), AttributeValue::N(max.normalize().to_string()));
+        }
+
+        item.insert("currency".to_string(), AttributeValue::S(self.currency.clone()));
 
         AttributeValue::M(item)
     }
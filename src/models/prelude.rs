@@ -1,6 +1,10 @@
 pub use super::address::Address;
 pub use super::pay::Pay;
+pub use super::employer::Employer;
 pub use super::job_posting::JobPosting;
+pub use super::job_application::JobApplication;
+pub use super::job_category::JobCategory;
+pub use super::user::User;
 
 
 pub use async_graphql::{ Context, Object, Error, InputObject };
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::auth_tables::TOKEN_FAMILY_INDEX,
+    models::timestamp,
+    repository::ItemQuery,
+    DynamoDbEntity,
+};
+
+/// A refresh token issued to a session.
+///
+/// Only a hash of the token is stored, as the id. Each refresh replaces the
+/// presented token with a new one in the same `family_id`, so presenting a
+/// token that was already rotated away reveals that it leaked and the whole
+/// family can be revoked.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RefreshToken {
+    // Hash of the token value
+    pub id: String,
+    pub user_id: String,
+    // Shared by every token descended from the same login
+    pub family_id: String,
+    pub revoked: bool,
+    pub expires_at: DateTime<Utc>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RefreshToken {
+    pub fn new(id: String, user_id: String, family_id: String, lifetime: Duration) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id,
+            user_id,
+            family_id,
+            revoked: false,
+            expires_at: now + lifetime,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+
+    pub fn revoke(&mut self) {
+        self.revoked = true;
+        self.updated_at = Utc::now();
+    }
+
+    /// Every token in a family, through `TokenFamilyIndex`.
+    pub fn by_family_query(family_id: &str) -> ItemQuery {
+        ItemQuery::index(TOKEN_FAMILY_INDEX, "family_id", AttributeValue::S(family_id.to_string()))
+    }
+}
+
+impl DynamoDbEntity for RefreshToken {
+    fn table_name() -> &'static str {
+        "RefreshTokens"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let user_id = item.get("user_id")?.as_s().ok()?.to_string();
+        let family_id = item.get("family_id")?.as_s().ok()?.to_string();
+
+        let revoked = item
+            .get("revoked")
+            .and_then(|v| v.as_bool().ok())
+            .copied()
+            .unwrap_or(false);
+
+        let expires_at = item.get("expires_at").and_then(timestamp::from_attribute_value)?;
+
+        let created_at = item
+            .get("created_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        Some(Self {
+            id,
+            user_id,
+            family_id,
+            revoked,
+            expires_at,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("user_id".to_string(), AttributeValue::S(self.user_id.clone()));
+        // TokenFamilyIndex is keyed on family_id
+        item.insert("family_id".to_string(), AttributeValue::S(self.family_id.clone()));
+        item.insert("revoked".to_string(), AttributeValue::Bool(self.revoked));
+        item.insert("expires_at".to_string(), timestamp::to_attribute_value(&self.expires_at));
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        item
+    }
+}
@@ -0,0 +1,50 @@
+//! Timestamp encoding shared by every entity.
+//!
+//! Timestamps are stored as RFC 3339 strings in UTC with a fixed microsecond
+//! precision, so they sort lexicographically in the same order as in time and
+//! can be used as DynamoDB sort keys. Older items written with
+//! `DateTime::to_string` still parse.
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, SecondsFormat, Utc };
+
+pub fn format(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+pub(crate) fn to_attribute_value(timestamp: &DateTime<Utc>) -> AttributeValue {
+    AttributeValue::S(format(timestamp))
+}
+
+pub(crate) fn from_attribute_value(av: &AttributeValue) -> Option<DateTime<Utc>> {
+    av.as_s()
+        .ok()
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatted_timestamps_sort_in_time_order() {
+        let earlier: DateTime<Utc> = "2026-03-01T09:59:59.999999Z".parse().unwrap();
+        let later: DateTime<Utc> = "2026-03-01T10:00:00Z".parse().unwrap();
+
+        assert_eq!(format(&later), "2026-03-01T10:00:00.000000Z");
+        assert!(format(&earlier) < format(&later));
+    }
+
+    #[test]
+    fn legacy_and_current_encodings_parse() {
+        let expected: DateTime<Utc> = "2026-03-01T10:00:00Z".parse().unwrap();
+
+        for encoded in ["2026-03-01T10:00:00.000000Z", "2026-03-01 10:00:00 UTC"] {
+            let av = AttributeValue::S(encoded.to_string());
+            assert_eq!(from_attribute_value(&av), Some(expected), "{}", encoded);
+        }
+
+        assert_eq!(from_attribute_value(&to_attribute_value(&expected)), Some(expected));
+        assert_eq!(from_attribute_value(&AttributeValue::N("1".to_string())), None);
+    }
+}
@@ -0,0 +1,250 @@
+use std::{ collections::HashMap, fmt };
+
+use async_graphql::Enum;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Duration, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    db::auth_tables::EMAIL_INDEX,
+    models::{ job_application::{ normalize_email, validate_email }, timestamp },
+    repository::ItemQuery,
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+/// Consecutive failed logins after which an account is locked.
+pub const MAX_FAILED_LOGINS: u32 = 5;
+
+/// How long an account stays locked once [`MAX_FAILED_LOGINS`] is reached.
+pub const LOCKOUT_DURATION: Duration = Duration::minutes(15);
+
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    JobSeeker,
+    // Manages the postings of one employer
+    EmployerMember,
+    // Also manages which users belong to that employer
+    EmployerAdmin,
+    SiteAdmin,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Role {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Role::JobSeeker => "JOB_SEEKER",
+            Role::EmployerMember => "EMPLOYER_MEMBER",
+            Role::EmployerAdmin => "EMPLOYER_ADMIN",
+            Role::SiteAdmin => "SITE_ADMIN",
+        }
+    }
+
+    /// Whether holding `self` grants everything `other` may do.
+    ///
+    /// Site admins can do anything, employer admins can do what members can,
+    /// and every account can act as a job seeker.
+    pub fn includes(self, other: Role) -> bool {
+        use Role::*;
+
+        matches!(
+            (self, other),
+            (SiteAdmin, _) |
+                (EmployerAdmin, EmployerAdmin | EmployerMember | JobSeeker) |
+                (EmployerMember, EmployerMember | JobSeeker) |
+                (JobSeeker, JobSeeker)
+        )
+    }
+
+    pub(crate) fn from_string(s: &str) -> Result<Role, AppError> {
+        match s {
+            "JOB_SEEKER" => Ok(Self::JobSeeker),
+            "EMPLOYER_MEMBER" => Ok(Self::EmployerMember),
+            "EMPLOYER_ADMIN" => Ok(Self::EmployerAdmin),
+            "SITE_ADMIN" => Ok(Self::SiteAdmin),
+            _ => Err(AppError::DatabaseError("Cannot perform from_string on Role input".to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    // Stored lowercased so EmailIndex lookups are case-insensitive
+    pub email: String,
+    // argon2id PHC string; never exposed through GraphQL
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub role: Role,
+    // The employer an employer member or admin acts for
+    pub employer_id: Option<String>,
+    // Reset on every successful login
+    pub failed_login_attempts: u32,
+    pub locked_until: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn new(id: String, email: String, password_hash: String) -> Result<Self, AppError> {
+        let now = Utc::now();
+
+        let user = Self {
+            id,
+            email: normalize_email(&email),
+            password_hash,
+            role: Role::JobSeeker,
+            employer_id: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        validate_email(&user.email).map_err(AppError::ValidationError)?;
+
+        Ok(user)
+    }
+
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    /// Counts a failed login, locking the account once the limit is reached.
+    pub fn record_failed_login(&mut self, now: DateTime<Utc>) {
+        self.failed_login_attempts += 1;
+
+        if self.failed_login_attempts >= MAX_FAILED_LOGINS {
+            self.failed_login_attempts = 0;
+            self.locked_until = Some(now + LOCKOUT_DURATION);
+        }
+
+        self.updated_at = now;
+    }
+
+    /// Clears the failure count. Returns whether anything changed, so callers
+    /// can skip the write on the common path.
+    pub fn record_successful_login(&mut self, now: DateTime<Utc>) -> bool {
+        if self.failed_login_attempts == 0 && self.locked_until.is_none() {
+            return false;
+        }
+
+        self.failed_login_attempts = 0;
+        self.locked_until = None;
+        self.updated_at = now;
+
+        true
+    }
+
+    /// Id of the item in the Users table that reserves this user's email, so
+    /// no two accounts can register it. See [`Repository::create_claimed`].
+    pub fn email_claim_id(&self) -> String {
+        format!("email#{}", self.email)
+    }
+
+    /// Users with this email, through `EmailIndex`.
+    pub fn by_email_query(email: &str) -> ItemQuery {
+        ItemQuery::index(EMAIL_INDEX, "email", AttributeValue::S(normalize_email(email)))
+    }
+
+    pub async fn find_by_email(repo: &Repository, email: &str) -> Result<Option<Self>, AppError> {
+        let page = repo.query::<Self>(&Self::by_email_query(email), 1, None).await?;
+
+        Ok(page.into_entities().into_iter().next())
+    }
+}
+
+impl DynamoDbEntity for User {
+    fn table_name() -> &'static str {
+        "Users"
+    }
+
+    fn primary_key(&self) -> String {
+        self.id.clone()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let id = item.get("id")?.as_s().ok()?.to_string();
+        let email = item.get("email")?.as_s().ok()?.to_string();
+        let password_hash = item.get("password_hash")?.as_s().ok()?.to_string();
+
+        // Accounts created before roles existed are job seekers
+        let role = item
+            .get("user_role")
+            .and_then(|v| v.as_s().ok())
+            .and_then(|s| Role::from_string(s).ok())
+            .unwrap_or_default();
+
+        let employer_id = item
+            .get("employer_id")
+            .and_then(|v| v.as_s().ok())
+            .map(|s| s.to_string());
+
+        let failed_login_attempts = item
+            .get("failed_login_attempts")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0);
+
+        let locked_until = item.get("locked_until").and_then(timestamp::from_attribute_value);
+
+        let created_at = item
+            .get("created_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        let updated_at = item
+            .get("updated_at")
+            .and_then(timestamp::from_attribute_value)
+            .unwrap_or_else(Utc::now);
+
+        Some(Self {
+            id,
+            email,
+            password_hash,
+            role,
+            employer_id,
+            failed_login_attempts,
+            locked_until,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        // EmailIndex is keyed on email
+        item.insert("email".to_string(), AttributeValue::S(self.email.clone()));
+        item.insert("password_hash".to_string(), AttributeValue::S(self.password_hash.clone()));
+        item.insert("user_role".to_string(), AttributeValue::S(self.role.to_string()));
+
+        if let Some(employer_id) = &self.employer_id {
+            item.insert("employer_id".to_string(), AttributeValue::S(employer_id.clone()));
+        }
+
+        item.insert(
+            "failed_login_attempts".to_string(),
+            AttributeValue::N(self.failed_login_attempts.to_string())
+        );
+
+        if let Some(locked_until) = &self.locked_until {
+            item.insert("locked_until".to_string(), timestamp::to_attribute_value(locked_until));
+        }
+
+        item.insert("created_at".to_string(), timestamp::to_attribute_value(&self.created_at));
+        item.insert("updated_at".to_string(), timestamp::to_attribute_value(&self.updated_at));
+
+        item
+    }
+}
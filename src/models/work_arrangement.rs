@@ -0,0 +1,276 @@
+use std::{ collections::HashMap, fmt };
+
+use async_graphql::{ Enum, InputObject };
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{ Deserialize, Serialize };
+
+use crate::AppError;
+
+/// Where the work is done. Independent of the employment type, so a remote
+/// full-time job is `FULL_TIME` with a `REMOTE` arrangement.
+#[derive(Enum, Copy, Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkArrangementType {
+    // Postings stored before arrangements existed are on-site, unless their
+    // job type was REMOTE
+    #[default]
+    OnSite,
+    Hybrid,
+    Remote,
+}
+
+impl fmt::Display for WorkArrangementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl WorkArrangementType {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            WorkArrangementType::OnSite => "ON_SITE",
+            WorkArrangementType::Hybrid => "HYBRID",
+            WorkArrangementType::Remote => "REMOTE",
+        }
+    }
+    pub(crate) fn from_string(s: &str) -> Result<WorkArrangementType, AppError> {
+        match s {
+            "ON_SITE" => Ok(Self::OnSite),
+            "HYBRID" => Ok(Self::Hybrid),
+            "REMOTE" => Ok(Self::Remote),
+            _ =>
+                Err(
+                    AppError::DatabaseError(
+                        "Cannot perform from_string on WorkArrangementType input".to_string()
+                    )
+                ),
+        }
+    }
+}
+
+/// Largest offsets in use: UTC-12:00 (Baker Island) to UTC+14:00 (Line Islands).
+const MIN_UTC_OFFSET: i32 = -12 * 60;
+const MAX_UTC_OFFSET: i32 = 14 * 60;
+
+/// Parses a UTC offset such as `+05:30`, `-08:00`, `UTC+1` or `Z` into minutes.
+pub fn parse_utc_offset(s: &str) -> Result<i32, AppError> {
+    let invalid = || AppError::ValidationError(format!("Invalid UTC offset: {}", s));
+
+    let trimmed = s.trim();
+    let offset = trimmed.strip_prefix("UTC").unwrap_or(trimmed);
+
+    if offset.is_empty() || offset == "Z" {
+        return Ok(0);
+    }
+
+    let (sign, rest) = if let Some(rest) = offset.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = offset.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        return Err(invalid());
+    };
+
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours = hours.parse::<i32>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<i32>().map_err(|_| invalid())?;
+
+    if !(0..60).contains(&minutes) {
+        return Err(invalid());
+    }
+
+    let offset = sign * (hours * 60 + minutes);
+
+    if !(MIN_UTC_OFFSET..=MAX_UTC_OFFSET).contains(&offset) {
+        return Err(invalid());
+    }
+
+    Ok(offset)
+}
+
+/// Formats minutes east of UTC as `+05:30`.
+pub fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// The time zones remote staff may work from, as an inclusive range of UTC
+/// offsets in minutes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtcOffsetRange {
+    pub earliest: i32,
+    pub latest: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, InputObject)]
+pub struct UtcOffsetRangeInput {
+    /// Westernmost allowed offset, e.g. `-08:00`
+    pub earliest: String,
+    /// Easternmost allowed offset, e.g. `+01:00`
+    pub latest: String,
+}
+
+impl TryFrom<UtcOffsetRangeInput> for UtcOffsetRange {
+    type Error = AppError;
+
+    fn try_from(input: UtcOffsetRangeInput) -> Result<Self, Self::Error> {
+        Ok(Self {
+            earliest: parse_utc_offset(&input.earliest)?,
+            latest: parse_utc_offset(&input.latest)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkArrangement {
+    pub arrangement_type: WorkArrangementType,
+    // ISO 3166-1 alpha-2 codes remote staff may live in; empty for anywhere
+    pub countries: Vec<String>,
+    // Time zones remote staff may work from; None for any
+    pub utc_offsets: Option<UtcOffsetRange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, InputObject)]
+pub struct WorkArrangementInput {
+    #[graphql(name = "type")]
+    pub arrangement_type: WorkArrangementType,
+    /// Countries remote staff may live in, as ISO 3166-1 alpha-2 codes. Only
+    /// for `REMOTE`; leave out to allow any country.
+    pub countries: Option<Vec<String>>,
+    /// Time zones remote staff may work from. Only for `REMOTE`; leave out to
+    /// allow any.
+    pub utc_offsets: Option<UtcOffsetRangeInput>,
+}
+
+impl TryFrom<WorkArrangementInput> for WorkArrangement {
+    type Error = AppError;
+
+    fn try_from(input: WorkArrangementInput) -> Result<Self, Self::Error> {
+        let work_arrangement = Self {
+            arrangement_type: input.arrangement_type,
+            countries: input.countries
+                .unwrap_or_default()
+                .iter()
+                .map(|country| country.trim().to_ascii_uppercase())
+                .collect(),
+            utc_offsets: input.utc_offsets.map(UtcOffsetRange::try_from).transpose()?,
+        };
+        work_arrangement.validate()?;
+
+        Ok(work_arrangement)
+    }
+}
+
+impl WorkArrangement {
+    /// A fully remote arrangement open to any country and time zone.
+    pub fn remote() -> Self {
+        Self {
+            arrangement_type: WorkArrangementType::Remote,
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        let restricted = !self.countries.is_empty() || self.utc_offsets.is_some();
+
+        if restricted && self.arrangement_type != WorkArrangementType::Remote {
+            return Err(
+                AppError::ValidationError(
+                    "Countries and time zones can only be restricted for remote work".to_string()
+                )
+            );
+        }
+
+        if let Some(country) = self.countries.iter().find(|country| !is_country_code(country)) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Invalid ISO 3166-1 alpha-2 country code: {}", country)
+                )
+            );
+        }
+
+        if self.utc_offsets.is_some_and(|range| range.earliest > range.latest) {
+            return Err(
+                AppError::ValidationError(
+                    "The earliest UTC offset must not be after the latest".to_string()
+                )
+            );
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_attribute_value(&self) -> AttributeValue {
+        let mut item = HashMap::new();
+
+        item.insert("type".to_string(), AttributeValue::S(self.arrangement_type.to_string()));
+
+        if !self.countries.is_empty() {
+            item.insert(
+                "countries".to_string(),
+                AttributeValue::L(
+                    self.countries
+                        .iter()
+                        .map(|country| AttributeValue::S(country.clone()))
+                        .collect()
+                )
+            );
+        }
+
+        if let Some(utc_offsets) = &self.utc_offsets {
+            item.insert(
+                "utc_offset_earliest".to_string(),
+                AttributeValue::N(utc_offsets.earliest.to_string())
+            );
+            item.insert(
+                "utc_offset_latest".to_string(),
+                AttributeValue::N(utc_offsets.latest.to_string())
+            );
+        }
+
+        AttributeValue::M(item)
+    }
+
+    pub(crate) fn from_attribute_value(av: &AttributeValue) -> Option<Self> {
+        if let AttributeValue::M(item) = av {
+            let arrangement_type_string = item.get("type")?.as_s().ok()?;
+            let arrangement_type = WorkArrangementType::from_string(arrangement_type_string).ok()?;
+
+            let countries = item
+                .get("countries")
+                .and_then(|v| v.as_l().ok())
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|av|
+                            av
+                                .as_s()
+                                .ok()
+                                .map(|s| s.to_string())
+                        )
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let offset = |name: &str| item.get(name)?.as_n().ok()?.parse::<i32>().ok();
+
+            let utc_offsets = match (offset("utc_offset_earliest"), offset("utc_offset_latest")) {
+                (Some(earliest), Some(latest)) => Some(UtcOffsetRange { earliest, latest }),
+                _ => None,
+            };
+
+            Some(Self {
+                arrangement_type,
+                countries,
+                utc_offsets,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn is_country_code(code: &str) -> bool {
+    code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase())
+}
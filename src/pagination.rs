@@ -0,0 +1,217 @@
+//! Cursor-based pagination primitives.
+//!
+//! DynamoDB pages through a table or index with an `ExclusiveStartKey` and hands
+//! back a `LastEvaluatedKey` when more items remain. A [`Cursor`] wraps such a key
+//! and encodes it as an opaque, URL-safe string so it can travel through GraphQL.
+
+use std::collections::HashMap;
+
+use async_graphql::{ connection::{ Connection, CursorType, Edge }, OutputType };
+use aws_sdk_dynamodb::types::AttributeValue;
+use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+use serde_json::{ Map, Value };
+
+use crate::AppError;
+
+/// Default page size when the caller does not ask for one.
+pub const DEFAULT_PAGE_SIZE: i32 = 20;
+
+/// Upper bound on a single page, regardless of what the caller asks for.
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+/// Clamps a requested page size into `1..=MAX_PAGE_SIZE`.
+pub fn page_size(first: Option<i32>) -> i32 {
+    first.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Decodes an optional `after` argument.
+pub fn parse_after(after: Option<&str>) -> Result<Option<Cursor>, AppError> {
+    after.map(Cursor::decode).transpose()
+}
+
+/// An opaque pointer to a position in a table or index.
+///
+/// Only string and number key attributes are supported, which covers every key
+/// schema defined in `db::job_posting_tables`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor {
+    key: HashMap<String, AttributeValue>,
+}
+
+impl Cursor {
+    pub fn from_key(key: HashMap<String, AttributeValue>) -> Self {
+        Self { key }
+    }
+
+    /// Builds a cursor pointing at `item`, keeping only the given key attributes.
+    pub fn from_item(item: &HashMap<String, AttributeValue>, key_attributes: &[&str]) -> Self {
+        let key = key_attributes
+            .iter()
+            .filter_map(|name| item.get(*name).map(|v| (name.to_string(), v.clone())))
+            .collect();
+
+        Self { key }
+    }
+
+    pub fn into_key(self) -> HashMap<String, AttributeValue> {
+        self.key
+    }
+
+    pub fn encode(&self) -> String {
+        let mut map = Map::new();
+
+        for (name, value) in &self.key {
+            let encoded = match value {
+                AttributeValue::S(s) => serde_json::json!({ "S": s }),
+                AttributeValue::N(n) => serde_json::json!({ "N": n }),
+                _ => {
+                    continue;
+                }
+            };
+            map.insert(name.clone(), encoded);
+        }
+
+        URL_SAFE_NO_PAD.encode(Value::Object(map).to_string())
+    }
+
+    pub fn decode(s: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::ValidationError("Invalid pagination cursor".to_string());
+
+        let bytes = URL_SAFE_NO_PAD.decode(s).map_err(|_| invalid())?;
+        let value: Value = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        let map = value.as_object().ok_or_else(invalid)?;
+
+        let mut key = HashMap::new();
+
+        for (name, typed) in map {
+            let typed = typed.as_object().ok_or_else(invalid)?;
+            let av = match (typed.get("S"), typed.get("N")) {
+                (Some(Value::String(s)), None) => AttributeValue::S(s.clone()),
+                (None, Some(Value::String(n))) => AttributeValue::N(n.clone()),
+                _ => {
+                    return Err(invalid());
+                }
+            };
+            key.insert(name.clone(), av);
+        }
+
+        if key.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self { key })
+    }
+}
+
+impl CursorType for Cursor {
+    type Error = AppError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        Cursor::decode(s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        self.encode()
+    }
+}
+
+/// A single page of entities returned from the repository.
+///
+/// Every entity is paired with a cursor pointing at it, so a client can resume
+/// from any edge. `next_cursor` is set when DynamoDB reports more items.
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    pub items: Vec<(Cursor, T)>,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    pub fn has_next_page(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+
+    /// Converts this page into a Relay connection. `has_previous_page` should be
+    /// set when the page was requested with an `after` cursor.
+    pub fn into_connection(self, has_previous_page: bool) -> Connection<Cursor, T>
+        where T: OutputType
+    {
+        let mut connection = Connection::new(has_previous_page, self.next_cursor.is_some());
+        connection.edges.extend(
+            self.items.into_iter().map(|(cursor, entity)| Edge::new(cursor, entity))
+        );
+        connection
+    }
+
+    pub fn into_entities(self) -> Vec<T> {
+        self.items
+            .into_iter()
+            .map(|(_, entity)| entity)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S("job_posting-1".to_string())),
+            ("created_at".to_string(), AttributeValue::S("2026-03-01T10:00:00.000000Z".to_string())),
+            ("version".to_string(), AttributeValue::N("7".to_string())),
+        ])
+    }
+
+    #[test]
+    fn cursors_round_trip_through_their_encoding() {
+        let cursor = Cursor::from_key(key());
+        let encoded = cursor.encode();
+
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(Cursor::decode(&encoded).unwrap(), cursor);
+        assert_eq!(parse_after(Some(&encoded)).unwrap(), Some(cursor));
+        assert_eq!(parse_after(None).unwrap(), None);
+    }
+
+    #[test]
+    fn from_item_keeps_only_the_key_attributes() {
+        let mut item = key();
+        item.insert("job_title".to_string(), AttributeValue::S("Welder".to_string()));
+
+        let cursor = Cursor::from_item(&item, &["id", "created_at"]);
+        let key = cursor.into_key();
+
+        assert_eq!(key.len(), 2);
+        assert_eq!(key["id"], AttributeValue::S("job_posting-1".to_string()));
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        let encode = |json: &str| URL_SAFE_NO_PAD.encode(json);
+
+        for cursor in [
+            "not base64!".to_string(),
+            encode("not json"),
+            encode("[]"),
+            encode("{}"),
+            encode(r#"{ "id": "job_posting-1" }"#),
+            encode(r#"{ "id": { "S": "a", "N": "1" } }"#),
+            encode(r#"{ "id": { "BOOL": true } }"#),
+        ] {
+            assert!(
+                matches!(Cursor::decode(&cursor), Err(AppError::ValidationError(_))),
+                "{}",
+                cursor
+            );
+        }
+    }
+
+    #[test]
+    fn page_sizes_are_clamped() {
+        assert_eq!(page_size(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(-5)), 1);
+        assert_eq!(page_size(Some(50)), 50);
+        assert_eq!(page_size(Some(1000)), MAX_PAGE_SIZE);
+    }
+}
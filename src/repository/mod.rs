@@ -0,0 +1,315 @@
+use std::{ collections::HashMap, sync::Arc };
+use aws_sdk_dynamodb::{ Client, types::AttributeValue };
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{
+    pagination::{ Cursor, Page, MAX_PAGE_SIZE },
+    storage::{
+        Condition,
+        DynamoDbBackend,
+        InMemoryBackend,
+        PageRequest,
+        StorageBackend,
+        StorageError,
+        TransactPut,
+    },
+    AppError,
+};
+
+pub mod query;
+
+pub use query::{ Filter, FilterOp, ItemQuery, KeyCondition, SortKeyCondition, SortKeyOp };
+
+#[async_trait]
+pub trait DynamoDbEntity: Clone + Send + Sync {
+    fn table_name() -> &'static str;
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self>;
+    fn to_item(&self) -> HashMap<String, AttributeValue>;
+    fn primary_key(&self) -> String;
+}
+
+/// Entity-level access to storage.
+///
+/// Cheap to clone; every clone shares the same backend.
+#[derive(Clone)]
+pub struct Repository {
+    backend: Arc<dyn StorageBackend>,
+}
+
+impl Repository {
+    /// A repository backed by DynamoDB.
+    pub fn new(client: Client) -> Self {
+        Self::with_backend(DynamoDbBackend::new(client))
+    }
+
+    pub fn with_backend<B: StorageBackend + 'static>(backend: B) -> Self {
+        Self { backend: Arc::new(backend) }
+    }
+
+    /// A repository backed by a fresh [`InMemoryBackend`].
+    pub fn in_memory() -> Self {
+        Self::with_backend(InMemoryBackend::new())
+    }
+
+    pub fn backend(&self) -> &dyn StorageBackend {
+        self.backend.as_ref()
+    }
+
+    pub async fn get<T: DynamoDbEntity>(&self, id: String) -> Result<Option<T>, AppError> {
+        let item = self.backend
+            .get_item(T::table_name(), &id).await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to get item: {}", e)))?;
+
+        Ok(item.and_then(|item| T::from_item(&item)))
+    }
+
+    /// Fetches the entities with the given ids in as few round trips as the
+    /// backend allows, keyed by id. Missing ids are absent from the map.
+    pub async fn get_many<T: DynamoDbEntity>(
+        &self,
+        ids: &[String]
+    ) -> Result<HashMap<String, T>, AppError> {
+        let items = self.backend
+            .batch_get_items(T::table_name(), ids).await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to get items: {}", e)))?;
+
+        Ok(
+            items
+                .iter()
+                .filter_map(T::from_item)
+                .map(|entity| (entity.primary_key(), entity))
+                .collect()
+        )
+    }
+
+    pub async fn create<T: DynamoDbEntity>(&self, entity: T) -> Result<T, AppError> {
+        let item = entity.to_item();
+        info!("New {} item in repository: {:?}", T::table_name(), &item);
+
+        self.backend
+            .put_item_if(T::table_name(), item, Condition::AttributeNotExists("id".to_string())).await
+            .map_err(|e| {
+                match e {
+                    StorageError::ConditionFailed =>
+                        AppError::ValidationError("Entity with this ID already exists".to_string()),
+                    e => AppError::DatabaseError(format!("Failed to create entity: {}", e)),
+                }
+            })?;
+
+        Ok(entity)
+    }
+
+    /// Creates `entity` along with a claim on `claim_id`: an item in the same
+    /// table holding only that id and the entity's id. Both are written in one
+    /// transaction that fails if either id is taken, so the claim enforces a
+    /// unique value that secondary indexes, being eventually consistent,
+    /// cannot.
+    pub async fn create_claimed<T: DynamoDbEntity>(
+        &self,
+        entity: T,
+        claim_id: String
+    ) -> Result<T, AppError> {
+        let claim = HashMap::from([
+            ("id".to_string(), AttributeValue::S(claim_id)),
+            ("claimed_by".to_string(), AttributeValue::S(entity.primary_key())),
+        ]);
+
+        let puts = [entity.to_item(), claim]
+            .into_iter()
+            .map(|item| TransactPut {
+                table: T::table_name().to_string(),
+                item,
+                condition: Condition::AttributeNotExists("id".to_string()),
+            })
+            .collect();
+
+        self.backend.transact_put_items(puts).await.map_err(|e| {
+            match e {
+                StorageError::ConditionFailed =>
+                    AppError::ValidationError("Entity with this ID already exists".to_string()),
+                e => AppError::DatabaseError(format!("Failed to create entity: {}", e)),
+            }
+        })?;
+
+        Ok(entity)
+    }
+
+    /// Writes the entity whether or not it already exists.
+    pub async fn put<T: DynamoDbEntity>(&self, entity: T) -> Result<T, AppError> {
+        self.backend
+            .put_item(T::table_name(), entity.to_item()).await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to put entity: {}", e)))?;
+
+        Ok(entity)
+    }
+
+    /// Writes the entities in batches, whether or not they already exist. Not
+    /// atomic: if it fails, some of them may have been written.
+    pub async fn put_many<T: DynamoDbEntity>(&self, entities: &[T]) -> Result<(), AppError> {
+        let items = entities.iter().map(DynamoDbEntity::to_item).collect();
+
+        self.backend
+            .batch_put_items(T::table_name(), items).await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to put entities: {}", e)))
+    }
+
+    pub async fn update<T: DynamoDbEntity>(&self, entity: T) -> Result<T, AppError> {
+        let item = entity.to_item();
+
+        self.backend
+            .put_item_if(T::table_name(), item, Condition::AttributeExists("id".to_string())).await
+            .map_err(|e| {
+                match e {
+                    StorageError::ConditionFailed =>
+                        AppError::NotFound(
+                            format!("No {} item with id {}", T::table_name(), entity.primary_key())
+                        ),
+                    e => AppError::DatabaseError(format!("Failed to update entity: {}", e)),
+                }
+            })?;
+
+        Ok(entity)
+    }
+
+    /// Replaces an existing entity only if `attribute` still holds `expected`,
+    /// for optimistic concurrency on read-modify-write updates.
+    pub async fn update_if<T: DynamoDbEntity>(
+        &self,
+        entity: T,
+        attribute: &str,
+        expected: AttributeValue
+    ) -> Result<T, AppError> {
+        let item = entity.to_item();
+
+        self.backend
+            .put_item_if(
+                T::table_name(),
+                item,
+                Condition::AttributeEquals(attribute.to_string(), expected)
+            ).await
+            .map_err(|e| {
+                match e {
+                    StorageError::ConditionFailed =>
+                        AppError::ValidationError(
+                            format!(
+                                "{} item {} was modified concurrently; reload and retry",
+                                T::table_name(),
+                                entity.primary_key()
+                            )
+                        ),
+                    e => AppError::DatabaseError(format!("Failed to update entity: {}", e)),
+                }
+            })?;
+
+        Ok(entity)
+    }
+
+    pub async fn delete<T: DynamoDbEntity>(&self, id: String) -> Result<bool, AppError> {
+        self.backend
+            .delete_item(
+                T::table_name(),
+                &id,
+                Some(Condition::AttributeExists("id".to_string()))
+            ).await
+            .map_err(|e| {
+                match e {
+                    StorageError::ConditionFailed =>
+                        AppError::NotFound(format!("No {} item with id {}", T::table_name(), id)),
+                    e => AppError::DatabaseError(format!("Failed to delete entity: {}", e)),
+                }
+            })?;
+
+        Ok(true)
+    }
+
+    /// Deletes the entities with these ids in batches, ignoring ids that do
+    /// not exist. Not atomic: if it fails, some of them may have been deleted.
+    pub async fn delete_many<T: DynamoDbEntity>(&self, ids: Vec<String>) -> Result<(), AppError> {
+        self.backend
+            .batch_delete_items(T::table_name(), ids).await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to delete entities: {}", e)))
+    }
+
+    /// Scans a page of entities, resuming after `after` when given.
+    pub async fn list<T: DynamoDbEntity>(
+        &self,
+        limit: i32,
+        after: Option<Cursor>
+    ) -> Result<Page<T>, AppError> {
+        self.query(&ItemQuery::scan(), limit, after).await
+    }
+
+    /// Runs `query` and reads every matching entity, page by page.
+    pub async fn query_all<T: DynamoDbEntity>(&self, query: &ItemQuery) -> Result<Vec<T>, AppError> {
+        let mut entities = Vec::new();
+        let mut after = None;
+
+        loop {
+            let page = self.query::<T>(query, MAX_PAGE_SIZE, after).await?;
+            after = page.next_cursor.clone();
+            entities.extend(page.into_entities());
+
+            if after.is_none() {
+                return Ok(entities);
+            }
+        }
+    }
+
+    /// Runs `query` and returns a page of matching entities.
+    ///
+    /// Queries with a key condition go to their index through the backend's
+    /// `query`; anything else is a filtered `scan`. DynamoDB may stop short of
+    /// `limit` (at the 1 MB page boundary, or because filters discarded items),
+    /// so this keeps reading until the page is full or the table is exhausted.
+    /// The returned cursor is the raw `LastEvaluatedKey`.
+    pub async fn query<T: DynamoDbEntity>(
+        &self,
+        query: &ItemQuery,
+        limit: i32,
+        after: Option<Cursor>
+    ) -> Result<Page<T>, AppError> {
+        let key_attributes = query.key_attributes();
+        let mut items = Vec::new();
+        let mut start_key = after.map(Cursor::into_key);
+
+        loop {
+            let page = PageRequest {
+                limit: Some(limit - (items.len() as i32)),
+                exclusive_start_key: start_key.take(),
+                segment: query.segment,
+            };
+
+            let result = (
+                match &query.key_condition {
+                    Some(key_condition) =>
+                        self.backend.query(
+                            T::table_name(),
+                            key_condition,
+                            &query.filters,
+                            query.descending,
+                            page
+                        ).await,
+                    None => self.backend.scan(T::table_name(), &query.filters, page).await,
+                }
+            )?;
+
+            for item in result.items {
+                if let Some(entity) = T::from_item(&item) {
+                    items.push((Cursor::from_item(&item, &key_attributes), entity));
+                }
+            }
+
+            start_key = result.last_evaluated_key;
+
+            if start_key.is_none() || (items.len() as i32) >= limit {
+                break;
+            }
+        }
+
+        Ok(Page {
+            items,
+            next_cursor: start_key.map(Cursor::from_key),
+        })
+    }
+}
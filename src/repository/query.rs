@@ -0,0 +1,283 @@
+//! Declarative description of a read against a table.
+//!
+//! An [`ItemQuery`] either targets a global secondary index through a key
+//! condition, in which case it is executed as a DynamoDB `Query`, or it has no
+//! key condition and falls back to a `Scan`. Filters are applied server-side in
+//! both cases.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use crate::storage::Segment;
+
+/// Partition key equality condition on a global secondary index, optionally
+/// narrowed by a condition on the index sort key.
+#[derive(Clone, Debug)]
+pub struct KeyCondition {
+    pub index_name: String,
+    pub partition_key: String,
+    pub value: AttributeValue,
+    /// Sort key attribute of the index, if it has one. Results are ordered by it.
+    pub sort_key_attribute: Option<String>,
+    pub sort_key: Option<SortKeyCondition>,
+}
+
+impl KeyCondition {
+    /// Attributes that make up a `LastEvaluatedKey` on this index.
+    pub(crate) fn key_attributes(&self) -> Vec<&str> {
+        let mut attributes = vec!["id", self.partition_key.as_str()];
+
+        if let Some(sort_key_attribute) = &self.sort_key_attribute {
+            attributes.push(sort_key_attribute);
+        }
+
+        attributes
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKeyOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    BeginsWith,
+}
+
+#[derive(Clone, Debug)]
+pub struct SortKeyCondition {
+    pub attribute: String,
+    pub op: SortKeyOp,
+    pub value: AttributeValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    /// The attribute is a list or set containing the value, or a string
+    /// containing it as a substring.
+    Contains,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A non-key condition on a top-level attribute.
+///
+/// Like in DynamoDB, a condition on a missing attribute is false, unless the
+/// filter is marked with [`Filter::or_missing`].
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pub attribute: String,
+    pub op: FilterOp,
+    pub value: AttributeValue,
+    /// Items without the attribute pass the filter.
+    pub or_missing: bool,
+}
+
+impl Filter {
+    fn new(attribute: &str, op: FilterOp, value: AttributeValue) -> Self {
+        Self {
+            attribute: attribute.to_string(),
+            op,
+            value,
+            or_missing: false,
+        }
+    }
+
+    pub fn eq(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Eq, value)
+    }
+
+    pub fn contains(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Contains, value)
+    }
+
+    /// The attribute is missing or differs from the value.
+    pub fn ne(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Ne, value).or_missing()
+    }
+
+    pub fn lt(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Lt, value)
+    }
+
+    pub fn le(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Le, value)
+    }
+
+    pub fn gt(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Gt, value)
+    }
+
+    pub fn ge(attribute: &str, value: AttributeValue) -> Self {
+        Self::new(attribute, FilterOp::Ge, value)
+    }
+
+    /// Lets items that do not have the attribute at all pass the filter.
+    pub fn or_missing(mut self) -> Self {
+        self.or_missing = true;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ItemQuery {
+    pub key_condition: Option<KeyCondition>,
+    pub filters: Vec<Filter>,
+    /// Return items in descending sort key order. Only meaningful for queries
+    /// against an index with a sort key.
+    pub descending: bool,
+    /// Part of the table to read. Only meaningful for scans.
+    pub segment: Option<Segment>,
+}
+
+impl ItemQuery {
+    /// A plain scan of the whole table.
+    pub fn scan() -> Self {
+        Self::default()
+    }
+
+    /// A `Query` against `index_name` for items whose `partition_key` equals `value`.
+    pub fn index(index_name: &str, partition_key: &str, value: AttributeValue) -> Self {
+        Self {
+            key_condition: Some(KeyCondition {
+                index_name: index_name.to_string(),
+                partition_key: partition_key.to_string(),
+                value,
+                sort_key_attribute: None,
+                sort_key: None,
+            }),
+            filters: Vec::new(),
+            descending: false,
+            segment: None,
+        }
+    }
+
+    /// Declares the sort key of the queried index. Has no effect on scans.
+    pub fn sorted_by(mut self, attribute: &str) -> Self {
+        if let Some(key_condition) = &mut self.key_condition {
+            key_condition.sort_key_attribute = Some(attribute.to_string());
+        }
+        self
+    }
+
+    /// Narrows an index query by its sort key. Has no effect on scans.
+    pub fn sort_key(mut self, attribute: &str, op: SortKeyOp, value: AttributeValue) -> Self {
+        if let Some(key_condition) = &mut self.key_condition {
+            key_condition.sort_key_attribute = Some(attribute.to_string());
+            key_condition.sort_key = Some(SortKeyCondition {
+                attribute: attribute.to_string(),
+                op,
+                value,
+            });
+        }
+        self
+    }
+
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Reads only segment `index` of `total`, so that `total` scans together
+    /// cover the table once. Has no effect on index queries.
+    pub fn segment(mut self, index: i32, total: i32) -> Self {
+        self.segment = Some(Segment { index, total });
+        self
+    }
+
+    /// Attributes that make up a `LastEvaluatedKey` for this query.
+    pub(crate) fn key_attributes(&self) -> Vec<&str> {
+        match &self.key_condition {
+            Some(key_condition) => key_condition.key_attributes(),
+            None => vec!["id"],
+        }
+    }
+}
+
+/// Expression attribute names and values accumulated while rendering a query.
+#[derive(Default)]
+pub(crate) struct ExpressionBuilder {
+    pub names: HashMap<String, String>,
+    pub values: HashMap<String, AttributeValue>,
+}
+
+impl ExpressionBuilder {
+    pub fn key_condition(&mut self, key_condition: &KeyCondition) -> String {
+        self.names.insert("#pk".to_string(), key_condition.partition_key.clone());
+        self.values.insert(":pk".to_string(), key_condition.value.clone());
+
+        let Some(sort_key) = &key_condition.sort_key else {
+            return "#pk = :pk".to_string();
+        };
+
+        self.names.insert("#sk".to_string(), sort_key.attribute.clone());
+        self.values.insert(":sk".to_string(), sort_key.value.clone());
+
+        let sort_key_expression = match sort_key.op {
+            SortKeyOp::Eq => "#sk = :sk",
+            SortKeyOp::Lt => "#sk < :sk",
+            SortKeyOp::Le => "#sk <= :sk",
+            SortKeyOp::Gt => "#sk > :sk",
+            SortKeyOp::Ge => "#sk >= :sk",
+            SortKeyOp::BeginsWith => "begins_with(#sk, :sk)",
+        };
+
+        format!("#pk = :pk AND {}", sort_key_expression)
+    }
+
+    pub fn filter_expression(&mut self, filters: &[Filter]) -> Option<String> {
+        if filters.is_empty() {
+            return None;
+        }
+
+        let clauses: Vec<String> = filters
+            .iter()
+            .enumerate()
+            .map(|(i, filter)| {
+                let name = format!("#f{}", i);
+                let value = format!(":f{}", i);
+
+                self.names.insert(name.clone(), filter.attribute.clone());
+                self.values.insert(value.clone(), filter.value.clone());
+
+                let clause = match filter.op {
+                    FilterOp::Eq => format!("{} = {}", name, value),
+                    FilterOp::Contains => format!("contains({}, {})", name, value),
+                    FilterOp::Ne => format!("{} <> {}", name, value),
+                    FilterOp::Lt => format!("{} < {}", name, value),
+                    FilterOp::Le => format!("{} <= {}", name, value),
+                    FilterOp::Gt => format!("{} > {}", name, value),
+                    FilterOp::Ge => format!("{} >= {}", name, value),
+                };
+
+                // Every comparison, `<>` included, is false on a missing attribute
+                if filter.or_missing {
+                    format!("(attribute_not_exists({}) OR {})", name, clause)
+                } else {
+                    clause
+                }
+            })
+            .collect();
+
+        Some(clauses.join(" AND "))
+    }
+
+    pub fn take_names(&mut self) -> Option<HashMap<String, String>> {
+        (!self.names.is_empty()).then(|| std::mem::take(&mut self.names))
+    }
+
+    pub fn take_values(&mut self) -> Option<HashMap<String, AttributeValue>> {
+        (!self.values.is_empty()).then(|| std::mem::take(&mut self.values))
+    }
+}
@@ -0,0 +1,31 @@
+//! Batched loaders for fields that would otherwise issue one read per parent.
+//!
+//! Loaders live in the schema data and are shared across requests, so they only
+//! batch; they do not cache, and every request sees current data.
+
+use std::collections::HashMap;
+
+use async_graphql::{ dataloader::Loader, Error };
+
+use crate::{ models::employer::Employer, Repository };
+
+/// Loads employers by id, turning the `employer` fields of a page of postings
+/// into batched reads.
+pub struct EmployerLoader {
+    repository: Repository,
+}
+
+impl EmployerLoader {
+    pub fn new(repository: Repository) -> Self {
+        Self { repository }
+    }
+}
+
+impl Loader<String> for EmployerLoader {
+    type Value = Employer;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Employer>, Error> {
+        self.repository.get_many::<Employer>(keys).await.map_err(|e| e.to_graphql_error())
+    }
+}
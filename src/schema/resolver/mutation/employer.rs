@@ -0,0 +1,230 @@
+use async_graphql::ID;
+
+use chrono::Duration;
+
+use crate::{
+    auth::guard::{ EmployerAdminGuard, RoleGuard },
+    context::ContextExtensions,
+    models::{ prelude::*, employer::EmployerPatchInput, user::Role },
+    pagination::MAX_PAGE_SIZE,
+    schema::types::employer::DomainChallenge,
+    verification::{ self, VerificationMethod },
+    AppError,
+    Repository,
+};
+
+#[derive(Debug, Default)]
+pub struct EmployerMutation;
+
+async fn get_employer(repo: &Repository, id: &str) -> Result<Employer, AppError> {
+    repo.get::<Employer>(id.to_string()).await?.ok_or_else(||
+        AppError::NotFound(format!("Employer {} does not exist", id))
+    )
+}
+
+/// Rejects `name` if another employer already uses it.
+async fn ensure_unique_name(repo: &Repository, id: &str, name: &str) -> Result<(), AppError> {
+    let existing = Employer::find_by_name(repo, name).await?;
+
+    if existing.is_some_and(|existing| existing.id != id) {
+        return Err(
+            AppError::ValidationError(format!("Employer name '{}' is already in use", name))
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns every posting of `employer_id` held for moderation to the public
+/// listings. Returns how many were released.
+async fn release_held_postings(repo: &Repository, employer_id: &str) -> Result<usize, AppError> {
+    let query = JobPosting::held_by_employer_query(employer_id);
+    let mut after = None;
+    let mut released = 0;
+
+    loop {
+        let page = repo.query::<JobPosting>(&query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for mut job_posting in page.into_entities() {
+            job_posting.held_for_moderation = false;
+            repo.update(job_posting).await?;
+            released += 1;
+        }
+
+        if after.is_none() {
+            break;
+        }
+    }
+
+    Ok(released)
+}
+
+#[Object]
+impl EmployerMutation {
+    /// Creates an employer profile.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn create_employer(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        description: Option<String>,
+        website_url: Option<String>,
+        logo_url: Option<String>,
+        domain: Option<String>
+    ) -> Result<Employer, Error> {
+        info!("Creating employer: {}", name);
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let id = format!("employer-{}", Uuid::new_v4());
+
+        let employer = Employer::new(
+            id,
+            name,
+            description,
+            website_url,
+            logo_url,
+            domain
+        ).map_err(|e| e.to_graphql_error())?;
+
+        ensure_unique_name(repo, &employer.id, &employer.name).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        repo.create(employer).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Applies a partial update to an employer profile.
+    #[graphql(guard = "EmployerAdminGuard::new(&id)")]
+    async fn update_employer(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        patch: EmployerPatchInput
+    ) -> Result<Employer, Error> {
+        info!("Updating employer: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut employer = get_employer(repo, id.as_str()).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        employer.apply_patch(patch).map_err(|e| e.to_graphql_error())?;
+
+        ensure_unique_name(repo, &employer.id, &employer.name).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        repo.update(employer).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Deletes an employer that no longer has any job postings.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn delete_employer(&self, ctx: &Context<'_>, id: ID) -> Result<bool, Error> {
+        info!("Deleting employer: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let postings = repo
+            .query::<JobPosting>(&JobPosting::by_employer_query(id.as_str()), 1, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if !postings.items.is_empty() {
+            return Err(
+                AppError::ValidationError(
+                    "Cannot delete an employer that still has job postings".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        repo.delete::<Employer>(id.to_string()).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Issues a token for proving ownership of the employer's domain, taken
+    /// from `domain` or else from the website URL. Any earlier token stops
+    /// working.
+    #[graphql(guard = "EmployerAdminGuard::new(&employer_id)")]
+    async fn request_domain_challenge(
+        &self,
+        ctx: &Context<'_>,
+        employer_id: ID
+    ) -> Result<DomainChallenge, Error> {
+        info!("Issuing domain challenge for employer: {}", employer_id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let mut employer = get_employer(repo, employer_id.as_str()).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        let domain = employer.verification_domain().ok_or_else(|| {
+            AppError::ValidationError(
+                "Set a domain or website URL before requesting a challenge".to_string()
+            ).to_graphql_error()
+        })?;
+
+        let token = verification::generate_challenge_token().map_err(|e| e.to_graphql_error())?;
+        let lifetime = Duration::seconds(config.verification.challenge_expiry as i64);
+
+        let expires_at = employer.issue_domain_challenge(domain.clone(), token.clone(), lifetime);
+        employer.validate().map_err(|e| AppError::ValidationError(e).to_graphql_error())?;
+
+        repo.update(employer).await.map_err(|e| e.to_graphql_error())?;
+
+        Ok(DomainChallenge { domain, token, expires_at })
+    }
+
+    /// Checks the outstanding challenge with `method` and marks the domain
+    /// verified when the token is found. Postings held for moderation are
+    /// released once the domain is verified.
+    #[graphql(guard = "EmployerAdminGuard::new(&employer_id)")]
+    async fn verify_domain(
+        &self,
+        ctx: &Context<'_>,
+        employer_id: ID,
+        method: VerificationMethod
+    ) -> Result<Employer, Error> {
+        info!("Verifying domain for employer: {} via {:?}", employer_id.as_str(), method);
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let verifier = ctx.domain_verifier().map_err(|e| e.to_graphql_error())?;
+
+        let mut employer = get_employer(repo, employer_id.as_str()).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        let (Some(domain), Some(token)) = (
+            employer.domain.clone(),
+            employer.active_domain_challenge().map(str::to_string),
+        ) else {
+            return Err(
+                AppError::ValidationError(
+                    "No active domain challenge; request a new one".to_string()
+                ).to_graphql_error()
+            );
+        };
+
+        verifier.verify(&domain, &token, method).await.map_err(|e| e.to_graphql_error())?;
+
+        // Only accept the result if nobody re-issued the challenge meanwhile
+        let expected = AttributeValue::S(token);
+        employer.mark_domain_verified();
+
+        let employer = repo
+            .update_if(employer, "domain_challenge", expected).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let released = release_held_postings(repo, &employer.id).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        if released > 0 {
+            info!("Released {} held job postings for employer {}", released, employer.id);
+        }
+
+        Ok(employer)
+    }
+}
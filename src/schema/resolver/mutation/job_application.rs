@@ -0,0 +1,138 @@
+use async_graphql::ID;
+
+use crate::{
+    auth::guard::JobApplicationAccessGuard,
+    context::ContextExtensions,
+    models::{ prelude::*, job_application::{ ApplicationStatus, JobApplication } },
+    AppError,
+};
+
+#[derive(Debug, Default)]
+pub struct JobApplicationMutation;
+
+#[Object]
+impl JobApplicationMutation {
+    /// Submits an application to an existing job posting.
+    ///
+    /// Signed-in applicants are recorded on the application, which is what
+    /// later grants them access to it; anonymous applications belong to no one.
+    async fn apply_to_job(
+        &self,
+        ctx: &Context<'_>,
+        job_posting_id: ID,
+        applicant_name: String,
+        applicant_email: String,
+        cover_note: Option<String>
+    ) -> Result<JobApplication, Error> {
+        info!("Creating job application for posting: {}", job_posting_id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let job_posting = repo
+            .get::<JobPosting>(job_posting_id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let Some(job_posting) = job_posting else {
+            return Err(
+                AppError::NotFound(
+                    format!("Job posting {} does not exist", job_posting_id.as_str())
+                ).to_graphql_error()
+            );
+        };
+
+        // Only postings that are listed right now take applications
+        if !job_posting.is_live(Utc::now()) {
+            return Err(
+                AppError::ValidationError(
+                    format!("Job posting {} is not accepting applications", job_posting_id.as_str())
+                ).to_graphql_error()
+            );
+        }
+
+        let applicant_id = ctx
+            .principal()
+            .ok()
+            .map(|principal| principal.subject.clone());
+        let id = format!("job_application-{}", Uuid::new_v4());
+
+        let job_application = JobApplication::new(
+            id,
+            job_posting_id.to_string(),
+            applicant_name,
+            applicant_email,
+            applicant_id,
+            cover_note
+        ).map_err(|e| e.to_graphql_error())?;
+
+        repo.create(job_application).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Moves an application to a new status and appends to its history.
+    ///
+    /// The owning employer may make any legal transition except withdrawing,
+    /// which only the applicant may do. The change is recorded under the
+    /// caller's account.
+    #[graphql(guard = "JobApplicationAccessGuard::new(&id)")]
+    async fn update_application_status(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        status: ApplicationStatus,
+        note: Option<String>
+    ) -> Result<JobApplication, Error> {
+        info!("Updating job application {} to {}", id.as_str(), status);
+
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut job_application = repo
+            .get::<JobApplication>(id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(
+                    format!("Job application {} does not exist", id.as_str())
+                ).to_graphql_error()
+            })?;
+
+        if status == ApplicationStatus::Withdrawn {
+            // Emails are not verified, so only the account that applied counts
+            if job_application.applicant_id.as_deref() != Some(principal.subject.as_str()) {
+                return Err(
+                    AppError::Forbidden(
+                        "Only the applicant can withdraw an application".to_string()
+                    ).to_graphql_error()
+                );
+            }
+        } else {
+            let employer_id = repo
+                .get::<JobPosting>(job_application.job_posting_id.clone()).await
+                .map_err(|e| e.to_graphql_error())?
+                .and_then(|posting| posting.employer_id);
+
+            if !principal.acts_for(employer_id.as_deref()) {
+                return Err(
+                    AppError::Forbidden(
+                        "Applicants can only withdraw their applications".to_string()
+                    ).to_graphql_error()
+                );
+            }
+        }
+
+        let previous_status = job_application.status;
+
+        job_application
+            .transition(status, principal.subject.clone(), note)
+            .map_err(|e| e.to_graphql_error())?;
+
+        // Status and history go out in one conditional write, so StatusIndex
+        // never disagrees with the history
+        repo
+            .update_if(
+                job_application,
+                "application_status",
+                AttributeValue::S(previous_status.to_string())
+            ).await
+            .map_err(|e| e.to_graphql_error())
+    }
+}
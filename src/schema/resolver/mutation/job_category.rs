@@ -0,0 +1,182 @@
+use async_graphql::{ MaybeUndefined, ID };
+
+use crate::{
+    auth::guard::RoleGuard,
+    context::ContextExtensions,
+    models::{ prelude::*, job_category::{ slugify, JobCategory }, user::Role },
+    pagination::MAX_PAGE_SIZE,
+    AppError,
+    Repository,
+};
+
+#[derive(Debug, Default)]
+pub struct JobCategoryMutation;
+
+async fn get_category(repo: &Repository, id: &str) -> Result<JobCategory, AppError> {
+    repo.get::<JobCategory>(id.to_string()).await?.ok_or_else(||
+        AppError::NotFound(format!("Job category {} does not exist", id))
+    )
+}
+
+/// Rejects `name` or `slug` if another category already uses it.
+async fn ensure_unique(
+    repo: &Repository,
+    id: &str,
+    name: &str,
+    slug: &str
+) -> Result<(), AppError> {
+    let existing = JobCategory::find_by_name(repo, name).await?;
+
+    if existing.is_some_and(|existing| existing.id != id) {
+        return Err(
+            AppError::ValidationError(format!("Category name '{}' is already in use", name))
+        );
+    }
+    let existing = JobCategory::find_by_slug(repo, slug).await?;
+
+    if existing.is_some_and(|existing| existing.id != id) {
+        return Err(
+            AppError::ValidationError(format!("Category slug '{}' is already in use", slug))
+        );
+    }
+
+    Ok(())
+}
+
+/// Ensures `parent_id` exists and that making it the parent of `id` would not
+/// create a cycle.
+async fn ensure_valid_parent(repo: &Repository, id: &str, parent_id: &str) -> Result<(), AppError> {
+    let mut current = Some(parent_id.to_string());
+
+    while let Some(ancestor_id) = current {
+        if ancestor_id == id {
+            return Err(
+                AppError::ValidationError(
+                    "Category cannot be moved beneath one of its descendants".to_string()
+                )
+            );
+        }
+
+        current = get_category(repo, &ancestor_id).await?.parent_id;
+    }
+
+    Ok(())
+}
+
+#[Object]
+impl JobCategoryMutation {
+    /// Creates a category. The slug is derived from the name when omitted.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn create_job_category(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        slug: Option<String>,
+        parent_id: Option<ID>
+    ) -> Result<JobCategory, Error> {
+        info!("Creating job category: {}", name);
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let id = format!("job_category-{}", Uuid::new_v4());
+
+        let category = JobCategory::new(
+            id,
+            name,
+            slug,
+            parent_id.map(|id| id.to_string())
+        ).map_err(|e| e.to_graphql_error())?;
+
+        ensure_unique(repo, &category.id, &category.name, &category.slug).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        if let Some(parent_id) = &category.parent_id {
+            ensure_valid_parent(repo, &category.id, parent_id).await.map_err(|e|
+                e.to_graphql_error()
+            )?;
+        }
+
+        repo.create(category).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Renames or moves a category. Passing `parentId: null` makes it a root.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn update_job_category(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        name: Option<String>,
+        slug: Option<String>,
+        #[graphql(default)] parent_id: MaybeUndefined<ID>
+    ) -> Result<JobCategory, Error> {
+        info!("Updating job category: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut category = get_category(repo, id.as_str()).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        if let Some(name) = name {
+            // Keep derived slugs in step with the name unless one was supplied
+            let slug_was_derived = category.slug == slugify(&category.name);
+
+            category.name = name.trim().to_string();
+
+            if slug.is_none() && slug_was_derived {
+                category.slug = slugify(&category.name);
+            }
+        }
+        if let Some(slug) = slug {
+            category.slug = slug;
+        }
+        match parent_id {
+            MaybeUndefined::Undefined => {}
+            MaybeUndefined::Null => {
+                category.parent_id = None;
+            }
+            MaybeUndefined::Value(parent_id) => {
+                category.parent_id = Some(parent_id.to_string());
+            }
+        }
+
+        category.validate().map_err(|e| AppError::ValidationError(e).to_graphql_error())?;
+
+        ensure_unique(repo, &category.id, &category.name, &category.slug).await.map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        if let Some(parent_id) = &category.parent_id {
+            ensure_valid_parent(repo, &category.id, parent_id).await.map_err(|e|
+                e.to_graphql_error()
+            )?;
+        }
+
+        category.updated_at = Utc::now();
+
+        repo.update(category).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Deletes a category. Categories that still have children cannot be deleted.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn delete_job_category(&self, ctx: &Context<'_>, id: ID) -> Result<bool, Error> {
+        info!("Deleting job category: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let children = repo
+            .query::<JobCategory>(&JobCategory::children_query(id.as_str()), MAX_PAGE_SIZE, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if !children.items.is_empty() {
+            return Err(
+                AppError::ValidationError(
+                    format!("Job category {} still has child categories", id.as_str())
+                ).to_graphql_error()
+            );
+        }
+
+        repo.delete::<JobCategory>(id.to_string()).await.map_err(|e| e.to_graphql_error())
+    }
+}
@@ -1,27 +1,67 @@
+use async_graphql::ID;
+
+use chrono::Duration;
+
 use crate::{
+    auth::guard::{ JobPostingOwnerGuard, RoleGuard },
+    config::Config,
+    context::ContextExtensions,
+    geo,
+    import::{ ImportFormat, ImportReport, Importer },
+    lifecycle::{ self, SweepReport },
+    search,
     models::{
         address::AddressInput,
-        job_posting::{ ExpectedHoursRange, ExpectedHoursRangeInput, JobPosting },
+        coordinates::{ Coordinates, CoordinatesInput },
+        job_application::JobApplication,
+        job_category::JobCategory,
+        job_posting::{
+            ExpectedHoursRange,
+            ExpectedHoursRangeInput,
+            JobPosting,
+            JobPostingPatchInput,
+        },
         pay::PayInput,
         prelude::*,
+        user::Role,
+        work_arrangement::{ WorkArrangement, WorkArrangementInput },
     },
     AppError,
-    DbClient,
     Repository,
 };
 
+fn default_lifetime(config: &Config) -> Duration {
+    Duration::seconds(config.lifecycle.default_posting_lifetime as i64)
+}
+
+async fn get_job_posting(repo: &Repository, id: &ID) -> Result<JobPosting, AppError> {
+    repo.get::<JobPosting>(id.to_string()).await?.ok_or_else(||
+        AppError::NotFound(format!("Job posting {} does not exist", id.as_str()))
+    )
+}
+
 #[derive(Debug, Default)]
 pub struct JobPostingMutation;
 
 #[Object]
 impl JobPostingMutation {
+    /// Creates a job posting owned by the caller's employer. Site admins name
+    /// the employer with `employerId` instead.
+    ///
+    /// The posting is published right away, or at `publishAt`, and expires at
+    /// `expiresAt` or after the configured default lifetime. With `draft` set
+    /// it is kept unpublished until `publishJobPosting`.
+    ///
+    /// Without `coordinates`, the posting is located by geocoding its address.
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(guard = "RoleGuard::new(Role::EmployerMember)")]
     async fn create_job_posting(
         &self,
         ctx: &Context<'_>,
         job_title: String,
-        employer_name: String,
-        employer_url: String,
+        employer_id: Option<ID>,
         address: AddressInput,
+        coordinates: Option<CoordinatesInput>,
         pay: Option<PayInput>,
         job_type: String,
         link_to_application: Option<String>,
@@ -29,31 +69,56 @@ impl JobPostingMutation {
         employee_responsibilities: Option<Vec<String>>,
         experience_requirements: Option<Vec<String>>,
         extra_info: Option<String>,
-        expected_hours: ExpectedHoursRangeInput
+        expected_hours: ExpectedHoursRangeInput,
+        work_arrangement: Option<WorkArrangementInput>,
+        category_ids: Option<Vec<String>>,
+        draft: Option<bool>,
+        publish_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>
     ) -> Result<JobPosting, Error> {
         info!("Creating new job posting: {}", job_title);
 
-        let db_client = ctx.data::<DbClient>().map_err(|e| {
-            warn!("Failed to get db_client from context: {:?}", e);
-            AppError::InternalServerError(
-                "Failed to access application db_client".to_string()
-            ).to_graphql_error()
-        })?;
+        let draft = draft.unwrap_or(false);
+
+        if draft && (publish_at.is_some() || expires_at.is_some()) {
+            return Err(
+                AppError::ValidationError(
+                    "Drafts are scheduled when they are published".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let employer_id = principal
+            .posting_employer_id(repo, employer_id.as_deref().map(String::as_str)).await
+            .map_err(|e| e.to_graphql_error())?;
 
         let id = format!("job_posting-{}", Uuid::new_v4());
 
-        let pay_value = match pay {
-            Some(p) => Some(Pay::from(p)),
-            None => None,
-        };
+        let pay_value = pay.map(Pay::from);
+
+        let coordinates = coordinates
+            .map(Coordinates::try_from)
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?;
+
+        let work_arrangement = work_arrangement
+            .map(WorkArrangement::try_from)
+            .transpose()
+            .map_err(|e| e.to_graphql_error())?
+            .unwrap_or_default();
 
-        let repo = Repository::new(db_client.clone());
+        let category_ids = category_ids.unwrap_or_default();
 
-        let job_posting = JobPosting::new(
+        JobCategory::ensure_exist(repo, &category_ids).await.map_err(|e| e.to_graphql_error())?;
+
+        let mut job_posting = JobPosting::new(
             id,
             job_title,
-            employer_name,
-            employer_url,
+            employer_id.clone(),
             Address::from(address),
             pay_value,
             job_type,
@@ -62,10 +127,207 @@ impl JobPostingMutation {
             employee_responsibilities,
             experience_requirements,
             extra_info,
-            ExpectedHoursRange::from(expected_hours)
+            ExpectedHoursRange::from(expected_hours),
+            work_arrangement,
+            category_ids
         ).map_err(|e| e.to_graphql_error())?;
-        
-        repo.create(job_posting).await.map_err(|e| e.to_graphql_error())
 
+        job_posting.validate().map_err(|e| e.to_graphql_error())?;
+
+        match coordinates {
+            Some(coordinates) => {
+                job_posting.coordinates = Some(coordinates);
+            }
+            None => {
+                let geocoder = ctx.geocoder().map_err(|e| e.to_graphql_error())?;
+                geo::locate_or_warn(geocoder, &mut job_posting).await;
+            }
+        }
+
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        if !draft {
+            job_posting
+                .publish(publish_at, expires_at, default_lifetime(config), Utc::now())
+                .map_err(|e| e.to_graphql_error())?;
+        }
+
+        if config.verification.hold_unverified_postings {
+            let verified = repo
+                .get::<Employer>(employer_id).await
+                .map_err(|e| e.to_graphql_error())?
+                .is_some_and(|employer| employer.is_domain_verified());
+
+            job_posting.held_for_moderation = !verified;
+        }
+
+        let job_posting = repo.create(job_posting).await.map_err(|e| e.to_graphql_error())?;
+
+        search::index_job_posting_or_warn(repo, &job_posting).await;
+
+        Ok(job_posting)
+    }
+
+    /// Creates job postings from a CSV or NDJSON document, one per row, with
+    /// the fields of `createJobPosting` as columns. Rows without an
+    /// `employer_id` belong to `employerId`, or to the caller's employer.
+    ///
+    /// Nothing is written if any row has errors, or with `dryRun` set; the
+    /// report lists the errors of every row either way.
+    #[graphql(guard = "RoleGuard::new(Role::EmployerMember)")]
+    async fn import_job_postings(
+        &self,
+        ctx: &Context<'_>,
+        format: ImportFormat,
+        data: String,
+        dry_run: Option<bool>,
+        employer_id: Option<ID>
+    ) -> Result<ImportReport, Error> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+        let geocoder = ctx.geocoder().map_err(|e| e.to_graphql_error())?;
+
+        Importer::new(repo, config, geocoder, principal)
+            .import(
+                format,
+                &data,
+                employer_id.as_deref().map(String::as_str),
+                dry_run.unwrap_or(false),
+                Utc::now()
+            ).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    /// Applies a partial update to an existing job posting.
+    #[graphql(guard = "JobPostingOwnerGuard::new(&id)")]
+    async fn update_job_posting(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        patch: JobPostingPatchInput
+    ) -> Result<JobPosting, Error> {
+        info!("Updating job posting: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut job_posting = repo
+            .get::<JobPosting>(id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Job posting {} does not exist", id.as_str())).to_graphql_error()
+            })?;
+
+        if let Some(category_ids) = &patch.category_ids {
+            JobCategory::ensure_exist(repo, category_ids).await.map_err(|e|
+                e.to_graphql_error()
+            )?;
+        }
+
+        let relocated = patch.address.is_some() && patch.coordinates.is_undefined();
+
+        job_posting.apply_patch(patch).map_err(|e| e.to_graphql_error())?;
+
+        if relocated {
+            let geocoder = ctx.geocoder().map_err(|e| e.to_graphql_error())?;
+            geo::locate_or_warn(geocoder, &mut job_posting).await;
+        }
+
+        let job_posting = repo.update(job_posting).await.map_err(|e| e.to_graphql_error())?;
+
+        search::index_job_posting_or_warn(repo, &job_posting).await;
+
+        Ok(job_posting)
+    }
+
+    /// Publishes a draft, republishes a closed or expired posting, or
+    /// reschedules a published one. `publishAt` defaults to now and
+    /// `expiresAt` to the configured default lifetime after `publishAt`.
+    #[graphql(guard = "JobPostingOwnerGuard::new(&id)")]
+    async fn publish_job_posting(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        publish_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>
+    ) -> Result<JobPosting, Error> {
+        info!("Publishing job posting: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let mut job_posting = get_job_posting(repo, &id).await.map_err(|e| e.to_graphql_error())?;
+
+        job_posting
+            .publish(publish_at, expires_at, default_lifetime(config), Utc::now())
+            .map_err(|e| e.to_graphql_error())?;
+
+        repo.update(job_posting).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Takes a published posting out of the listings before it expires.
+    #[graphql(guard = "JobPostingOwnerGuard::new(&id)")]
+    async fn close_job_posting(&self, ctx: &Context<'_>, id: ID) -> Result<JobPosting, Error> {
+        info!("Closing job posting: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut job_posting = get_job_posting(repo, &id).await.map_err(|e| e.to_graphql_error())?;
+
+        job_posting.close(Utc::now()).map_err(|e| e.to_graphql_error())?;
+
+        repo.update(job_posting).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Runs the lifecycle sweep now instead of waiting for the scheduled run.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn sweep_job_postings(&self, ctx: &Context<'_>) -> Result<SweepReport, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        lifecycle
+            ::sweep_job_postings(repo, &config.lifecycle, Utc::now()).await
+            .map_err(|e| e.to_graphql_error())
+    }
+
+    /// Releases a posting held for moderation to the public listings.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn approve_job_posting(&self, ctx: &Context<'_>, id: ID) -> Result<JobPosting, Error> {
+        info!("Approving job posting: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut job_posting = repo
+            .get::<JobPosting>(id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Job posting {} does not exist", id.as_str())).to_graphql_error()
+            })?;
+
+        job_posting.held_for_moderation = false;
+
+        repo.update(job_posting).await.map_err(|e| e.to_graphql_error())
+    }
+
+    #[graphql(guard = "JobPostingOwnerGuard::new(&id)")]
+    async fn delete_job_posting(&self, ctx: &Context<'_>, id: ID) -> Result<bool, Error> {
+        info!("Deleting job posting: {}", id.as_str());
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        // Applications go first, so none outlive the posting if this fails
+        let applications = JobApplication::delete_for_posting(repo, id.as_str()).await
+            .map_err(|e| e.to_graphql_error())?;
+        info!("Deleted {} applications for job posting {}", applications, id.as_str());
+
+        let deleted = repo
+            .delete::<JobPosting>(id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        if let Err(e) = search::remove_job_posting(repo, id.as_str()).await {
+            warn!("Failed to remove job posting {} from the search index: {}", id.as_str(), e);
+        }
+
+        Ok(deleted)
     }
 }
@@ -1,6 +1,16 @@
 use async_graphql::MergedObject;
 
+pub mod employer;
+pub mod job_application;
+pub mod job_category;
 pub mod job_posting;
+pub mod user;
 
 #[derive(Debug, Default, MergedObject)]
-pub struct MutationRoot(job_posting::JobPostingMutation);
+pub struct MutationRoot(
+    job_posting::JobPostingMutation,
+    job_application::JobApplicationMutation,
+    job_category::JobCategoryMutation,
+    employer::EmployerMutation,
+    user::UserMutation,
+);
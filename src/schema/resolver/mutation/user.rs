@@ -0,0 +1,323 @@
+use async_graphql::ID;
+use chrono::Duration;
+
+use crate::{
+    auth::{ self, guard::RoleGuard, password, Principal },
+    config::AuthConfig,
+    context::ContextExtensions,
+    models::{
+        prelude::*,
+        refresh_token::RefreshToken,
+        timestamp,
+        user::Role,
+    },
+    pagination::MAX_PAGE_SIZE,
+    schema::types::user::AuthPayload,
+    AppError,
+    AppResult,
+    Repository,
+};
+
+#[derive(Debug, Default)]
+pub struct UserMutation;
+
+fn invalid_credentials() -> AppError {
+    AppError::AuthError("Invalid email or password".to_string())
+}
+
+fn invalid_refresh_token() -> AppError {
+    AppError::AuthError("Refresh token is invalid or has expired".to_string())
+}
+
+/// Issues an access token and a refresh token for `user`. The refresh token
+/// joins `family_id` when rotating, or starts a new family on login.
+async fn issue_tokens(
+    repo: &Repository,
+    config: &AuthConfig,
+    user: User,
+    family_id: Option<String>
+) -> AppResult<AuthPayload> {
+    let access_token = auth::issue_token(&Principal::from(&user), config)?;
+    let refresh_token = auth::generate_refresh_token()?;
+
+    let record = RefreshToken::new(
+        auth::hash_refresh_token(&refresh_token),
+        user.id.clone(),
+        family_id.unwrap_or_else(|| format!("token_family-{}", Uuid::new_v4())),
+        Duration::seconds(config.refresh_token_expiry as i64)
+    );
+
+    repo.create(record).await?;
+
+    Ok(AuthPayload {
+        access_token,
+        refresh_token,
+        expires_in: config.token_expiry,
+        user,
+    })
+}
+
+/// Revokes every outstanding token in a family.
+async fn revoke_family(repo: &Repository, family_id: &str) -> AppResult<()> {
+    let query = RefreshToken::by_family_query(family_id);
+    let mut after = None;
+
+    loop {
+        let page = repo.query::<RefreshToken>(&query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for mut token in page.into_entities() {
+            if !token.revoked {
+                token.revoke();
+                repo.update(token).await?;
+            }
+        }
+
+        if after.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+#[Object]
+impl UserMutation {
+    /// Creates an account and signs it in.
+    async fn register(
+        &self,
+        ctx: &Context<'_>,
+        email: String,
+        password: String
+    ) -> Result<AuthPayload, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        password::validate_password(&password).map_err(|e| e.to_graphql_error())?;
+
+        let existing = User::find_by_email(repo, &email).await.map_err(|e| e.to_graphql_error())?;
+
+        if existing.is_some() {
+            return Err(
+                AppError::ValidationError(
+                    "An account with this email already exists".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let password_hash = password::hash_password(&password).map_err(|e| e.to_graphql_error())?;
+
+        let user = User::new(format!("user-{}", Uuid::new_v4()), email, password_hash).map_err(
+            |e| e.to_graphql_error()
+        )?;
+
+        info!("Registering user: {}", user.id);
+
+        // EmailIndex lags behind writes, so the check above can miss a
+        // concurrent registration; the email claim cannot
+        let claim_id = user.email_claim_id();
+        let user = repo.create_claimed(user, claim_id).await.map_err(|e| {
+            match e {
+                AppError::ValidationError(_) =>
+                    AppError::ValidationError(
+                        "An account with this email already exists".to_string()
+                    ).to_graphql_error(),
+                e => e.to_graphql_error(),
+            }
+        })?;
+
+        issue_tokens(repo, &config.auth, user, None).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Exchanges credentials for tokens.
+    ///
+    /// After `MAX_FAILED_LOGINS` consecutive failures the account is locked
+    /// for `LOCKOUT_DURATION`, during which even correct passwords are refused.
+    async fn login(
+        &self,
+        ctx: &Context<'_>,
+        email: String,
+        password: String
+    ) -> Result<AuthPayload, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let mut user = User::find_by_email(repo, &email).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| invalid_credentials().to_graphql_error())?;
+
+        let now = Utc::now();
+
+        if user.is_locked(now) {
+            return Err(
+                AppError::AuthError(
+                    "Account is temporarily locked after too many failed login attempts".to_string()
+                ).to_graphql_error()
+            );
+        }
+
+        let verified = password::verify_password(&password, &user.password_hash).map_err(|e|
+            e.to_graphql_error()
+        )?;
+
+        // Conditioned on the previous updated_at so concurrent attempts
+        // cannot overwrite each other's failure counts
+        let previous = timestamp::to_attribute_value(&user.updated_at);
+
+        if !verified {
+            warn!("Failed login for user: {}", user.id);
+
+            user.record_failed_login(now);
+            repo.update_if(user, "updated_at", previous).await.map_err(|e| e.to_graphql_error())?;
+
+            return Err(invalid_credentials().to_graphql_error());
+        }
+
+        if user.record_successful_login(now) {
+            user = repo
+                .update_if(user, "updated_at", previous).await
+                .map_err(|e| e.to_graphql_error())?;
+        }
+
+        issue_tokens(repo, &config.auth, user, None).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Rotates a refresh token: the presented token is revoked and a new
+    /// access and refresh token pair is returned.
+    ///
+    /// Presenting a token that has already been rotated revokes its whole
+    /// family, ending the session everywhere it was copied to.
+    async fn refresh_token(
+        &self,
+        ctx: &Context<'_>,
+        refresh_token: String
+    ) -> Result<AuthPayload, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+        let config = ctx.config().map_err(|e| e.to_graphql_error())?;
+
+        let mut token = repo
+            .get::<RefreshToken>(auth::hash_refresh_token(&refresh_token)).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| invalid_refresh_token().to_graphql_error())?;
+
+        let family_id = token.family_id.clone();
+
+        if token.revoked {
+            warn!("Revoked refresh token reused; revoking family: {}", family_id);
+
+            revoke_family(repo, &family_id).await.map_err(|e| e.to_graphql_error())?;
+
+            return Err(invalid_refresh_token().to_graphql_error());
+        }
+
+        if token.is_expired(Utc::now()) {
+            return Err(invalid_refresh_token().to_graphql_error());
+        }
+
+        let user_id = token.user_id.clone();
+
+        token.revoke();
+
+        // Only one caller can rotate a given token; the loser is treated as reuse
+        match repo.update_if(token, "revoked", AttributeValue::Bool(false)).await {
+            Ok(_) => {}
+            Err(AppError::ValidationError(_)) => {
+                revoke_family(repo, &family_id).await.map_err(|e| e.to_graphql_error())?;
+
+                return Err(invalid_refresh_token().to_graphql_error());
+            }
+            Err(e) => {
+                return Err(e.to_graphql_error());
+            }
+        }
+
+        let user = repo
+            .get::<User>(user_id).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| invalid_refresh_token().to_graphql_error())?;
+
+        issue_tokens(repo, &config.auth, user, Some(family_id)).await.map_err(|e|
+            e.to_graphql_error()
+        )
+    }
+
+    /// Ends the session a refresh token belongs to. Returns whether a session
+    /// was found.
+    async fn logout(&self, ctx: &Context<'_>, refresh_token: String) -> Result<bool, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let token = repo
+            .get::<RefreshToken>(auth::hash_refresh_token(&refresh_token)).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let Some(token) = token else {
+            return Ok(false);
+        };
+
+        revoke_family(repo, &token.family_id).await.map_err(|e| e.to_graphql_error())?;
+
+        Ok(true)
+    }
+
+    /// Changes a user's role and employer.
+    ///
+    /// Site admins can assign any role. Employer admins can only add users to,
+    /// promote within, or remove users from their own employer. Takes effect
+    /// when the user next logs in or refreshes their token.
+    #[graphql(guard = "RoleGuard::new(Role::EmployerAdmin)")]
+    async fn assign_user_role(
+        &self,
+        ctx: &Context<'_>,
+        user_id: ID,
+        role: Role,
+        employer_id: Option<String>
+    ) -> Result<User, Error> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let mut user = repo
+            .get::<User>(user_id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("User {} does not exist", user_id.as_str())).to_graphql_error()
+            })?;
+
+        let employer_role = matches!(role, Role::EmployerMember | Role::EmployerAdmin);
+
+        let employer_id = if principal.has_role(Role::SiteAdmin) {
+            employer_id
+        } else {
+            // Employer admins act within their own employer only
+            let own_employer = principal.employer_id.clone();
+            let allowed =
+                role != Role::SiteAdmin &&
+                user.role != Role::SiteAdmin &&
+                employer_id.as_ref().is_none_or(|id| own_employer.as_ref() == Some(id)) &&
+                (user.employer_id.is_none() || user.employer_id == own_employer);
+
+            if !allowed {
+                return Err(
+                    AppError::Forbidden(
+                        "Employer admins can only manage users of their own employer".to_string()
+                    ).to_graphql_error()
+                );
+            }
+
+            own_employer
+        };
+
+        if employer_role && employer_id.is_none() {
+            return Err(
+                AppError::ValidationError(
+                    format!("The {} role requires an employer", role)
+                ).to_graphql_error()
+            );
+        }
+
+        info!("Assigning role {} to user {}", role, user.id);
+
+        user.role = role;
+        user.employer_id = if employer_role { employer_id } else { None };
+        user.updated_at = Utc::now();
+
+        repo.update(user).await.map_err(|e| e.to_graphql_error())
+    }
+}
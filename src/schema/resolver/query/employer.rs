@@ -0,0 +1,38 @@
+use async_graphql::{ connection::Connection, ID };
+
+use crate::{
+    context::ContextExtensions,
+    models::prelude::*,
+    pagination::{ self, Cursor },
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct EmployerQuery;
+
+#[Object]
+impl EmployerQuery {
+    async fn employer(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Employer>, Error> {
+        let loader = ctx.employer_loader().map_err(|e| e.to_graphql_error())?;
+
+        loader.load_one(id.to_string()).await
+    }
+
+    /// Relay-style paginated listing of employers.
+    async fn employers(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>
+    ) -> Result<Connection<Cursor, Employer>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let page = repo
+            .list::<Employer>(pagination::page_size(first), after).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+}
@@ -0,0 +1,113 @@
+use async_graphql::{ connection::Connection, ID };
+
+use crate::{
+    auth::guard::{ ApplicantGuard, JobApplicationAccessGuard, JobPostingOwnerGuard, RoleGuard },
+    context::ContextExtensions,
+    models::{ prelude::*, job_application::{ ApplicationStatus, JobApplication }, user::Role },
+    pagination::{ self, Cursor },
+    repository::Filter,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct JobApplicationQuery;
+
+#[Object]
+impl JobApplicationQuery {
+    #[graphql(guard = "JobApplicationAccessGuard::new(&id)")]
+    async fn job_application(
+        &self,
+        ctx: &Context<'_>,
+        id: ID
+    ) -> Result<Option<JobApplication>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        repo.get::<JobApplication>(id.to_string()).await.map_err(|e| e.to_graphql_error())
+    }
+
+    /// Applications submitted for a job posting.
+    #[graphql(guard = "JobPostingOwnerGuard::new(&job_posting_id)")]
+    async fn job_applications_for_posting(
+        &self,
+        ctx: &Context<'_>,
+        job_posting_id: ID,
+        first: Option<i32>,
+        after: Option<String>
+    ) -> Result<Connection<Cursor, JobApplication>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let page = repo
+            .query::<JobApplication>(
+                &JobApplication::by_job_posting_query(job_posting_id.as_str()),
+                pagination::page_size(first),
+                after
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+
+    /// Applications submitted by an applicant, matched case-insensitively on email.
+    ///
+    /// Callers other than site admins only see the applications they submitted
+    /// while signed in.
+    #[graphql(guard = "ApplicantGuard::new(&applicant_email)")]
+    async fn job_applications_by_applicant(
+        &self,
+        ctx: &Context<'_>,
+        applicant_email: String,
+        first: Option<i32>,
+        after: Option<String>
+    ) -> Result<Connection<Cursor, JobApplication>, Error> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let mut query = JobApplication::by_applicant_query(&applicant_email);
+
+        if !principal.has_role(Role::SiteAdmin) {
+            query = query.filter(
+                Filter::eq("applicant_id", AttributeValue::S(principal.subject.clone()))
+            );
+        }
+
+        let page = repo
+            .query::<JobApplication>(
+                &query,
+                pagination::page_size(first),
+                after
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+
+    /// Applications currently in the given status.
+    #[graphql(guard = "RoleGuard::new(Role::SiteAdmin)")]
+    async fn job_applications_by_status(
+        &self,
+        ctx: &Context<'_>,
+        status: ApplicationStatus,
+        first: Option<i32>,
+        after: Option<String>
+    ) -> Result<Connection<Cursor, JobApplication>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let page = repo
+            .query::<JobApplication>(
+                &JobApplication::by_status_query(status),
+                pagination::page_size(first),
+                after
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+}
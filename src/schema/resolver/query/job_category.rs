@@ -0,0 +1,42 @@
+use async_graphql::ID;
+
+use crate::{
+    context::ContextExtensions,
+    models::{ prelude::*, job_category::JobCategory },
+    pagination::MAX_PAGE_SIZE,
+    repository::ItemQuery,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct JobCategoryQuery;
+
+#[Object]
+impl JobCategoryQuery {
+    async fn job_categories(&self, ctx: &Context<'_>) -> Result<Vec<JobCategory>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let page = repo
+            .query::<JobCategory>(&ItemQuery::scan(), MAX_PAGE_SIZE, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_entities())
+    }
+
+    /// Looks up a category by id, or by slug when no id is given.
+    async fn job_category(
+        &self,
+        ctx: &Context<'_>,
+        id: Option<ID>,
+        slug: Option<String>
+    ) -> Result<Option<JobCategory>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        match (id, slug) {
+            (Some(id), _) =>
+                repo.get::<JobCategory>(id.to_string()).await.map_err(|e| e.to_graphql_error()),
+            (None, Some(slug)) =>
+                JobCategory::find_by_slug(repo, &slug).await.map_err(|e| e.to_graphql_error()),
+            (None, None) => Ok(None),
+        }
+    }
+}
@@ -1,31 +1,216 @@
+use async_graphql::{ connection::Connection, ID };
+
 use crate::{
+    context::ContextExtensions,
     error::AppError,
-    models::{ prelude::*, job_posting::JobPosting },
-    DbClient,
+    models::{
+        prelude::*,
+        coordinates::Coordinates,
+        job_category::JobCategory,
+        job_posting::{ JobPosting, JobPostingFilter, NearbyJobPosting },
+    },
+    pagination::{ self, Cursor },
+    repository::ItemQuery,
+    search::{ self, SearchHit },
+    AppResult,
     Repository,
 };
 
+/// Most postings `jobPostings` returns when no `limit` is given.
+const MAX_UNPAGED_JOB_POSTINGS: i32 = 1000;
+
+/// Builds the query for `filter`, resolving its category slug and employer
+/// name to ids. Returns `None` when either names nothing, or the employer
+/// name and id disagree, so nothing can match.
+async fn filter_query(
+    repo: &Repository,
+    filter: Option<JobPostingFilter>
+) -> AppResult<Option<ItemQuery>> {
+    let mut filter = filter.unwrap_or_default();
+
+    if let Some(name) = &filter.employer_name {
+        let Some(employer) = Employer::find_by_name(repo, name).await? else {
+            return Ok(None);
+        };
+
+        if filter.employer_id.as_ref().is_some_and(|id| id.as_str() != employer.id) {
+            return Ok(None);
+        }
+
+        filter.employer_id = Some(ID(employer.id));
+    }
+
+    let category_id = match &filter.category_slug {
+        Some(slug) =>
+            match JobCategory::find_by_slug(repo, slug).await? {
+                Some(category) => Some(category.id),
+                None => {
+                    return Ok(None);
+                }
+            }
+        None => None,
+    };
+
+    filter.to_query(category_id.as_deref(), Utc::now()).map(Some)
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct JobPostingQuery;
 
 #[Object]
 impl JobPostingQuery {
+    /// Looks up a single job posting by id.
+    ///
+    /// Unknown ids resolve to `null`, unless `strict` is set, in which case a
+    /// `NOT_FOUND` error is returned instead. Drafts, scheduled and archived
+    /// postings, and postings held for moderation, are only visible to their
+    /// employer and site admins.
+    async fn job_posting(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        strict: Option<bool>
+    ) -> Result<Option<JobPosting>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let job_posting = repo
+            .get::<JobPosting>(id.to_string()).await
+            .map_err(|e| e.to_graphql_error())?
+            .filter(|job_posting| {
+                job_posting.is_public(Utc::now()) ||
+                    ctx
+                        .principal()
+                        .is_ok_and(|principal| principal.acts_for(job_posting.employer_id.as_deref()))
+            });
+
+        if job_posting.is_none() && strict.unwrap_or(false) {
+            return Err(
+                AppError::NotFound(format!("Job posting {} does not exist", id.as_str())).to_graphql_error()
+            );
+        }
+
+        Ok(job_posting)
+    }
+
+    /// Job postings that are live now, i.e. published and not yet expired.
+    ///
+    /// `limit` is clamped like a page size. Without it up to 1000 matching
+    /// postings are returned; use `jobPostingsConnection` to page through
+    /// larger result sets.
     async fn job_postings(
         &self,
         ctx: &Context<'_>,
-        limit: Option<i32>
+        limit: Option<i32>,
+        filter: Option<JobPostingFilter>
     ) -> Result<Vec<JobPosting>, Error> {
-        let db_client = ctx.data::<DbClient>().map_err(|e| {
-            warn!("Failed to get db_client from context: {:?}", e);
-            AppError::InternalServerError(
-                "Failed to access application db_client".to_string()
-            ).to_graphql_error()
-        })?;
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let Some(query) = filter_query(repo, filter).await.map_err(|e| e.to_graphql_error())? else {
+            return Ok(Vec::new());
+        };
 
-        let repo = Repository::new(db_client.clone());
+        let limit = match limit {
+            Some(limit) => pagination::page_size(Some(limit)),
+            None => MAX_UNPAGED_JOB_POSTINGS,
+        };
+
+        let page = repo
+            .query::<JobPosting>(&query, limit, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_entities())
+    }
+
+    /// Relay-style paginated listing of the job postings that are live now.
+    async fn job_postings_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        filter: Option<JobPostingFilter>
+    ) -> Result<Connection<Cursor, JobPosting>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let Some(query) = filter_query(repo, filter).await.map_err(|e| e.to_graphql_error())? else {
+            return Ok(Connection::new(has_previous_page, false));
+        };
+
+        let page = repo
+            .query::<JobPosting>(&query, pagination::page_size(first), after).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+
+    /// Live job postings in descending creation order, optionally limited to
+    /// those created at or after `since`.
+    async fn latest_job_postings(
+        &self,
+        ctx: &Context<'_>,
+        since: Option<DateTime<Utc>>,
+        first: Option<i32>,
+        after: Option<String>
+    ) -> Result<Connection<Cursor, JobPosting>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let after = pagination::parse_after(after.as_deref()).map_err(|e| e.to_graphql_error())?;
+        let has_previous_page = after.is_some();
+
+        let page = repo
+            .query::<JobPosting>(
+                &JobPosting::latest_query(since, Utc::now()),
+                pagination::page_size(first),
+                after
+            ).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_connection(has_previous_page))
+    }
+
+    /// Live job postings within `radiusKm` kilometres of the point at `lat`,
+    /// `lng`, nearest first. Postings that could not be located are left out.
+    async fn job_postings_near(
+        &self,
+        ctx: &Context<'_>,
+        lat: f64,
+        lng: f64,
+        radius_km: f64,
+        limit: Option<i32>
+    ) -> Result<Vec<NearbyJobPosting>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let center = Coordinates::new(lat, lng).map_err(|e| e.to_graphql_error())?;
+        let limit = pagination::page_size(limit.or(Some(pagination::MAX_PAGE_SIZE)));
+
+        let mut nearby = JobPosting
+            ::find_near(repo, center, radius_km, Utc::now()).await
+            .map_err(|e| e.to_graphql_error())?;
+        nearby.truncate(limit as usize);
+
+        Ok(nearby)
+    }
+
+    /// Live job postings matching the keywords in `query`, most relevant
+    /// first, with highlighted excerpts of the matching fields.
+    ///
+    /// Searches the title, description, responsibilities and experience
+    /// requirements. Words are matched by their stem, so `welding` finds
+    /// `welds`, and common words such as `the` are ignored.
+    async fn search_job_postings(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        limit: Option<i32>
+    ) -> Result<Vec<SearchHit>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
 
-        let job_postings = repo.list::<JobPosting>(limit).await.map_err(|e| e.to_graphql_error())?;
+        let limit = pagination::page_size(limit.or(Some(pagination::DEFAULT_PAGE_SIZE)));
 
-        Ok(job_postings)
+        search
+            ::search_job_postings(repo, &query, limit as usize, Utc::now()).await
+            .map_err(|e| e.to_graphql_error())
     }
 }
@@ -1,6 +1,16 @@
 use async_graphql::MergedObject;
 
+pub mod employer;
+pub mod job_application;
+pub mod job_category;
 pub mod job_posting;
+pub mod user;
 
 #[derive(Debug, Default, MergedObject)]
-pub struct QueryRoot(job_posting::JobPostingQuery);
+pub struct QueryRoot(
+    job_posting::JobPostingQuery,
+    job_application::JobApplicationQuery,
+    job_category::JobCategoryQuery,
+    employer::EmployerQuery,
+    user::UserQuery,
+);
@@ -0,0 +1,19 @@
+use crate::{ context::ContextExtensions, models::prelude::*, AppError };
+
+#[derive(Debug, Default)]
+pub(crate) struct UserQuery;
+
+#[Object]
+impl UserQuery {
+    /// The authenticated user.
+    async fn me(&self, ctx: &Context<'_>) -> Result<User, Error> {
+        let principal = ctx.principal().map_err(|e| e.to_graphql_error())?;
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        repo
+            .get::<User>(principal.subject.clone()).await
+            .map_err(|e| e.to_graphql_error())?
+            .ok_or_else(|| AppError::Unauthorized("Account no longer exists".to_string()).to_graphql_error())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::models::{ prelude::*, coordinates::Coordinates };
+
+#[Object]
+impl Coordinates {
+    async fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    async fn longitude(&self) -> f64 {
+        self.longitude
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{ models::prelude::*, verification };
+
+#[Object]
+impl Employer {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+    async fn name(&self) -> &str {
+        &self.name
+    }
+    async fn description(&self) -> &Option<String> {
+        &self.description
+    }
+    async fn website_url(&self) -> &Option<String> {
+        &self.website_url
+    }
+    async fn logo_url(&self) -> &Option<String> {
+        &self.logo_url
+    }
+    async fn domain(&self) -> &Option<String> {
+        &self.domain
+    }
+    /// Whether ownership of `domain` has been proven.
+    async fn domain_verified(&self) -> bool {
+        self.is_domain_verified()
+    }
+    async fn domain_verified_at(&self) -> &Option<DateTime<Utc>> {
+        &self.domain_verified_at
+    }
+    async fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    async fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Instructions returned by `requestDomainChallenge`. Publish the token in
+/// either place, then call `verifyDomain` with the matching method.
+pub struct DomainChallenge {
+    pub domain: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[Object]
+impl DomainChallenge {
+    async fn domain(&self) -> &str {
+        &self.domain
+    }
+    async fn token(&self) -> &str {
+        &self.token
+    }
+    async fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+    /// Name of the TXT record to create for `DNS_TXT` verification.
+    async fn dns_record_name(&self) -> String {
+        verification::dns_record_name(&self.domain)
+    }
+    /// Value of the TXT record.
+    async fn dns_record_value(&self) -> String {
+        verification::dns_record_value(&self.token)
+    }
+    /// Where to serve the token as plain text for `WELL_KNOWN_FILE` verification.
+    async fn well_known_url(&self) -> String {
+        verification::well_known_url(&self.domain)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::models::{ prelude::*, job_application::{ ApplicationStatus, StatusChange } };
+
+#[Object]
+impl JobApplication {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+    async fn job_posting_id(&self) -> &str {
+        &self.job_posting_id
+    }
+    async fn applicant_name(&self) -> &str {
+        &self.applicant_name
+    }
+    async fn applicant_email(&self) -> &str {
+        &self.applicant_email
+    }
+    async fn cover_note(&self) -> &Option<String> {
+        &self.cover_note
+    }
+    async fn status(&self) -> &ApplicationStatus {
+        &self.status
+    }
+    async fn status_history(&self) -> &Vec<StatusChange> {
+        &self.status_history
+    }
+    async fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+#[Object]
+impl StatusChange {
+    async fn from(&self) -> &Option<ApplicationStatus> {
+        &self.from
+    }
+    async fn to(&self) -> &ApplicationStatus {
+        &self.to
+    }
+    async fn changed_by(&self) -> &str {
+        &self.changed_by
+    }
+    async fn changed_at(&self) -> &DateTime<Utc> {
+        &self.changed_at
+    }
+    async fn note(&self) -> &Option<String> {
+        &self.note
+    }
+}
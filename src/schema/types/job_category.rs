@@ -0,0 +1,42 @@
+use crate::{ context::ContextExtensions, models::prelude::*, pagination::MAX_PAGE_SIZE };
+
+#[Object]
+impl JobCategory {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+    async fn name(&self) -> &str {
+        &self.name
+    }
+    async fn slug(&self) -> &str {
+        &self.slug
+    }
+    async fn parent_id(&self) -> &Option<String> {
+        &self.parent_id
+    }
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<JobCategory>, Error> {
+        let Some(parent_id) = &self.parent_id else {
+            return Ok(None);
+        };
+
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        repo.get::<JobCategory>(parent_id.clone()).await.map_err(|e| e.to_graphql_error())
+    }
+    async fn children(&self, ctx: &Context<'_>) -> Result<Vec<JobCategory>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        let page = repo
+            .query::<JobCategory>(&JobCategory::children_query(&self.id), MAX_PAGE_SIZE, None).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        Ok(page.into_entities())
+    }
+    async fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
@@ -1,4 +1,14 @@
-use crate::models::{ prelude::*, job_posting::{ JobTypeOption, ExpectedHoursRange } };
+use crate::{
+    context::ContextExtensions,
+    json_ld,
+    models::{
+        prelude::*,
+        coordinates::Coordinates,
+        job_posting::{ JobTypeOption, ExpectedHoursRange, PostingStatus },
+        pay::AnnualPayRange,
+        work_arrangement::WorkArrangement,
+    },
+};
 
 #[Object]
 impl JobPosting {
@@ -8,18 +18,63 @@ impl JobPosting {
     async fn job_title(&self) -> &str {
         &self.job_title
     }
-    async fn employer_name(&self) -> &str {
-        &self.employer_name
+    async fn employer_id(&self) -> &Option<String> {
+        &self.employer_id
     }
-    async fn employer_url(&self) -> &str {
-        &self.employer_url
+    /// The employer offering this job, loaded in batches across a page of postings.
+    async fn employer(&self, ctx: &Context<'_>) -> Result<Option<Employer>, Error> {
+        let Some(employer_id) = &self.employer_id else {
+            return Ok(None);
+        };
+
+        let loader = ctx.employer_loader().map_err(|e| e.to_graphql_error())?;
+
+        loader.load_one(employer_id.clone()).await
+    }
+    /// Whether the employer has proven ownership of its domain.
+    async fn verified(&self, ctx: &Context<'_>) -> Result<bool, Error> {
+        Ok(self.employer(ctx).await?.is_some_and(|employer| employer.is_domain_verified()))
+    }
+    async fn held_for_moderation(&self) -> bool {
+        self.held_for_moderation
+    }
+    /// Current lifecycle status. Published postings past their expiry read as
+    /// `EXPIRED` right away.
+    async fn status(&self) -> PostingStatus {
+        self.status_at(Utc::now())
+    }
+    async fn publish_at(&self) -> &Option<DateTime<Utc>> {
+        &self.publish_at
+    }
+    async fn expires_at(&self) -> &Option<DateTime<Utc>> {
+        &self.expires_at
+    }
+    async fn closed_at(&self) -> &Option<DateTime<Utc>> {
+        &self.closed_at
+    }
+    #[graphql(deprecation = "Use `employer { name }`")]
+    async fn employer_name(&self, ctx: &Context<'_>) -> Result<Option<String>, Error> {
+        Ok(self.employer(ctx).await?.map(|employer| employer.name))
+    }
+    #[graphql(deprecation = "Use `employer { websiteUrl }`")]
+    async fn employer_url(&self, ctx: &Context<'_>) -> Result<Option<String>, Error> {
+        Ok(self.employer(ctx).await?.and_then(|employer| employer.website_url))
     }
     async fn address(&self) -> &Address {
         &self.address
     }
+    /// Where the job is, or `null` if the address could not be located.
+    async fn coordinates(&self) -> &Option<Coordinates> {
+        &self.coordinates
+    }
     async fn pay(&self) -> &Option<Pay> {
         &self.pay
     }
+    /// The pay as a yearly range, so postings with different pay cadences can
+    /// be compared. Hourly and daily pay are scaled by the expected hours.
+    async fn annualized_pay(&self) -> Option<AnnualPayRange> {
+        self.pay.as_ref().and_then(|pay| pay.annualized(&self.expected_hours))
+    }
     async fn job_type(&self) -> &JobTypeOption {
         &self.job_type
     }
@@ -41,6 +96,40 @@ impl JobPosting {
     async fn expected_hours(&self) -> &ExpectedHoursRange {
         &self.expected_hours
     }
+    async fn work_arrangement(&self) -> &WorkArrangement {
+        &self.work_arrangement
+    }
+    async fn category_ids(&self) -> &Vec<String> {
+        &self.category_ids
+    }
+    /// Categories this posting is listed under. Ids of deleted categories are skipped.
+    async fn categories(&self, ctx: &Context<'_>) -> Result<Vec<JobCategory>, Error> {
+        let repo = ctx.repository().map_err(|e| e.to_graphql_error())?;
+
+        // A batch get rejects repeated keys, so each id is fetched only once.
+        let mut ids = self.category_ids.clone();
+        ids.sort();
+        ids.dedup();
+
+        let mut found = repo
+            .get_many::<JobCategory>(&ids).await
+            .map_err(|e| e.to_graphql_error())?;
+
+        let categories = self.category_ids
+            .iter()
+            .filter_map(|id| found.remove(id))
+            .collect();
+
+        Ok(categories)
+    }
+    /// schema.org `JobPosting` structured data for search engines, serialized
+    /// and escaped so it can be embedded as is in an `application/ld+json`
+    /// script element of the listing page.
+    async fn json_ld(&self, ctx: &Context<'_>) -> Result<String, Error> {
+        let employer = self.employer(ctx).await?;
+
+        Ok(json_ld::serialize(&json_ld::job_posting(self, employer.as_ref())))
+    }
     async fn created_at(&self) -> &DateTime<Utc> {
         &self.created_at
     }
@@ -1,3 +1,9 @@
 pub mod address;
+pub mod coordinates;
+pub mod employer;
+pub mod job_application;
+pub mod job_category;
 pub mod job_posting;
 pub mod pay;
+pub mod user;
+pub mod work_arrangement;
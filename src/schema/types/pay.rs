@@ -1,3 +1,5 @@
+use rust_decimal::{ prelude::ToPrimitive, Decimal };
+
 use crate::models::{ prelude::*, pay::CadenceOption };
 
 #[Object]
@@ -6,7 +8,21 @@ impl Pay {
         &self.cadence
     }
 
-    async fn min_base_pay(&self) -> &u32 {
-        &self.min_base_pay
+    async fn min(&self) -> &Decimal {
+        &self.min
+    }
+
+    async fn max(&self) -> &Option<Decimal> {
+        &self.max
+    }
+
+    /// ISO 4217 currency code.
+    async fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    #[graphql(deprecation = "Use `min`")]
+    async fn min_base_pay(&self) -> u32 {
+        self.min.trunc().to_u32().unwrap_or(u32::MAX)
     }
 }
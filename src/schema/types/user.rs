@@ -0,0 +1,51 @@
+use crate::models::{ prelude::*, user::Role };
+
+#[Object]
+impl User {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+    async fn email(&self) -> &str {
+        &self.email
+    }
+    async fn role(&self) -> Role {
+        self.role
+    }
+    async fn employer_id(&self) -> &Option<String> {
+        &self.employer_id
+    }
+    async fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Tokens returned by `register`, `login` and `refreshToken`.
+pub struct AuthPayload {
+    pub access_token: String,
+    pub refresh_token: String,
+    // Access token lifetime in seconds
+    pub expires_in: u64,
+    pub user: User,
+}
+
+#[Object]
+impl AuthPayload {
+    /// Bearer token for the `Authorization` header.
+    async fn access_token(&self) -> &str {
+        &self.access_token
+    }
+    /// Single-use token for `refreshToken`; a new one is returned on each refresh.
+    async fn refresh_token(&self) -> &str {
+        &self.refresh_token
+    }
+    async fn expires_in(&self) -> u64 {
+        self.expires_in
+    }
+    async fn user(&self) -> &User {
+        &self.user
+    }
+}
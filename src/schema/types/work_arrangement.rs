@@ -0,0 +1,34 @@
+use crate::models::{
+    prelude::*,
+    work_arrangement::{ format_utc_offset, UtcOffsetRange, WorkArrangement, WorkArrangementType },
+};
+
+#[Object]
+impl WorkArrangement {
+    #[graphql(name = "type")]
+    async fn arrangement_type(&self) -> &WorkArrangementType {
+        &self.arrangement_type
+    }
+
+    /// ISO 3166-1 alpha-2 codes of the countries remote staff may live in.
+    /// Empty when any country is allowed.
+    async fn countries(&self) -> &Vec<String> {
+        &self.countries
+    }
+
+    /// Time zones remote staff may work from, or `null` for any.
+    async fn utc_offsets(&self) -> &Option<UtcOffsetRange> {
+        &self.utc_offsets
+    }
+}
+
+#[Object]
+impl UtcOffsetRange {
+    async fn earliest(&self) -> String {
+        format_utc_offset(self.earliest)
+    }
+
+    async fn latest(&self) -> String {
+        format_utc_offset(self.latest)
+    }
+}
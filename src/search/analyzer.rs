@@ -0,0 +1,292 @@
+//! Turns text into index terms.
+//!
+//! Text is split into runs of letters and digits, lowercased, stripped of
+//! English stop words and reduced to its stem with the Porter algorithm, so
+//! "Welding", "welds" and "weld" all meet on the term `weld`. The algorithm
+//! only strips suffixes from longer stems, so "welders" stays `welder`.
+
+use std::ops::Range;
+
+/// Longer tokens are dropped; they are never real words and would bloat the
+/// index keys.
+const MAX_TOKEN_LENGTH: usize = 64;
+
+/// Words too common to tell postings apart.
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can", "for", "from",
+    "has", "have", "if", "in", "into", "is", "it", "its", "no", "not", "of", "on", "or", "our",
+    "such", "that", "the", "their", "then", "there", "these", "they", "this", "to", "was", "we",
+    "were", "will", "with", "you", "your",
+];
+
+/// A term and where its token sits in the analyzed text, in bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub term: String,
+    pub span: Range<usize>,
+}
+
+/// Every index term of `text`, with its position.
+pub fn tokens(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => {
+                start = Some(i);
+            }
+            (Some(from), false) => {
+                start = None;
+
+                if let Some(term) = analyze_word(&text[from..i]) {
+                    tokens.push(Token { term, span: from..i });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// The index terms of `text`, in order, repeats included.
+pub fn terms(text: &str) -> Vec<String> {
+    tokens(text)
+        .into_iter()
+        .map(|token| token.term)
+        .collect()
+}
+
+fn analyze_word(word: &str) -> Option<String> {
+    if word.len() > MAX_TOKEN_LENGTH {
+        return None;
+    }
+
+    let word = word.to_lowercase();
+
+    if STOP_WORDS.contains(&word.as_str()) {
+        return None;
+    }
+
+    Some(stem(&word))
+}
+
+/// Reduces a lowercase word to its stem with the Porter (1980) algorithm.
+/// Words that are not plain ASCII letters are returned unchanged.
+pub fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut w = word.as_bytes().to_vec();
+
+    step_1a(&mut w);
+    step_1b(&mut w);
+    step_1c(&mut w);
+    step_2(&mut w);
+    step_3(&mut w);
+    step_4(&mut w);
+    step_5(&mut w);
+
+    String::from_utf8_lossy(&w).into_owned()
+}
+
+fn is_consonant(w: &[u8], i: usize) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(w, i - 1),
+        _ => true,
+    }
+}
+
+/// The number of vowel-consonant sequences in `w`, Porter's m.
+fn measure(w: &[u8]) -> usize {
+    let mut m = 0;
+    let mut previous_vowel = false;
+
+    for i in 0..w.len() {
+        let vowel = !is_consonant(w, i);
+
+        if previous_vowel && !vowel {
+            m += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    m
+}
+
+fn contains_vowel(w: &[u8]) -> bool {
+    (0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+fn ends_with_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+
+    n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+/// Consonant, vowel, consonant at the end, the last not w, x or y.
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+
+    n >= 3 &&
+        is_consonant(w, n - 3) &&
+        !is_consonant(w, n - 2) &&
+        is_consonant(w, n - 1) &&
+        !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+/// If `w` ends with `suffix`, the length of the stem before it.
+fn stem_length(w: &[u8], suffix: &str) -> Option<usize> {
+    w.ends_with(suffix.as_bytes()).then(|| w.len() - suffix.len())
+}
+
+fn replace_suffix(w: &mut Vec<u8>, stem_length: usize, replacement: &str) {
+    w.truncate(stem_length);
+    w.extend_from_slice(replacement.as_bytes());
+}
+
+/// Replaces the first of `rules` whose suffix `w` ends with, if the remaining
+/// stem has a measure above `min_measure`. Later rules are not tried once a
+/// suffix matches.
+fn apply_rules(w: &mut Vec<u8>, rules: &[(&str, &str)], min_measure: usize) {
+    for (suffix, replacement) in rules {
+        if let Some(n) = stem_length(w, suffix) {
+            if measure(&w[..n]) > min_measure {
+                replace_suffix(w, n, replacement);
+            }
+            return;
+        }
+    }
+}
+
+fn step_1a(w: &mut Vec<u8>) {
+    if let Some(n) = stem_length(w, "sses") {
+        replace_suffix(w, n, "ss");
+    } else if let Some(n) = stem_length(w, "ies") {
+        replace_suffix(w, n, "i");
+    } else if w.ends_with(b"ss") {
+        // unchanged
+    } else if w.ends_with(b"s") {
+        w.pop();
+    }
+}
+
+fn step_1b(w: &mut Vec<u8>) {
+    if let Some(n) = stem_length(w, "eed") {
+        if measure(&w[..n]) > 0 {
+            replace_suffix(w, n, "ee");
+        }
+        return;
+    }
+
+    let Some(n) = stem_length(w, "ed").or_else(|| stem_length(w, "ing")) else {
+        return;
+    };
+
+    if !contains_vowel(&w[..n]) {
+        return;
+    }
+
+    w.truncate(n);
+
+    if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+        w.push(b'e');
+    } else if ends_with_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+fn step_1c(w: &mut Vec<u8>) {
+    if let Some(n) = stem_length(w, "y") && contains_vowel(&w[..n]) {
+        replace_suffix(w, n, "i");
+    }
+}
+
+fn step_2(w: &mut Vec<u8>) {
+    apply_rules(
+        w,
+        &[
+            ("ational", "ate"),
+            ("tional", "tion"),
+            ("enci", "ence"),
+            ("anci", "ance"),
+            ("izer", "ize"),
+            ("bli", "ble"),
+            ("alli", "al"),
+            ("entli", "ent"),
+            ("eli", "e"),
+            ("ousli", "ous"),
+            ("ization", "ize"),
+            ("ation", "ate"),
+            ("ator", "ate"),
+            ("alism", "al"),
+            ("iveness", "ive"),
+            ("fulness", "ful"),
+            ("ousness", "ous"),
+            ("aliti", "al"),
+            ("iviti", "ive"),
+            ("biliti", "ble"),
+            ("logi", "log"),
+        ],
+        0
+    );
+}
+
+fn step_3(w: &mut Vec<u8>) {
+    apply_rules(
+        w,
+        &[
+            ("icate", "ic"),
+            ("ative", ""),
+            ("alize", "al"),
+            ("iciti", "ic"),
+            ("ical", "ic"),
+            ("ful", ""),
+            ("ness", ""),
+        ],
+        0
+    );
+}
+
+fn step_4(w: &mut Vec<u8>) {
+    const SUFFIXES: &[&str] = &[
+        "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+        "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+    ];
+
+    // Of the suffixes that match, only the longest counts
+    let Some((suffix, n)) = SUFFIXES.iter()
+        .filter_map(|suffix| stem_length(w, suffix).map(|n| (*suffix, n)))
+        .min_by_key(|(_, n)| *n) else {
+        return;
+    };
+
+    let stem = &w[..n];
+
+    if suffix == "ion" && !matches!(stem.last(), Some(b's' | b't')) {
+        return;
+    }
+
+    if measure(stem) > 1 {
+        w.truncate(n);
+    }
+}
+
+fn step_5(w: &mut Vec<u8>) {
+    if let Some(n) = stem_length(w, "e") {
+        let m = measure(&w[..n]);
+
+        if m > 1 || (m == 1 && !ends_cvc(&w[..n])) {
+            w.truncate(n);
+        }
+    }
+
+    if measure(w) > 1 && ends_with_double_consonant(w) && w.ends_with(b"l") {
+        w.pop();
+    }
+}
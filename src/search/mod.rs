@@ -0,0 +1,566 @@
+//! Keyword search over job postings.
+//!
+//! The JobPostingTerms table holds an inverted index of the title,
+//! description, responsibilities and experience requirements of every
+//! posting. It holds three kinds of items:
+//!
+//! - one per term and posting, carrying the term's frequency in the posting;
+//!   `TermIndex` groups them into the postings list of each term
+//! - one document item per posting, listing its terms, so an update can delete
+//!   the terms it no longer contains
+//! - a single statistics item with the number of indexed postings and their
+//!   total length, which BM25 ranking needs
+//!
+//! The mutations that create, update and delete postings keep the index in
+//! step. If one of those writes fails, the posting is still saved and the index
+//! catches up on the next edit, or when `job_board_lambda reindex` rebuilds it.
+
+use std::collections::{ HashMap, HashSet };
+
+use async_graphql::{ Enum, SimpleObject };
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use tracing::{ info, warn };
+
+use crate::{
+    db::search_tables::{ JOB_POSTING_TERMS_TABLE, TERM_INDEX },
+    models::job_posting::JobPosting,
+    pagination::MAX_PAGE_SIZE,
+    repository::{ Filter, ItemQuery },
+    AppError,
+    DynamoDbEntity,
+    Repository,
+};
+
+pub mod analyzer;
+pub mod snippet;
+
+/// BM25 term frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document length normalization.
+const B: f64 = 0.75;
+/// A term in the job title counts as this many occurrences elsewhere.
+const TITLE_WEIGHT: u32 = 3;
+/// Longest query accepted, in characters.
+const MAX_QUERY_LENGTH: usize = 256;
+/// Most distinct terms a query may analyze to; each one is a postings list read.
+const MAX_QUERY_TERMS: usize = 16;
+/// Attempts at the optimistic update of the statistics item.
+const STATS_ATTEMPTS: usize = 10;
+
+const KIND_ATTRIBUTE: &str = "kind";
+const DOCUMENT_KIND: &str = "DOCUMENT";
+const STATS_ID: &str = "stats";
+
+/// The parts of a posting that are searched.
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub enum SearchField {
+    JobTitle,
+    JobDescription,
+    EmployeeResponsibilities,
+    ExperienceRequirements,
+}
+
+/// A highlighted excerpt of a field that matched the query.
+#[derive(Clone, Debug, SimpleObject)]
+pub struct SearchHighlight {
+    pub field: SearchField,
+    /// HTML-escaped excerpt with the matching words wrapped in `<mark>`
+    pub snippet: String,
+}
+
+#[derive(Clone, Debug, SimpleObject)]
+pub struct SearchHit {
+    pub job_posting: JobPosting,
+    /// BM25 relevance; only meaningful relative to the other hits
+    pub score: f64,
+    pub highlights: Vec<SearchHighlight>,
+}
+
+/// The searched text of a posting, field by field. List fields contribute
+/// one entry per item.
+fn searched_text(job_posting: &JobPosting) -> Vec<(SearchField, &str)> {
+    let mut text = vec![
+        (SearchField::JobTitle, job_posting.job_title.as_str()),
+        (SearchField::JobDescription, job_posting.job_description.as_str())
+    ];
+
+    for item in job_posting.employee_responsibilities.iter().flatten() {
+        text.push((SearchField::EmployeeResponsibilities, item.as_str()));
+    }
+    for item in job_posting.experience_requirements.iter().flatten() {
+        text.push((SearchField::ExperienceRequirements, item.as_str()));
+    }
+
+    text
+}
+
+/// Weighted frequency of every term of the posting, and the posting's
+/// weighted length.
+fn term_frequencies(job_posting: &JobPosting) -> (HashMap<String, u32>, u32) {
+    let mut frequencies = HashMap::new();
+    let mut length = 0;
+
+    for (field, text) in searched_text(job_posting) {
+        let weight = if field == SearchField::JobTitle { TITLE_WEIGHT } else { 1 };
+
+        for term in analyzer::terms(text) {
+            *frequencies.entry(term).or_insert(0) += weight;
+            length += weight;
+        }
+    }
+
+    (frequencies, length)
+}
+
+/// One posting's entry in the postings list of a term.
+#[derive(Clone, Debug)]
+struct IndexedTerm {
+    term: String,
+    job_posting_id: String,
+    term_frequency: u32,
+    document_length: u32,
+}
+
+impl IndexedTerm {
+    fn id(job_posting_id: &str, term: &str) -> String {
+        format!("{}#{}", job_posting_id, term)
+    }
+
+    fn postings_query(term: &str) -> ItemQuery {
+        ItemQuery::index(TERM_INDEX, "term", AttributeValue::S(term.to_string()))
+    }
+}
+
+impl DynamoDbEntity for IndexedTerm {
+    fn table_name() -> &'static str {
+        JOB_POSTING_TERMS_TABLE
+    }
+
+    fn primary_key(&self) -> String {
+        Self::id(&self.job_posting_id, &self.term)
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let number = |name: &str| item.get(name)?.as_n().ok()?.parse::<u32>().ok();
+
+        Some(Self {
+            term: item.get("term")?.as_s().ok()?.to_string(),
+            job_posting_id: item.get("job_posting_id")?.as_s().ok()?.to_string(),
+            term_frequency: number("term_frequency")?,
+            document_length: number("document_length")?,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S(self.primary_key())),
+            ("term".to_string(), AttributeValue::S(self.term.clone())),
+            ("job_posting_id".to_string(), AttributeValue::S(self.job_posting_id.clone())),
+            ("term_frequency".to_string(), AttributeValue::N(self.term_frequency.to_string())),
+            ("document_length".to_string(), AttributeValue::N(self.document_length.to_string())),
+        ])
+    }
+}
+
+/// The terms a posting was last indexed with.
+#[derive(Clone, Debug)]
+struct IndexedDocument {
+    job_posting_id: String,
+    terms: Vec<String>,
+    length: u32,
+}
+
+impl IndexedDocument {
+    fn id(job_posting_id: &str) -> String {
+        format!("document#{}", job_posting_id)
+    }
+}
+
+impl DynamoDbEntity for IndexedDocument {
+    fn table_name() -> &'static str {
+        JOB_POSTING_TERMS_TABLE
+    }
+
+    fn primary_key(&self) -> String {
+        Self::id(&self.job_posting_id)
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        if item.get(KIND_ATTRIBUTE)?.as_s().ok()? != DOCUMENT_KIND {
+            return None;
+        }
+
+        let terms = item
+            .get("terms")
+            .and_then(|v| v.as_l().ok())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|av|
+                        av
+                            .as_s()
+                            .ok()
+                            .map(|s| s.to_string())
+                    )
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            job_posting_id: item.get("job_posting_id")?.as_s().ok()?.to_string(),
+            terms,
+            length: item.get("length")?.as_n().ok()?.parse::<u32>().ok()?,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S(self.primary_key())),
+            (KIND_ATTRIBUTE.to_string(), AttributeValue::S(DOCUMENT_KIND.to_string())),
+            ("job_posting_id".to_string(), AttributeValue::S(self.job_posting_id.clone())),
+            (
+                "terms".to_string(),
+                AttributeValue::L(
+                    self.terms
+                        .iter()
+                        .map(|term| AttributeValue::S(term.clone()))
+                        .collect()
+                ),
+            ),
+            ("length".to_string(), AttributeValue::N(self.length.to_string())),
+        ])
+    }
+}
+
+/// Collection statistics for BM25. `version` guards concurrent updates.
+#[derive(Clone, Debug, Default)]
+struct IndexStats {
+    document_count: u64,
+    total_length: u64,
+    version: u64,
+}
+
+impl IndexStats {
+    fn average_length(&self) -> f64 {
+        if self.document_count == 0 {
+            return 0.0;
+        }
+
+        (self.total_length as f64) / (self.document_count as f64)
+    }
+}
+
+impl DynamoDbEntity for IndexStats {
+    fn table_name() -> &'static str {
+        JOB_POSTING_TERMS_TABLE
+    }
+
+    fn primary_key(&self) -> String {
+        STATS_ID.to_string()
+    }
+
+    fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let number = |name: &str| item.get(name)?.as_n().ok()?.parse::<u64>().ok();
+
+        Some(Self {
+            document_count: number("document_count")?,
+            total_length: number("total_length")?,
+            version: number("version")?,
+        })
+    }
+
+    fn to_item(&self) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S(STATS_ID.to_string())),
+            ("document_count".to_string(), AttributeValue::N(self.document_count.to_string())),
+            ("total_length".to_string(), AttributeValue::N(self.total_length.to_string())),
+            ("version".to_string(), AttributeValue::N(self.version.to_string())),
+        ])
+    }
+}
+
+/// Adds `documents` and `length`, either of which may be negative, to the
+/// statistics item, retrying when another writer got there first.
+async fn adjust_stats(repo: &Repository, documents: i64, length: i64) -> Result<(), AppError> {
+    if documents == 0 && length == 0 {
+        return Ok(());
+    }
+
+    for _ in 0..STATS_ATTEMPTS {
+        let current = repo.get::<IndexStats>(STATS_ID.to_string()).await?;
+
+        let base = current.clone().unwrap_or_default();
+        let stats = IndexStats {
+            document_count: base.document_count.saturating_add_signed(documents),
+            total_length: base.total_length.saturating_add_signed(length),
+            version: base.version + 1,
+        };
+
+        let result = match current {
+            Some(current) => {
+                let expected = AttributeValue::N(current.version.to_string());
+                repo.update_if(stats, "version", expected).await
+            }
+            None => repo.create(stats).await,
+        };
+
+        match result {
+            Ok(_) => {
+                return Ok(());
+            }
+            // Lost the race; read the new version and try again
+            Err(AppError::ValidationError(_)) => {}
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    Err(AppError::DatabaseError("Search index statistics are under contention".to_string()))
+}
+
+/// Brings the index entries of a posting up to date with its text.
+pub async fn index_job_posting(repo: &Repository, job_posting: &JobPosting) -> Result<(), AppError> {
+    let (frequencies, length) = term_frequencies(job_posting);
+    let previous = repo.get::<IndexedDocument>(IndexedDocument::id(&job_posting.id)).await?;
+
+    let term_items: Vec<IndexedTerm> = frequencies
+        .iter()
+        .map(|(term, term_frequency)| IndexedTerm {
+            term: term.clone(),
+            job_posting_id: job_posting.id.clone(),
+            term_frequency: *term_frequency,
+            document_length: length,
+        })
+        .collect();
+    repo.put_many(&term_items).await?;
+
+    let stale = previous
+        .iter()
+        .flat_map(|document| &document.terms)
+        .filter(|term| !frequencies.contains_key(*term))
+        .map(|term| IndexedTerm::id(&job_posting.id, term))
+        .collect();
+    repo.delete_many::<IndexedTerm>(stale).await?;
+
+    let mut terms: Vec<String> = frequencies.into_keys().collect();
+    terms.sort();
+
+    repo.put(IndexedDocument {
+        job_posting_id: job_posting.id.clone(),
+        terms,
+        length,
+    }).await?;
+
+    match previous {
+        Some(previous) => adjust_stats(repo, 0, (length as i64) - (previous.length as i64)).await,
+        None => adjust_stats(repo, 1, length as i64).await,
+    }
+}
+
+/// Drops a deleted posting from the index.
+pub async fn remove_job_posting(repo: &Repository, job_posting_id: &str) -> Result<(), AppError> {
+    let Some(document) = repo.get::<IndexedDocument>(IndexedDocument::id(job_posting_id)).await? else {
+        return Ok(());
+    };
+
+    let mut ids: Vec<String> = document.terms
+        .iter()
+        .map(|term| IndexedTerm::id(job_posting_id, term))
+        .collect();
+    ids.push(document.primary_key());
+    repo.delete_many::<IndexedTerm>(ids).await?;
+
+    adjust_stats(repo, -1, -(document.length as i64)).await
+}
+
+/// Logs instead of failing when the index cannot follow a write to a
+/// posting; the posting itself is already saved.
+pub async fn index_job_posting_or_warn(repo: &Repository, job_posting: &JobPosting) {
+    if let Err(e) = index_job_posting(repo, job_posting).await {
+        warn!("Failed to index job posting {}: {}", job_posting.id, e);
+    }
+}
+
+/// Reads every entry of the postings list of `term`.
+async fn postings(repo: &Repository, term: &str) -> Result<Vec<IndexedTerm>, AppError> {
+    let query = IndexedTerm::postings_query(term);
+    let mut postings = Vec::new();
+    let mut after = None;
+
+    loop {
+        let page = repo.query::<IndexedTerm>(&query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+        postings.extend(page.into_entities());
+
+        if after.is_none() {
+            return Ok(postings);
+        }
+    }
+}
+
+fn highlights(job_posting: &JobPosting, terms: &HashSet<String>) -> Vec<SearchHighlight> {
+    let mut highlights: Vec<SearchHighlight> = Vec::new();
+
+    for (field, text) in searched_text(job_posting) {
+        // One excerpt per field, from its first matching list item
+        if highlights.iter().any(|highlight| highlight.field == field) {
+            continue;
+        }
+
+        if let Some(snippet) = snippet::snippet(text, terms) {
+            highlights.push(SearchHighlight { field, snippet });
+        }
+    }
+
+    highlights
+}
+
+/// Postings live at `now` that match `query`, best match first.
+///
+/// Each posting is scored with BM25 over the query terms it contains, so a
+/// posting need not contain every term. A query made only of stop words
+/// matches nothing. Queries longer than `MAX_QUERY_LENGTH` characters or with
+/// more than `MAX_QUERY_TERMS` distinct terms are rejected.
+pub async fn search_job_postings(
+    repo: &Repository,
+    query: &str,
+    limit: usize,
+    now: DateTime<Utc>
+) -> Result<Vec<SearchHit>, AppError> {
+    if query.chars().count() > MAX_QUERY_LENGTH {
+        return Err(
+            AppError::ValidationError(
+                format!("Search query cannot exceed {} characters", MAX_QUERY_LENGTH)
+            )
+        );
+    }
+
+    let terms: HashSet<String> = analyzer::terms(query).into_iter().collect();
+
+    if terms.len() > MAX_QUERY_TERMS {
+        return Err(
+            AppError::ValidationError(
+                format!("Search query cannot contain more than {} words", MAX_QUERY_TERMS)
+            )
+        );
+    }
+
+    let Some(stats) = repo.get::<IndexStats>(STATS_ID.to_string()).await? else {
+        return Ok(Vec::new());
+    };
+
+    let document_count = stats.document_count as f64;
+    let average_length = stats.average_length().max(1.0);
+    let mut scores: HashMap<String, f64> = HashMap::new();
+
+    for term in &terms {
+        let postings = postings(repo, term).await?;
+
+        let frequency = postings.len() as f64;
+        let idf = (1.0 + (document_count - frequency + 0.5) / (frequency + 0.5)).ln();
+
+        for posting in postings {
+            let tf = posting.term_frequency as f64;
+            let norm = 1.0 - B + (B * (posting.document_length as f64)) / average_length;
+
+            *scores.entry(posting.job_posting_id).or_insert(0.0) +=
+                (idf * (tf * (K1 + 1.0))) / (tf + K1 * norm);
+        }
+    }
+
+    let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut hits = Vec::new();
+
+    // Held, draft and expired postings are indexed too, so read in pages
+    // until enough live ones turn up
+    for chunk in ranked.chunks(MAX_PAGE_SIZE as usize) {
+        let ids: Vec<String> = chunk
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect();
+        let mut job_postings = repo.get_many::<JobPosting>(&ids).await?;
+
+        for (id, score) in chunk {
+            let Some(job_posting) = job_postings.remove(id) else {
+                continue;
+            };
+
+            if !job_posting.is_live(now) {
+                continue;
+            }
+
+            let highlights = highlights(&job_posting, &terms);
+
+            hits.push(SearchHit { job_posting, score: *score, highlights });
+
+            if hits.len() >= limit {
+                return Ok(hits);
+            }
+        }
+    }
+
+    Ok(hits)
+}
+
+/// Indexes every posting, drops the entries of postings that no longer exist
+/// and recounts the statistics. Returns the number of postings indexed.
+pub async fn reindex_job_postings(repo: &Repository) -> Result<usize, AppError> {
+    let mut indexed = HashSet::new();
+    let mut after = None;
+
+    loop {
+        let page = repo.list::<JobPosting>(MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for job_posting in page.into_entities() {
+            index_job_posting(repo, &job_posting).await?;
+            indexed.insert(job_posting.id);
+        }
+
+        if after.is_none() {
+            break;
+        }
+    }
+
+    let documents_query = ItemQuery::scan().filter(
+        Filter::eq(KIND_ATTRIBUTE, AttributeValue::S(DOCUMENT_KIND.to_string()))
+    );
+    let mut stats = IndexStats::default();
+    let mut orphans = Vec::new();
+    let mut after = None;
+
+    loop {
+        let page = repo.query::<IndexedDocument>(&documents_query, MAX_PAGE_SIZE, after).await?;
+        after = page.next_cursor.clone();
+
+        for document in page.into_entities() {
+            if indexed.contains(&document.job_posting_id) {
+                stats.document_count += 1;
+                stats.total_length += document.length as u64;
+            } else {
+                orphans.push(document.job_posting_id);
+            }
+        }
+
+        if after.is_none() {
+            break;
+        }
+    }
+
+    for job_posting_id in &orphans {
+        info!("Dropping job posting {} from the search index", job_posting_id);
+        remove_job_posting(repo, job_posting_id).await?;
+    }
+
+    // Recounted from the documents, replacing whatever drift the statistics
+    // picked up from failed writes
+    let previous = repo.get::<IndexStats>(STATS_ID.to_string()).await?;
+    stats.version = previous.map(|previous| previous.version + 1).unwrap_or(1);
+    repo.put(stats).await?;
+
+    Ok(indexed.len())
+}
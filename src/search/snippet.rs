@@ -0,0 +1,79 @@
+//! Highlighted excerpts of matching text.
+
+use std::collections::HashSet;
+
+use crate::markup::escape_into;
+
+use super::analyzer;
+
+/// Index terms an excerpt spans, stop words not counted.
+const SNIPPET_TERMS: usize = 24;
+/// Terms kept before the first match of an excerpt.
+const LEADING_TERMS: usize = 3;
+
+const MARK_OPEN: &str = "<mark>";
+const MARK_CLOSE: &str = "</mark>";
+
+/// The excerpt of `text` with the most words matching `terms`, HTML-escaped,
+/// with the matches wrapped in `<mark>` and `…` where text was cut. `None` if
+/// no word matches.
+pub fn snippet(text: &str, terms: &HashSet<String>) -> Option<String> {
+    let tokens = analyzer::tokens(text);
+    let matches: Vec<usize> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| terms.contains(&token.term))
+        .map(|(i, _)| i)
+        .collect();
+
+    // The window starting at a match that holds the most matches
+    let best = matches
+        .iter()
+        .map(|&first| {
+            let count = matches
+                .iter()
+                .filter(|&&i| i >= first && i < first + SNIPPET_TERMS)
+                .count();
+            (first, count)
+        })
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?
+        .0;
+
+    // Keep a few terms before the first match, and more when the excerpt
+    // would otherwise run out of text at the end
+    let start = best
+        .saturating_sub(LEADING_TERMS)
+        .min(tokens.len().saturating_sub(SNIPPET_TERMS));
+    let end = (start + SNIPPET_TERMS).min(tokens.len()) - 1;
+
+    let from = if start == 0 { 0 } else { tokens[start].span.start };
+    let to = if end == tokens.len() - 1 { text.len() } else { tokens[end].span.end };
+
+    let mut out = String::new();
+
+    if from > 0 {
+        out.push('…');
+    }
+
+    let mut position = from;
+
+    for token in &tokens[start..=end] {
+        if !terms.contains(&token.term) {
+            continue;
+        }
+
+        escape_into(&text[position..token.span.start], &mut out);
+        out.push_str(MARK_OPEN);
+        escape_into(&text[token.span.clone()], &mut out);
+        out.push_str(MARK_CLOSE);
+        position = token.span.end;
+    }
+
+    escape_into(&text[position..to], &mut out);
+
+    if to < text.len() {
+        out.push('…');
+    }
+
+    Some(out.trim().to_string())
+}
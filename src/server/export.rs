@@ -0,0 +1,77 @@
+//! The admin download of bulk exports.
+
+use axum::{
+    body::{ Body, Bytes },
+    extract::{ Extension, Query },
+    http::{ header, StatusCode },
+    response::{ IntoResponse, Response },
+};
+use futures::stream;
+use tracing::error;
+
+use crate::{
+    auth::Principal,
+    export::{ Export, ExportParams },
+    models::user::Role,
+    AppError,
+    Repository,
+};
+
+/// Serves `GET /admin/export` to site admins: postings or applications as a
+/// CSV or NDJSON attachment, narrowed by the [`ExportParams`] in the query.
+/// The body is streamed as the table is scanned; if a read fails partway,
+/// the response is cut off rather than completed.
+pub(crate) async fn export(
+    Extension(repo): Extension<Repository>,
+    principal: Option<Extension<Principal>>,
+    Query(params): Query<ExportParams>
+) -> Response {
+    match principal {
+        None => {
+            return (StatusCode::UNAUTHORIZED, "Authentication is required").into_response();
+        }
+        Some(Extension(principal)) if !principal.has_role(Role::SiteAdmin) => {
+            return (StatusCode::FORBIDDEN, "Exports are only available to site admins").into_response();
+        }
+        Some(_) => {}
+    }
+
+    let content_type = params.format.content_type();
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        params.kind.as_str(),
+        params.format.extension()
+    );
+
+    let export = match Export::start(repo, params) {
+        Ok(export) => export,
+        Err(AppError::ValidationError(message)) => {
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+        Err(e) => {
+            error!("Failed to start export: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let chunks = stream::unfold(Some(export), |export| async move {
+        let mut export = export?;
+
+        match export.next_chunk().await {
+            Ok(Some(chunk)) => Some((Ok(Bytes::from(chunk)), Some(export))),
+            Ok(None) => None,
+            Err(e) => {
+                error!("Failed to export: {}", e);
+                Some((Err(e), None))
+            }
+        }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(chunks),
+    ).into_response()
+}
@@ -0,0 +1,167 @@
+//! RSS and Atom feed routes, and the aggregator export.
+
+use axum::{
+    body::{ Body, Bytes },
+    extract::{ Extension, OriginalUri, Query },
+    http::{ header, HeaderMap, HeaderValue, StatusCode },
+    response::{ IntoResponse, Response },
+};
+use chrono::{ DurationRound, TimeDelta, Utc };
+use futures::stream;
+use tracing::error;
+
+use crate::{
+    aggregator::{ self, IndeedExport },
+    config::Config,
+    feed::{ self, Feed, FeedParams },
+    AppError,
+    Repository,
+};
+
+#[derive(Clone, Copy)]
+enum Format {
+    Rss,
+    Atom,
+}
+
+/// Whether the client's cached copy, described by the conditional request
+/// headers, is still current. `If-None-Match` takes precedence over
+/// `If-Modified-Since`.
+fn not_modified(headers: &HeaderMap, feed: &Feed<'_>, etag: &str) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match
+            .to_str()
+            .is_ok_and(|tags| {
+                tags.split(',').any(|tag| {
+                    let tag = tag.trim();
+                    tag == "*" || tag.trim_start_matches("W/") == etag
+                })
+            });
+    }
+
+    let since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(feed::parse_http_date);
+
+    match (since, feed.last_modified()) {
+        // HTTP dates have whole seconds
+        (Some(since), Some(last_modified)) =>
+            last_modified
+                .duration_trunc(TimeDelta::seconds(1))
+                .is_ok_and(|last_modified| last_modified <= since),
+        _ => false,
+    }
+}
+
+async fn render(
+    repo: &Repository,
+    config: &Config,
+    params: &FeedParams,
+    headers: &HeaderMap,
+    uri: &str,
+    format: Format
+) -> Result<Response, AppError> {
+    let job_postings = feed::latest(repo, params, Utc::now()).await?;
+    let feed = Feed::load(repo, &config.feeds, job_postings).await?;
+
+    let (content_type, etag) = match format {
+        Format::Rss => (feed::RSS_CONTENT_TYPE, feed.etag("rss")),
+        Format::Atom => (feed::ATOM_CONTENT_TYPE, feed.etag("atom")),
+    };
+
+    let mut response = if not_modified(headers, &feed, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let body = match format {
+            Format::Rss => feed.rss(),
+            Format::Atom => {
+                let id = format!("{}{}", config.feeds.site_url.trim_end_matches('/'), uri);
+                feed.atom(&id)
+            }
+        };
+
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, etag);
+    }
+    if
+        let Some(last_modified) = feed.last_modified() &&
+        let Ok(last_modified) = HeaderValue::from_str(&feed::http_date(&last_modified))
+    {
+        response_headers.insert(header::LAST_MODIFIED, last_modified);
+    }
+
+    Ok(response)
+}
+
+async fn respond(
+    repo: &Repository,
+    config: &Config,
+    params: &FeedParams,
+    headers: &HeaderMap,
+    uri: &str,
+    format: Format
+) -> Response {
+    match render(repo, config, params, headers, uri, format).await {
+        Ok(response) => response,
+        Err(AppError::ValidationError(message)) => {
+            (StatusCode::BAD_REQUEST, message).into_response()
+        }
+        Err(e) => {
+            error!("Failed to render job feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Serves `GET /feeds/jobs.rss`.
+pub(crate) async fn jobs_rss(
+    Extension(repo): Extension<Repository>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<FeedParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap
+) -> Response {
+    respond(&repo, &config, &params, &headers, &uri.to_string(), Format::Rss).await
+}
+
+/// Serves `GET /feeds/jobs.atom`.
+pub(crate) async fn jobs_atom(
+    Extension(repo): Extension<Repository>,
+    Extension(config): Extension<Config>,
+    Query(params): Query<FeedParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap
+) -> Response {
+    respond(&repo, &config, &params, &headers, &uri.to_string(), Format::Atom).await
+}
+
+/// Serves `GET /feeds/indeed.xml`: every live posting in the aggregator XML
+/// format. The body is streamed a page of postings at a time; if a read fails
+/// partway, the response is cut off rather than completed.
+pub(crate) async fn indeed_xml(
+    Extension(repo): Extension<Repository>,
+    Extension(config): Extension<Config>
+) -> Response {
+    let export = IndeedExport::new(repo, config.feeds.clone(), Utc::now());
+
+    let chunks = stream::unfold(Some(export), |export| async move {
+        let mut export = export?;
+
+        match export.next_chunk().await {
+            Ok(Some(chunk)) => Some((Ok(Bytes::from(chunk)), Some(export))),
+            Ok(None) => None,
+            Err(e) => {
+                error!("Failed to export job postings: {}", e);
+                Some((Err(e), None))
+            }
+        }
+    });
+
+    ([(header::CONTENT_TYPE, aggregator::CONTENT_TYPE)], Body::from_stream(chunks)).into_response()
+}
@@ -0,0 +1,49 @@
+//! REST routes for individual job postings.
+
+use axum::{
+    extract::{ Extension, Path },
+    http::{ header, StatusCode },
+    response::{ IntoResponse, Response },
+};
+use chrono::Utc;
+use tracing::error;
+
+use crate::{ json_ld, Employer, JobPosting, Repository };
+
+/// Serves `GET /jobs/{id}.jsonld`: the schema.org document of a posting that
+/// anyone may look up. Drafts, held and archived postings are not found.
+pub(crate) async fn job_posting_json_ld(
+    Extension(repo): Extension<Repository>,
+    Path(file): Path<String>
+) -> Response {
+    let Some(id) = file.strip_suffix(".jsonld") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let job_posting = match repo.get::<JobPosting>(id.to_string()).await {
+        Ok(Some(job_posting)) if job_posting.is_public(Utc::now()) => job_posting,
+        Ok(_) => {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        Err(e) => {
+            error!("Failed to load job posting {}: {}", id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let employer = match &job_posting.employer_id {
+        Some(employer_id) =>
+            match repo.get::<Employer>(employer_id.clone()).await {
+                Ok(employer) => employer,
+                Err(e) => {
+                    error!("Failed to load employer {}: {}", employer_id, e);
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            }
+        None => None,
+    };
+
+    let document = json_ld::job_posting(&job_posting, employer.as_ref());
+
+    ([(header::CONTENT_TYPE, json_ld::CONTENT_TYPE)], json_ld::serialize(&document)).into_response()
+}
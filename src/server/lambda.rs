@@ -0,0 +1,312 @@
+//! Lambda runtime mode.
+//!
+//! API Gateway REST APIs deliver payload format 1.0 events; HTTP APIs and
+//! Function URLs deliver payload format 2.0. [`request_from_event`] turns
+//! either into an `http::Request` for the axum router, and
+//! [`event_from_response`] turns the router's response back into the matching
+//! response payload. Both are plain functions over `serde_json::Value`, so
+//! recorded events can be replayed without a Lambda environment.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::{ self, Body },
+    http::{ header, response::Parts, HeaderName, HeaderValue, Method, Request, Response, StatusCode },
+    Router,
+};
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use lambda_runtime::{ service_fn, Diagnostic, LambdaEvent };
+use serde::Deserialize;
+use serde_json::{ json, Value };
+use tower::ServiceExt;
+
+use crate::AppError;
+
+/// The largest response body returned through Lambda. Invocation responses are
+/// capped at 6 MB, and a base64-encoded body grows by a third, so this leaves
+/// room for the encoding and the rest of the payload.
+pub const MAX_RESPONSE_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// The API Gateway payload format an event arrived in. Responses must be
+/// returned in the same format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadVersion {
+    /// REST APIs
+    V1,
+    /// HTTP APIs and Function URLs
+    V2,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V1Event {
+    http_method: String,
+    path: String,
+    headers: Option<HashMap<String, String>>,
+    multi_value_headers: Option<HashMap<String, Vec<String>>>,
+    query_string_parameters: Option<HashMap<String, String>>,
+    multi_value_query_string_parameters: Option<HashMap<String, Vec<String>>>,
+    body: Option<String>,
+    is_base64_encoded: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V2Event {
+    raw_path: String,
+    raw_query_string: Option<String>,
+    // Repeated headers arrive joined with commas
+    headers: Option<HashMap<String, String>>,
+    // Cookies are split out of the headers in this format
+    cookies: Option<Vec<String>>,
+    request_context: V2RequestContext,
+    body: Option<String>,
+    is_base64_encoded: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct V2RequestContext {
+    http: V2Http,
+}
+
+#[derive(Debug, Deserialize)]
+struct V2Http {
+    method: String,
+}
+
+fn invalid_event(message: impl std::fmt::Display) -> AppError {
+    AppError::ValidationError(format!("Invalid Lambda event: {}", message))
+}
+
+fn decode_body(body: Option<String>, is_base64_encoded: Option<bool>) -> Result<Body, AppError> {
+    match body {
+        None => Ok(Body::empty()),
+        Some(body) if is_base64_encoded.unwrap_or(false) => {
+            let bytes = STANDARD.decode(body).map_err(invalid_event)?;
+            Ok(Body::from(bytes))
+        }
+        Some(body) => Ok(Body::from(body)),
+    }
+}
+
+fn build_request(
+    method: &str,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: Body
+) -> Result<Request<Body>, AppError> {
+    let method = Method::from_bytes(method.as_bytes()).map_err(invalid_event)?;
+
+    let mut request = Request::builder().method(method).uri(uri).body(body).map_err(invalid_event)?;
+
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(invalid_event)?;
+        let value = HeaderValue::from_str(&value).map_err(invalid_event)?;
+        request.headers_mut().append(name, value);
+    }
+
+    Ok(request)
+}
+
+fn with_query(path: &str, query: &str) -> String {
+    if query.is_empty() { path.to_string() } else { format!("{}?{}", path, query) }
+}
+
+fn request_from_v1(event: V1Event) -> Result<Request<Body>, AppError> {
+    // The multi-value maps are a superset of the single-value ones when present
+    let headers: Vec<(String, String)> = match (event.multi_value_headers, event.headers) {
+        (Some(multi), _) =>
+            multi
+                .into_iter()
+                .flat_map(|(name, values)| values.into_iter().map(move |v| (name.clone(), v)))
+                .collect(),
+        (None, Some(single)) => single.into_iter().collect(),
+        (None, None) => Vec::new(),
+    };
+
+    let mut query = form_urlencoded::Serializer::new(String::new());
+
+    match (event.multi_value_query_string_parameters, event.query_string_parameters) {
+        (Some(multi), _) => {
+            for (name, values) in &multi {
+                for value in values {
+                    query.append_pair(name, value);
+                }
+            }
+        }
+        (None, Some(single)) => {
+            query.extend_pairs(single.iter());
+        }
+        (None, None) => {}
+    }
+
+    build_request(
+        &event.http_method,
+        with_query(&event.path, &query.finish()),
+        headers,
+        decode_body(event.body, event.is_base64_encoded)?
+    )
+}
+
+fn request_from_v2(event: V2Event) -> Result<Request<Body>, AppError> {
+    let mut headers: Vec<(String, String)> = event.headers.unwrap_or_default().into_iter().collect();
+
+    if let Some(cookies) = event.cookies.filter(|cookies| !cookies.is_empty()) {
+        headers.push((header::COOKIE.to_string(), cookies.join("; ")));
+    }
+
+    build_request(
+        &event.request_context.http.method,
+        with_query(&event.raw_path, event.raw_query_string.as_deref().unwrap_or_default()),
+        headers,
+        decode_body(event.body, event.is_base64_encoded)?
+    )
+}
+
+/// The payload format of an API Gateway or Function URL event.
+fn payload_version(event: &Value) -> Result<PayloadVersion, AppError> {
+    if event.get("version").and_then(Value::as_str) == Some("2.0") {
+        return Ok(PayloadVersion::V2);
+    }
+
+    if event.get("httpMethod").is_some() {
+        return Ok(PayloadVersion::V1);
+    }
+
+    Err(invalid_event("not an API Gateway or Function URL event"))
+}
+
+/// Translates an API Gateway or Function URL event into an HTTP request.
+pub fn request_from_event(event: Value) -> Result<(PayloadVersion, Request<Body>), AppError> {
+    let version = payload_version(&event)?;
+
+    let request = match version {
+        PayloadVersion::V1 =>
+            request_from_v1(serde_json::from_value(event).map_err(invalid_event)?)?,
+        PayloadVersion::V2 =>
+            request_from_v2(serde_json::from_value(event).map_err(invalid_event)?)?,
+    };
+
+    Ok((version, request))
+}
+
+/// Translates an HTTP response into the response payload for `version`.
+///
+/// Bodies are passed through as text unless they are content-encoded (the
+/// router compresses responses) or not valid UTF-8, in which case they are
+/// base64-encoded. The body is buffered, so one over
+/// [`MAX_RESPONSE_BODY_BYTES`] is replaced with a `502 Bad Gateway`.
+pub async fn event_from_response(version: PayloadVersion, response: Response<Body>) -> Value {
+    let (parts, body) = response.into_parts();
+
+    match body::to_bytes(body, MAX_RESPONSE_BODY_BYTES).await {
+        Ok(bytes) => payload(version, parts, &bytes),
+        Err(e) =>
+            error_payload(
+                version,
+                StatusCode::BAD_GATEWAY,
+                &format!("Response body unavailable through Lambda: {}", e)
+            ),
+    }
+}
+
+/// A plain text response payload, for requests the router never saw or
+/// responses that cannot be returned.
+fn error_payload(version: PayloadVersion, status: StatusCode, message: &str) -> Value {
+    let mut response = Response::new(());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8")
+    );
+
+    payload(version, response.into_parts().0, message.as_bytes())
+}
+
+fn payload(version: PayloadVersion, parts: Parts, bytes: &[u8]) -> Value {
+    let encoded = parts.headers.contains_key(header::CONTENT_ENCODING);
+
+    let (body, is_base64_encoded) = match std::str::from_utf8(bytes) {
+        Ok(text) if !encoded => (text.to_string(), false),
+        _ => (STANDARD.encode(bytes), true),
+    };
+
+    let mut headers: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (name, value) in &parts.headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        headers.entry(name.to_string()).or_default().push(value);
+    }
+
+    let status = parts.status.as_u16();
+
+    match version {
+        PayloadVersion::V1 =>
+            json!({
+                "statusCode": status,
+                "multiValueHeaders": headers,
+                "body": body,
+                "isBase64Encoded": is_base64_encoded,
+            }),
+        PayloadVersion::V2 => {
+            let cookies = headers.remove(header::SET_COOKIE.as_str()).unwrap_or_default();
+            let headers: HashMap<String, String> = headers
+                .into_iter()
+                .map(|(name, values)| (name, values.join(", ")))
+                .collect();
+
+            json!({
+                "statusCode": status,
+                "headers": headers,
+                "cookies": cookies,
+                "body": body,
+                "isBase64Encoded": is_base64_encoded,
+            })
+        }
+    }
+}
+
+/// Routes a single event through `router` and returns the response payload.
+///
+/// Fails only for events that are not from API Gateway or a Function URL.
+/// Requests that cannot be translated, such as ones with invalid headers, get
+/// a `400 Bad Request` response instead.
+pub async fn handle_event(router: Router, event: Value) -> Result<Value, AppError> {
+    let version = payload_version(&event)?;
+
+    let request = match request_from_event(event) {
+        Ok((_, request)) => request,
+        Err(e) => {
+            return Ok(error_payload(version, StatusCode::BAD_REQUEST, &e.to_string()));
+        }
+    };
+
+    let response = match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    };
+
+    Ok(event_from_response(version, response).await)
+}
+
+impl From<AppError> for Diagnostic {
+    fn from(error: AppError) -> Self {
+        Diagnostic {
+            error_type: "AppError".to_string(),
+            error_message: error.to_string(),
+        }
+    }
+}
+
+/// Serves `router` from the Lambda runtime API until the environment shuts
+/// the function down.
+pub async fn run(router: Router) -> Result<(), AppError> {
+    lambda_runtime
+        ::run(
+            service_fn(move |event: LambdaEvent<Value>| {
+                let router = router.clone();
+                async move { handle_event(router, event.payload).await }
+            })
+        ).await
+        .map_err(|e| AppError::InternalServerError(format!("Lambda runtime error: {}", e)))
+}
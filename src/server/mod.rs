@@ -0,0 +1,131 @@
+//! HTTP surface of the service.
+//!
+//! [`build_router`] assembles the axum `Router` once; it is then either served
+//! over TCP by [`serve_http`] for local development, or driven by Lambda
+//! invocations through [`lambda::run`].
+
+mod export;
+mod feeds;
+mod jobs;
+pub mod lambda;
+
+use axum::{
+    extract::Extension,
+    http::{ HeaderValue, Method },
+    middleware,
+    routing::get,
+    Router,
+};
+use async_graphql_axum::{ GraphQLRequest, GraphQLResponse };
+use tower::ServiceBuilder;
+use tower_http::{ compression::CompressionLayer, cors::{ Any, CorsLayer } };
+use tracing::info;
+
+use crate::{ auth::{ self, Principal }, config::Config, AppError, GraphQLSchema, Repository };
+
+// Handler for GraphQL requests; forwards the authenticated principal, if any
+async fn graphql_handler(
+    Extension(schema): Extension<GraphQLSchema>,
+    principal: Option<Extension<Principal>>,
+    req: GraphQLRequest
+) -> GraphQLResponse {
+    let mut req = req.into_inner();
+
+    if let Some(Extension(principal)) = principal {
+        req = req.data(principal);
+    }
+
+    schema.execute(req).await.into()
+}
+
+// Handler for GraphQL playground
+async fn graphql_playground() -> impl axum::response::IntoResponse {
+    axum::response::Html(async_graphql::http::GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+// Health check endpoint
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+// Configure CORS based on environment
+fn cors_layer(config: &Config) -> Result<CorsLayer, AppError> {
+    if config.graphql.playground {
+        // Development mode - allow all origins
+        return Ok(
+            CorsLayer::new()
+                .allow_origin(Any)
+                .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+                .allow_headers(Any)
+        );
+    }
+
+    // Production mode - restrict origins to the configured list
+    let mut cors_layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers(Any);
+
+    for origin in config.allow_origins.split(",") {
+        let header_value = origin
+            .parse::<HeaderValue>()
+            .map_err(|e| {
+                AppError::ConfigError(format!("Could not configure cors layer: {:?}", e))
+            })?;
+        cors_layer = cors_layer.allow_origin(header_value);
+    }
+
+    Ok(cors_layer)
+}
+
+/// Builds the application router: the GraphQL endpoint, the health check, the
+/// JSON-LD documents of postings, the RSS and Atom feeds, the aggregator
+/// export, the admin export, and the authentication, compression and CORS
+/// layers.
+pub fn build_router(
+    schema: GraphQLSchema,
+    repository: Repository,
+    config: &Config
+) -> Result<Router, AppError> {
+    let cors = cors_layer(config)?;
+
+    let router = Router::new()
+        .route("/graphql", get(graphql_playground).post(graphql_handler))
+        .route("/health", get(health_check))
+        // The `.jsonld` suffix is stripped by the handler; path parameters
+        // must span a whole segment
+        .route("/jobs/{file}", get(jobs::job_posting_json_ld))
+        .route("/feeds/jobs.rss", get(feeds::jobs_rss))
+        .route("/feeds/jobs.atom", get(feeds::jobs_atom))
+        .route("/feeds/indeed.xml", get(feeds::indeed_xml))
+        .route("/admin/export", get(export::export));
+
+    Ok(
+        router.layer(
+            ServiceBuilder::new()
+                .layer(CompressionLayer::new().gzip(true).deflate(true).br(true))
+                .layer(Extension(repository))
+                .layer(Extension(schema))
+                .layer(Extension(config.clone()))
+                .layer(cors)
+                .layer(middleware::from_fn_with_state(config.auth.clone(), auth::middleware::authenticate))
+        )
+    )
+}
+
+/// Serves `router` over TCP on `port` until the process is stopped.
+pub async fn serve_http(router: Router, port: u16) -> Result<(), AppError> {
+    let bind_address = format!("0.0.0.0:{}", port);
+
+    let listener = tokio::net::TcpListener
+        ::bind(&bind_address).await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to bind to {}: {}", bind_address, e))
+        })?;
+
+    info!("Server running on http://localhost:{}", port);
+    info!("GraphQL Playground available at http://localhost:{}/graphql", port);
+
+    axum::serve(listener, router).await.map_err(|e| {
+        AppError::InternalServerError(format!("Server error: {}", e))
+    })
+}
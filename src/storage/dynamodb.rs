@@ -0,0 +1,388 @@
+use std::{ collections::HashMap, time::Duration };
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{
+    operation::transact_write_items::TransactWriteItemsError,
+    types::{
+        AttributeValue,
+        DeleteRequest,
+        KeysAndAttributes,
+        Put,
+        PutRequest,
+        TransactWriteItem,
+        WriteRequest,
+    },
+    Client,
+};
+
+use crate::repository::{ query::ExpressionBuilder, Filter, KeyCondition };
+
+use super::{ Condition, Item, ItemPage, PageRequest, StorageBackend, StorageError, TransactPut };
+
+/// Most keys a single `BatchGetItem` request accepts.
+const BATCH_GET_LIMIT: usize = 100;
+/// Attempts at draining `UnprocessedKeys` before a batch read gives up.
+const BATCH_GET_ATTEMPTS: u32 = 5;
+/// Most requests a single `BatchWriteItem` call accepts.
+const BATCH_WRITE_LIMIT: usize = 25;
+/// Attempts at draining `UnprocessedItems` before a batch write gives up.
+const BATCH_WRITE_ATTEMPTS: u32 = 5;
+
+/// [`StorageBackend`] backed by DynamoDB.
+#[derive(Clone, Debug)]
+pub struct DynamoDbBackend {
+    client: Client,
+}
+
+impl DynamoDbBackend {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Sends `writes` in `BatchWriteItem` calls of at most
+    /// [`BATCH_WRITE_LIMIT`] requests.
+    async fn batch_write(&self, table: &str, writes: Vec<WriteRequest>) -> Result<(), StorageError> {
+        let mut writes = writes.into_iter().peekable();
+
+        while writes.peek().is_some() {
+            let mut request = Some(writes.by_ref().take(BATCH_WRITE_LIMIT).collect::<Vec<_>>());
+            let mut attempt = 0;
+
+            // Writes DynamoDB could not take under load come back as
+            // UnprocessedItems; retry those with backoff
+            while let Some(chunk) = request.take() {
+                if attempt > 0 {
+                    if attempt >= BATCH_WRITE_ATTEMPTS {
+                        return Err(
+                            StorageError::Backend(
+                                format!("Batch write on {} left items unprocessed", table)
+                            )
+                        );
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(50 * (1 << attempt))).await;
+                }
+
+                let response = self.client
+                    .batch_write_item()
+                    .request_items(table, chunk)
+                    .send().await
+                    .map_err(|e| StorageError::Backend(format!("Failed to batch write items: {}", e)))?;
+
+                request = response.unprocessed_items
+                    .and_then(|mut unprocessed| unprocessed.remove(table))
+                    .filter(|chunk| !chunk.is_empty());
+                attempt += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn condition_expression(condition: &Condition, expressions: &mut ExpressionBuilder) -> String {
+    match condition {
+        Condition::AttributeExists(name) => {
+            expressions.names.insert("#c".to_string(), name.clone());
+            "attribute_exists(#c)".to_string()
+        }
+        Condition::AttributeNotExists(name) => {
+            expressions.names.insert("#c".to_string(), name.clone());
+            "attribute_not_exists(#c)".to_string()
+        }
+        Condition::AttributeEquals(name, value) => {
+            expressions.names.insert("#c".to_string(), name.clone());
+            expressions.values.insert(":c".to_string(), value.clone());
+            "#c = :c".to_string()
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for DynamoDbBackend {
+    async fn get_item(&self, table: &str, id: &str) -> Result<Option<Item>, StorageError> {
+        let response = self.client
+            .get_item()
+            .table_name(table)
+            .key("id", AttributeValue::S(id.to_string()))
+            .send().await
+            .map_err(|e| StorageError::Backend(format!("Failed to get item: {}", e)))?;
+
+        Ok(response.item)
+    }
+
+    async fn batch_get_items(&self, table: &str, ids: &[String]) -> Result<Vec<Item>, StorageError> {
+        let mut items = Vec::with_capacity(ids.len());
+
+        for chunk in ids.chunks(BATCH_GET_LIMIT) {
+            let keys = chunk
+                .iter()
+                .map(|id| HashMap::from([("id".to_string(), AttributeValue::S(id.clone()))]))
+                .collect();
+
+            let mut request = Some(
+                KeysAndAttributes::builder()
+                    .set_keys(Some(keys))
+                    .build()
+                    .map_err(|e| StorageError::Backend(format!("Failed to build keys: {}", e)))?
+            );
+            let mut attempt = 0;
+
+            // DynamoDB hands back whatever it could not read under load as
+            // UnprocessedKeys; retry those with backoff
+            while let Some(keys) = request.take() {
+                if attempt > 0 {
+                    if attempt >= BATCH_GET_ATTEMPTS {
+                        return Err(
+                            StorageError::Backend(
+                                format!("Batch get on {} left keys unprocessed", table)
+                            )
+                        );
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(50 * (1 << attempt))).await;
+                }
+
+                let response = self.client
+                    .batch_get_item()
+                    .request_items(table, keys)
+                    .send().await
+                    .map_err(|e| StorageError::Backend(format!("Failed to batch get items: {}", e)))?;
+
+                if let Some(mut responses) = response.responses {
+                    items.extend(responses.remove(table).unwrap_or_default());
+                }
+
+                request = response.unprocessed_keys
+                    .and_then(|mut unprocessed| unprocessed.remove(table))
+                    .filter(|keys| !keys.keys().is_empty());
+                attempt += 1;
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn put_item(&self, table: &str, item: Item) -> Result<(), StorageError> {
+        self.client
+            .put_item()
+            .table_name(table)
+            .set_item(Some(item))
+            .send().await
+            .map_err(|e| StorageError::Backend(format!("Failed to put item: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn batch_put_items(&self, table: &str, items: Vec<Item>) -> Result<(), StorageError> {
+        let writes = items
+            .into_iter()
+            .map(|item| {
+                let put = PutRequest::builder()
+                    .set_item(Some(item))
+                    .build()
+                    .map_err(|e| StorageError::Backend(format!("Failed to build put: {}", e)))?;
+
+                Ok(WriteRequest::builder().put_request(put).build())
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        self.batch_write(table, writes).await
+    }
+
+    async fn put_item_if(
+        &self,
+        table: &str,
+        item: Item,
+        condition: Condition
+    ) -> Result<(), StorageError> {
+        let mut expressions = ExpressionBuilder::default();
+        let condition_expression = condition_expression(&condition, &mut expressions);
+
+        self.client
+            .put_item()
+            .table_name(table)
+            .set_item(Some(item))
+            .condition_expression(condition_expression)
+            .set_expression_attribute_names(expressions.take_names())
+            .set_expression_attribute_values(expressions.take_values())
+            .send().await
+            .map_err(|e| {
+                if
+                    e
+                        .as_service_error()
+                        .is_some_and(|se| se.is_conditional_check_failed_exception())
+                {
+                    StorageError::ConditionFailed
+                } else {
+                    StorageError::Backend(format!("Failed to put item: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn transact_put_items(&self, puts: Vec<TransactPut>) -> Result<(), StorageError> {
+        let items = puts
+            .into_iter()
+            .map(|put| {
+                let mut expressions = ExpressionBuilder::default();
+                let condition_expression = condition_expression(&put.condition, &mut expressions);
+
+                let put = Put::builder()
+                    .table_name(put.table)
+                    .set_item(Some(put.item))
+                    .condition_expression(condition_expression)
+                    .set_expression_attribute_names(expressions.take_names())
+                    .set_expression_attribute_values(expressions.take_values())
+                    .build()
+                    .map_err(|e| StorageError::Backend(format!("Failed to build put: {}", e)))?;
+
+                Ok(TransactWriteItem::builder().put(put).build())
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        self.client
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .send().await
+            .map_err(|e| {
+                // A failed condition cancels the whole transaction, with the
+                // reason reported per item
+                let condition_failed = match e.as_service_error() {
+                    Some(TransactWriteItemsError::TransactionCanceledException(canceled)) =>
+                        canceled
+                            .cancellation_reasons()
+                            .iter()
+                            .any(|reason| reason.code() == Some("ConditionalCheckFailed")),
+                    _ => false,
+                };
+
+                if condition_failed {
+                    StorageError::ConditionFailed
+                } else {
+                    StorageError::Backend(format!("Failed to write items: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn delete_item(
+        &self,
+        table: &str,
+        id: &str,
+        condition: Option<Condition>
+    ) -> Result<(), StorageError> {
+        let mut expressions = ExpressionBuilder::default();
+        let condition_expression = condition
+            .as_ref()
+            .map(|condition| condition_expression(condition, &mut expressions));
+
+        self.client
+            .delete_item()
+            .table_name(table)
+            .key("id", AttributeValue::S(id.to_string()))
+            .set_condition_expression(condition_expression)
+            .set_expression_attribute_names(expressions.take_names())
+            .set_expression_attribute_values(expressions.take_values())
+            .send().await
+            .map_err(|e| {
+                if
+                    e
+                        .as_service_error()
+                        .is_some_and(|se| se.is_conditional_check_failed_exception())
+                {
+                    StorageError::ConditionFailed
+                } else {
+                    StorageError::Backend(format!("Failed to delete item: {}", e))
+                }
+            })?;
+
+        Ok(())
+    }
+
+    async fn batch_delete_items(&self, table: &str, ids: Vec<String>) -> Result<(), StorageError> {
+        let writes = ids
+            .into_iter()
+            .map(|id| {
+                let delete = DeleteRequest::builder()
+                    .key("id", AttributeValue::S(id))
+                    .build()
+                    .map_err(|e| StorageError::Backend(format!("Failed to build delete: {}", e)))?;
+
+                Ok(WriteRequest::builder().delete_request(delete).build())
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        self.batch_write(table, writes).await
+    }
+
+    async fn scan(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        let mut expressions = ExpressionBuilder::default();
+        let filter_expression = expressions.filter_expression(filters);
+
+        let response = self.client
+            .scan()
+            .table_name(table)
+            .set_filter_expression(filter_expression)
+            .set_expression_attribute_names(expressions.take_names())
+            .set_expression_attribute_values(expressions.take_values())
+            .set_limit(page.limit)
+            .set_exclusive_start_key(page.exclusive_start_key)
+            .set_segment(page.segment.map(|segment| segment.index))
+            .set_total_segments(page.segment.map(|segment| segment.total))
+            .send().await
+            .map_err(|e| StorageError::Backend(format!("Failed to scan table: {}", e)))?;
+
+        Ok(ItemPage {
+            items: response.items.unwrap_or_default(),
+            last_evaluated_key: response.last_evaluated_key,
+        })
+    }
+
+    async fn query(
+        &self,
+        table: &str,
+        key_condition: &KeyCondition,
+        filters: &[Filter],
+        descending: bool,
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        let mut expressions = ExpressionBuilder::default();
+        let key_condition_expression = expressions.key_condition(key_condition);
+        let filter_expression = expressions.filter_expression(filters);
+
+        let response = self.client
+            .query()
+            .table_name(table)
+            .index_name(&key_condition.index_name)
+            .key_condition_expression(key_condition_expression)
+            .scan_index_forward(!descending)
+            .set_filter_expression(filter_expression)
+            .set_expression_attribute_names(expressions.take_names())
+            .set_expression_attribute_values(expressions.take_values())
+            .set_limit(page.limit)
+            .set_exclusive_start_key(page.exclusive_start_key)
+            .send().await
+            .map_err(|e|
+                StorageError::Backend(
+                    format!("Failed to query {}: {}", key_condition.index_name, e)
+                )
+            )?;
+
+        Ok(ItemPage {
+            items: response.items.unwrap_or_default(),
+            last_evaluated_key: response.last_evaluated_key,
+        })
+    }
+}
@@ -0,0 +1,545 @@
+use std::{
+    cmp::Ordering,
+    collections::{ BTreeMap, HashMap },
+    str::FromStr,
+    sync::{ Arc, RwLock },
+};
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{ DateTime, Utc };
+use rust_decimal::Decimal;
+
+use crate::repository::{ Filter, FilterOp, KeyCondition, SortKeyCondition, SortKeyOp };
+
+use super::{
+    Condition,
+    Item,
+    ItemPage,
+    PageRequest,
+    Segment,
+    StorageBackend,
+    StorageError,
+    TransactPut,
+};
+
+type Tables = HashMap<String, BTreeMap<String, Item>>;
+
+/// [`StorageBackend`] that keeps every table in memory.
+///
+/// Clones share the same tables. Tables are created on first write, and
+/// secondary indexes need no declaration: a query simply considers every item
+/// that carries the index's key attributes, like a sparse GSI would.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryBackend {
+    tables: Arc<RwLock<Tables>>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&Tables) -> R) -> Result<R, StorageError> {
+        let tables = self.tables
+            .read()
+            .map_err(|_| StorageError::Backend("In-memory store lock poisoned".to_string()))?;
+
+        Ok(f(&tables))
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut Tables) -> R) -> Result<R, StorageError> {
+        let mut tables = self.tables
+            .write()
+            .map_err(|_| StorageError::Backend("In-memory store lock poisoned".to_string()))?;
+
+        Ok(f(&mut tables))
+    }
+
+    /// Deletes the items of `table` whose Time to Live attribute, in epoch
+    /// seconds, is at or before `now`, the way DynamoDB's TTL process
+    /// eventually does. Returns the number of items removed.
+    ///
+    /// DynamoDB runs this in the background; here it happens only when called.
+    pub fn expire_items(
+        &self,
+        table: &str,
+        ttl_attribute: &str,
+        now: DateTime<Utc>
+    ) -> Result<usize, StorageError> {
+        let now = now.timestamp();
+
+        self.write(|tables| {
+            let Some(items) = tables.get_mut(table) else {
+                return 0;
+            };
+            let before = items.len();
+
+            items.retain(|_, item| {
+                item
+                    .get(ttl_attribute)
+                    .and_then(|v| v.as_n().ok())
+                    .and_then(|n| n.parse::<i64>().ok())
+                    .is_none_or(|expires| expires > now)
+            });
+
+            before - items.len()
+        })
+    }
+}
+
+fn item_id(item: &Item) -> Result<String, StorageError> {
+    item.get("id")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(|| StorageError::Backend("Item is missing a string id".to_string()))
+}
+
+fn condition_holds(condition: &Condition, existing: Option<&Item>) -> bool {
+    match condition {
+        Condition::AttributeExists(name) => existing.is_some_and(|item| item.contains_key(name)),
+        Condition::AttributeNotExists(name) => !existing.is_some_and(|item| item.contains_key(name)),
+        Condition::AttributeEquals(name, value) => {
+            existing.is_some_and(|item| item.get(name) == Some(value))
+        }
+    }
+}
+
+/// Orders two attribute values the way DynamoDB compares them: strings
+/// lexicographically, numbers numerically. Values of different types are
+/// incomparable.
+pub(crate) fn compare(a: &AttributeValue, b: &AttributeValue) -> Option<Ordering> {
+    match (a, b) {
+        (AttributeValue::S(a), AttributeValue::S(b)) => Some(a.cmp(b)),
+        (AttributeValue::N(a), AttributeValue::N(b)) => {
+            match (Decimal::from_str(a), Decimal::from_str(b)) {
+                (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+                _ => a.parse::<f64>().ok()?.partial_cmp(&b.parse::<f64>().ok()?),
+            }
+        }
+        (AttributeValue::Bool(a), AttributeValue::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+pub(crate) fn matches_filter(item: &Item, filter: &Filter) -> bool {
+    let Some(value) = item.get(&filter.attribute) else {
+        return filter.or_missing;
+    };
+
+    match filter.op {
+        FilterOp::Eq => value == &filter.value,
+        FilterOp::Ne => value != &filter.value,
+        FilterOp::Lt => compare(value, &filter.value) == Some(Ordering::Less),
+        FilterOp::Le => compare(value, &filter.value).is_some_and(|o| o != Ordering::Greater),
+        FilterOp::Gt => compare(value, &filter.value) == Some(Ordering::Greater),
+        FilterOp::Ge => compare(value, &filter.value).is_some_and(|o| o != Ordering::Less),
+        FilterOp::Contains =>
+            match (value, &filter.value) {
+                (AttributeValue::L(list), needle) => list.contains(needle),
+                (AttributeValue::Ss(set), AttributeValue::S(needle)) => set.contains(needle),
+                (AttributeValue::S(haystack), AttributeValue::S(needle)) => {
+                    haystack.contains(needle.as_str())
+                }
+                _ => false,
+            }
+    }
+}
+
+fn matches_sort_key(item: &Item, condition: &SortKeyCondition) -> bool {
+    let Some(value) = item.get(&condition.attribute) else {
+        return false;
+    };
+
+    if condition.op == SortKeyOp::BeginsWith {
+        return match (value, &condition.value) {
+            (AttributeValue::S(value), AttributeValue::S(prefix)) => value.starts_with(prefix),
+            _ => false,
+        };
+    }
+
+    let Some(ordering) = compare(value, &condition.value) else {
+        return false;
+    };
+
+    match condition.op {
+        SortKeyOp::Eq => ordering == Ordering::Equal,
+        SortKeyOp::Lt => ordering == Ordering::Less,
+        SortKeyOp::Le => ordering != Ordering::Greater,
+        SortKeyOp::Gt => ordering == Ordering::Greater,
+        SortKeyOp::Ge => ordering != Ordering::Less,
+        SortKeyOp::BeginsWith => unreachable!(),
+    }
+}
+
+/// Whether the item with `id` falls in `segment`, by an FNV-1a hash of the id
+/// so that segments stay put across runs.
+fn in_segment(id: &str, segment: Segment) -> bool {
+    let hash = id
+        .bytes()
+        .fold(0x811c9dc5_u32, |hash, byte| (hash ^ (byte as u32)).wrapping_mul(0x01000193));
+
+    (hash % (segment.total.max(1) as u32)) as i32 == segment.index
+}
+
+/// Evaluates up to `limit` items of an already ordered sequence, applying
+/// filters afterwards, and reports where the page stopped.
+fn paginate<'a>(
+    ordered: impl Iterator<Item = &'a Item>,
+    filters: &[Filter],
+    limit: Option<i32>,
+    key_attributes: &[&str]
+) -> ItemPage {
+    let mut ordered = ordered.peekable();
+    let mut items = Vec::new();
+    let mut evaluated = 0;
+    let mut last_evaluated = None;
+
+    while let Some(item) = ordered.next() {
+        evaluated += 1;
+
+        if filters.iter().all(|filter| matches_filter(item, filter)) {
+            items.push(item.clone());
+        }
+
+        if limit.is_some_and(|limit| evaluated >= limit) {
+            if ordered.peek().is_some() {
+                last_evaluated = Some(item);
+            }
+            break;
+        }
+    }
+
+    let last_evaluated_key = last_evaluated.map(|item| {
+        key_attributes
+            .iter()
+            .filter_map(|name| item.get(*name).map(|v| (name.to_string(), v.clone())))
+            .collect()
+    });
+
+    ItemPage {
+        items,
+        last_evaluated_key,
+    }
+}
+
+#[async_trait]
+impl StorageBackend for InMemoryBackend {
+    async fn get_item(&self, table: &str, id: &str) -> Result<Option<Item>, StorageError> {
+        self.read(|tables| tables.get(table).and_then(|items| items.get(id)).cloned())
+    }
+
+    async fn put_item(&self, table: &str, item: Item) -> Result<(), StorageError> {
+        let id = item_id(&item)?;
+
+        self.write(|tables| {
+            tables.entry(table.to_string()).or_default().insert(id, item);
+        })
+    }
+
+    async fn put_item_if(
+        &self,
+        table: &str,
+        item: Item,
+        condition: Condition
+    ) -> Result<(), StorageError> {
+        let id = item_id(&item)?;
+
+        self.write(|tables| {
+            let items = tables.entry(table.to_string()).or_default();
+
+            if !condition_holds(&condition, items.get(&id)) {
+                return Err(StorageError::ConditionFailed);
+            }
+
+            items.insert(id, item);
+            Ok(())
+        })?
+    }
+
+    async fn transact_put_items(&self, puts: Vec<TransactPut>) -> Result<(), StorageError> {
+        let ids = puts
+            .iter()
+            .map(|put| item_id(&put.item))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.write(|tables| {
+            let holds = puts.iter().zip(&ids).all(|(put, id)| {
+                let existing = tables.get(&put.table).and_then(|items| items.get(id));
+                condition_holds(&put.condition, existing)
+            });
+
+            if !holds {
+                return Err(StorageError::ConditionFailed);
+            }
+
+            for (put, id) in puts.into_iter().zip(ids) {
+                tables.entry(put.table).or_default().insert(id, put.item);
+            }
+
+            Ok(())
+        })?
+    }
+
+    async fn delete_item(
+        &self,
+        table: &str,
+        id: &str,
+        condition: Option<Condition>
+    ) -> Result<(), StorageError> {
+        self.write(|tables| {
+            let items = tables.entry(table.to_string()).or_default();
+
+            if condition.as_ref().is_some_and(|c| !condition_holds(c, items.get(id))) {
+                return Err(StorageError::ConditionFailed);
+            }
+
+            items.remove(id);
+            Ok(())
+        })?
+    }
+
+    async fn batch_delete_items(&self, table: &str, ids: Vec<String>) -> Result<(), StorageError> {
+        self.write(|tables| {
+            if let Some(items) = tables.get_mut(table) {
+                for id in &ids {
+                    items.remove(id);
+                }
+            }
+        })
+    }
+
+    async fn scan(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        let start_id = page.exclusive_start_key
+            .as_ref()
+            .map(item_id)
+            .transpose()?;
+
+        self.read(|tables| {
+            let Some(items) = tables.get(table) else {
+                return ItemPage::default();
+            };
+
+            let ordered = items
+                .iter()
+                .filter(|(id, _)| start_id.as_ref().is_none_or(|start| *id > start))
+                .filter(|(id, _)| page.segment.is_none_or(|segment| in_segment(id, segment)))
+                .map(|(_, item)| item);
+
+            paginate(ordered, filters, page.limit, &["id"])
+        })
+    }
+
+    async fn query(
+        &self,
+        table: &str,
+        key_condition: &KeyCondition,
+        filters: &[Filter],
+        descending: bool,
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        let sort_attribute = key_condition.sort_key_attribute.as_deref();
+
+        // Index order: sort key first (when the index has one), then id.
+        let index_order = |a: &Item, b: &Item| {
+            let by_sort_key = sort_attribute
+                .and_then(|name| compare(a.get(name)?, b.get(name)?))
+                .unwrap_or(Ordering::Equal);
+            let ordering = by_sort_key.then_with(|| {
+                let a = a.get("id").and_then(|v| v.as_s().ok());
+                let b = b.get("id").and_then(|v| v.as_s().ok());
+                a.cmp(&b)
+            });
+
+            if descending { ordering.reverse() } else { ordering }
+        };
+
+        self.read(|tables| {
+            let Some(items) = tables.get(table) else {
+                return ItemPage::default();
+            };
+
+            let mut candidates: Vec<&Item> = items
+                .values()
+                .filter(|item| item.get(&key_condition.partition_key) == Some(&key_condition.value))
+                .filter(|item| sort_attribute.is_none_or(|name| item.contains_key(name)))
+                .filter(|item| {
+                    key_condition.sort_key
+                        .as_ref()
+                        .is_none_or(|condition| matches_sort_key(item, condition))
+                })
+                .collect();
+
+            candidates.sort_by(|a, b| index_order(a, b));
+
+            let ordered = candidates
+                .into_iter()
+                .filter(|item| {
+                    page.exclusive_start_key
+                        .as_ref()
+                        .is_none_or(|start| index_order(item, start) == Ordering::Greater)
+                });
+
+            paginate(ordered, filters, page.limit, &key_condition.key_attributes())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::repository::ItemQuery;
+
+    const TABLE: &str = "Things";
+
+    fn s(value: &str) -> AttributeValue {
+        AttributeValue::S(value.to_string())
+    }
+
+    fn n(value: &str) -> AttributeValue {
+        AttributeValue::N(value.to_string())
+    }
+
+    fn thing(id: &str, color: Option<&str>, size: &str) -> Item {
+        let mut item = HashMap::from([
+            ("id".to_string(), s(id)),
+            ("size".to_string(), n(size)),
+        ]);
+
+        if let Some(color) = color {
+            item.insert("color".to_string(), s(color));
+        }
+
+        item
+    }
+
+    async fn backend() -> InMemoryBackend {
+        let backend = InMemoryBackend::new();
+
+        for item in [
+            thing("a", Some("red"), "10"),
+            thing("b", Some("blue"), "9"),
+            thing("c", Some("red"), "100"),
+            thing("d", None, "1"),
+            thing("e", Some("red"), "2"),
+        ] {
+            backend.put_item(TABLE, item).await.unwrap();
+        }
+
+        backend
+    }
+
+    fn ids(page: &ItemPage) -> Vec<&str> {
+        page.items
+            .iter()
+            .map(|item| item["id"].as_s().unwrap().as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn conditional_writes_check_the_stored_item() {
+        let backend = backend().await;
+
+        let result = backend.put_item_if(
+            TABLE,
+            thing("a", None, "1"),
+            Condition::AttributeNotExists("id".to_string())
+        ).await;
+        assert!(matches!(result, Err(StorageError::ConditionFailed)));
+
+        let result = backend.put_item_if(
+            TABLE,
+            thing("z", None, "1"),
+            Condition::AttributeExists("id".to_string())
+        ).await;
+        assert!(matches!(result, Err(StorageError::ConditionFailed)));
+
+        backend
+            .put_item_if(
+                TABLE,
+                thing("a", Some("green"), "10"),
+                Condition::AttributeEquals("color".to_string(), s("red"))
+            ).await
+            .unwrap();
+        assert_eq!(backend.get_item(TABLE, "a").await.unwrap().unwrap()["color"], s("green"));
+
+        let result = backend.delete_item(
+            TABLE,
+            "z",
+            Some(Condition::AttributeExists("id".to_string()))
+        ).await;
+        assert!(matches!(result, Err(StorageError::ConditionFailed)));
+
+        backend.delete_item(TABLE, "a", None).await.unwrap();
+        assert!(backend.get_item(TABLE, "a").await.unwrap().is_none());
+        assert!(backend.get_item("Missing", "a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn limits_count_items_evaluated_before_filters() {
+        let backend = backend().await;
+        let red = [Filter::eq("color", s("red"))];
+
+        let page = PageRequest { limit: Some(2), ..Default::default() };
+        let first = backend.scan(TABLE, &red, page).await.unwrap();
+
+        // a and b were evaluated; only a passed the filter
+        assert_eq!(ids(&first), ["a"]);
+        assert_eq!(first.last_evaluated_key, Some(HashMap::from([("id".to_string(), s("b"))])));
+
+        let page = PageRequest { exclusive_start_key: first.last_evaluated_key, ..Default::default() };
+        let rest = backend.scan(TABLE, &red, page).await.unwrap();
+
+        assert_eq!(ids(&rest), ["c", "e"]);
+        assert_eq!(rest.last_evaluated_key, None);
+    }
+
+    #[tokio::test]
+    async fn queries_read_a_sparse_index_in_sort_key_order() {
+        let backend = backend().await;
+        let query = ItemQuery::index("ColorIndex", "color", s("red")).sorted_by("size");
+        let key_condition = query.key_condition.unwrap();
+
+        // Numbers sort numerically, and d has no color so is not in the index
+        let page = backend.query(TABLE, &key_condition, &[], false, PageRequest::default()).await.unwrap();
+        assert_eq!(ids(&page), ["e", "a", "c"]);
+
+        let page = PageRequest { limit: Some(1), ..Default::default() };
+        let first = backend.query(TABLE, &key_condition, &[], true, page).await.unwrap();
+        assert_eq!(ids(&first), ["c"]);
+
+        let resume = PageRequest { exclusive_start_key: first.last_evaluated_key, ..Default::default() };
+        let rest = backend.query(TABLE, &key_condition, &[], true, resume).await.unwrap();
+        assert_eq!(ids(&rest), ["a", "e"]);
+    }
+
+    #[tokio::test]
+    async fn filters_can_let_missing_attributes_through() {
+        let backend = backend().await;
+
+        let red = [Filter::eq("color", s("red"))];
+        let page = backend.scan(TABLE, &red, PageRequest::default()).await.unwrap();
+        assert_eq!(ids(&page), ["a", "c", "e"]);
+
+        let red_or_uncolored = [Filter::eq("color", s("red")).or_missing()];
+        let page = backend.scan(TABLE, &red_or_uncolored, PageRequest::default()).await.unwrap();
+        assert_eq!(ids(&page), ["a", "c", "d", "e"]);
+
+        let small = [Filter::lt("size", n("9.5"))];
+        let page = backend.scan(TABLE, &small, PageRequest::default()).await.unwrap();
+        assert_eq!(ids(&page), ["b", "d", "e"]);
+    }
+
+    #[test]
+    fn values_of_different_types_do_not_compare() {
+        assert_eq!(compare(&n("10"), &n("9")), Some(Ordering::Greater));
+        assert_eq!(compare(&s("10"), &s("9")), Some(Ordering::Less));
+        assert_eq!(compare(&s("10"), &n("10")), None);
+    }
+}
@@ -0,0 +1,162 @@
+//! Storage backends for the repository.
+//!
+//! [`StorageBackend`] is the narrow set of item operations the repository needs.
+//! [`DynamoDbBackend`] talks to DynamoDB (or DynamoDB Local); [`InMemoryBackend`]
+//! keeps everything in process, so the full schema can run without a network.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use thiserror::Error;
+
+use crate::{ repository::{ Filter, KeyCondition }, AppError };
+
+pub mod dynamodb;
+pub mod memory;
+
+pub use dynamodb::DynamoDbBackend;
+pub use memory::InMemoryBackend;
+
+/// A raw DynamoDB item.
+pub type Item = HashMap<String, AttributeValue>;
+
+/// Condition that must hold on the stored item for a write to go through.
+#[derive(Clone, Debug)]
+pub enum Condition {
+    AttributeExists(String),
+    AttributeNotExists(String),
+    /// The attribute exists and equals the given value.
+    AttributeEquals(String, AttributeValue),
+}
+
+/// One write of [`StorageBackend::transact_put_items`].
+#[derive(Clone, Debug)]
+pub struct TransactPut {
+    pub table: String,
+    pub item: Item,
+    pub condition: Condition,
+}
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Conditional check failed")]
+    ConditionFailed,
+
+    #[error("{0}")]
+    Backend(String),
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        AppError::DatabaseError(e.to_string())
+    }
+}
+
+/// One of `total` disjoint parts of a table, so that several scans can read
+/// it in parallel. Every item falls in exactly one segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub index: i32,
+    pub total: i32,
+}
+
+/// Paging parameters for scans and queries.
+#[derive(Clone, Debug, Default)]
+pub struct PageRequest {
+    /// Maximum number of items to evaluate, before filters are applied.
+    pub limit: Option<i32>,
+    pub exclusive_start_key: Option<Item>,
+    /// Part of the table a scan reads; the whole table when absent. Queries
+    /// ignore it.
+    pub segment: Option<Segment>,
+}
+
+/// One page of raw items.
+#[derive(Clone, Debug, Default)]
+pub struct ItemPage {
+    pub items: Vec<Item>,
+    pub last_evaluated_key: Option<Item>,
+}
+
+/// Item-level operations over tables keyed by a string `id`.
+///
+/// Implementations follow DynamoDB semantics: `limit` bounds the number of
+/// items evaluated before filtering, global secondary indexes are sparse, and a
+/// page that stops early reports a `last_evaluated_key`.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn get_item(&self, table: &str, id: &str) -> Result<Option<Item>, StorageError>;
+
+    /// Fetches every stored item among `ids`, in no particular order. Missing
+    /// ids are left out.
+    async fn batch_get_items(&self, table: &str, ids: &[String]) -> Result<Vec<Item>, StorageError> {
+        let mut items = Vec::with_capacity(ids.len());
+
+        for id in ids {
+            if let Some(item) = self.get_item(table, id).await? {
+                items.push(item);
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn put_item(&self, table: &str, item: Item) -> Result<(), StorageError>;
+
+    /// Writes every item, replacing stored items with the same id. Not atomic:
+    /// when it fails, some of the items may have been written.
+    async fn batch_put_items(&self, table: &str, items: Vec<Item>) -> Result<(), StorageError> {
+        for item in items {
+            self.put_item(table, item).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `item` only if `condition` holds on the currently stored item,
+    /// failing with [`StorageError::ConditionFailed`] otherwise.
+    async fn put_item_if(
+        &self,
+        table: &str,
+        item: Item,
+        condition: Condition
+    ) -> Result<(), StorageError>;
+
+    /// Writes every item, all or none, each only if its condition holds.
+    /// Fails with [`StorageError::ConditionFailed`] when any condition does not.
+    async fn transact_put_items(&self, puts: Vec<TransactPut>) -> Result<(), StorageError>;
+
+    async fn delete_item(
+        &self,
+        table: &str,
+        id: &str,
+        condition: Option<Condition>
+    ) -> Result<(), StorageError>;
+
+    /// Deletes every item among `ids`; missing ids are ignored. Not atomic:
+    /// when it fails, some of the items may have been deleted.
+    async fn batch_delete_items(&self, table: &str, ids: Vec<String>) -> Result<(), StorageError> {
+        for id in ids {
+            self.delete_item(table, &id, None).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn scan(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError>;
+
+    async fn query(
+        &self,
+        table: &str,
+        key_condition: &KeyCondition,
+        filters: &[Filter],
+        descending: bool,
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError>;
+}
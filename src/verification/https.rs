@@ -0,0 +1,233 @@
+//! Network implementations of the verification lookups.
+//!
+//! TXT records are resolved over DNS-over-HTTPS (the JSON API offered by
+//! Cloudflare and Google), so the Lambda needs nothing beyond outbound HTTPS.
+
+use std::{ sync::{ Arc, OnceLock }, time::Duration };
+
+use async_trait::async_trait;
+use axum::{ body::Bytes, http::{ header, Request, StatusCode } };
+use http_body_util::{ BodyExt, Empty, Limited };
+use hyper_rustls::{ HttpsConnector, HttpsConnectorBuilder };
+use hyper_util::{ client::legacy::{ connect::HttpConnector, Client }, rt::TokioExecutor };
+use serde::Deserialize;
+
+use crate::{ config::VerificationConfig, AppError };
+
+use super::{ TxtResolver, WellKnownFetcher };
+
+/// Responses larger than this are rejected rather than buffered.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+/// DNS record type number of TXT records.
+const TXT_RECORD_TYPE: u16 = 16;
+/// DNS response code for a name that does not exist.
+const NXDOMAIN: u16 = 3;
+
+type HttpsClient = Client<HttpsConnector<HttpConnector>, Empty<Bytes>>;
+
+fn lookup_failed(uri: &str, error: impl std::fmt::Display) -> AppError {
+    AppError::ValidationError(format!("Could not fetch {}: {}", uri, error))
+}
+
+/// Minimal HTTPS-only GET client. The TLS setup happens on first use, so a
+/// missing certificate store shows up as a lookup error instead of failing
+/// startup.
+#[derive(Clone)]
+struct HttpsGetter {
+    client: Arc<OnceLock<Result<HttpsClient, String>>>,
+    timeout: Duration,
+}
+
+impl HttpsGetter {
+    fn new(config: &VerificationConfig) -> Self {
+        Self {
+            client: Arc::new(OnceLock::new()),
+            timeout: Duration::from_secs(config.lookup_timeout),
+        }
+    }
+
+    fn client(&self) -> Result<&HttpsClient, AppError> {
+        self.client
+            .get_or_init(|| {
+                let connector = HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .map_err(|e| e.to_string())?
+                    .https_only()
+                    .enable_http1()
+                    .build();
+
+                Ok(Client::builder(TokioExecutor::new()).build(connector))
+            })
+            .as_ref()
+            .map_err(|e| AppError::InternalServerError(format!("Failed to load TLS roots: {}", e)))
+    }
+
+    async fn get(&self, uri: &str, accept: &str) -> Result<(StatusCode, Bytes), AppError> {
+        let client = self.client()?;
+
+        let request = Request::get(uri)
+            .header(header::ACCEPT, accept)
+            .body(Empty::new())
+            .map_err(|e| lookup_failed(uri, e))?;
+
+        let response = async {
+            let response = client.request(request).await.map_err(|e| lookup_failed(uri, e))?;
+            let status = response.status();
+
+            let body = Limited::new(response.into_body(), MAX_RESPONSE_BYTES)
+                .collect().await
+                .map_err(|e| lookup_failed(uri, e))?
+                .to_bytes();
+
+            Ok((status, body))
+        };
+
+        tokio::time::timeout(self.timeout, response).await.map_err(|_| {
+            AppError::ValidationError(format!("Timed out fetching {}", uri))
+        })?
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u16,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+/// Joins the character-strings of a TXT record in presentation format, e.g.
+/// `"abc" "def"` becomes `abcdef`. Unquoted data is returned as is.
+pub fn parse_txt_data(data: &str) -> String {
+    if !data.trim_start().starts_with('"') {
+        return data.to_string();
+    }
+
+    let mut value = String::new();
+    let mut chars = data.chars();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+            }
+            '\\' if quoted => {
+                let Some(next) = chars.next() else {
+                    break;
+                };
+
+                // \DDD is a decimal byte; anything else escapes itself
+                if let Some(first) = next.to_digit(10) {
+                    let digits: String = chars.by_ref().take(2).collect();
+                    let code = digits
+                        .chars()
+                        .filter_map(|d| d.to_digit(10))
+                        .fold(first, |code, d| code * 10 + d);
+                    value.extend(char::from_u32(code));
+                } else {
+                    value.push(next);
+                }
+            }
+            c if quoted => value.push(c),
+            _ => {}
+        }
+    }
+
+    value
+}
+
+/// [`TxtResolver`] that queries a DNS-over-HTTPS JSON endpoint.
+#[derive(Clone)]
+pub struct DohTxtResolver {
+    endpoint: String,
+    getter: HttpsGetter,
+}
+
+impl DohTxtResolver {
+    pub fn new(config: &VerificationConfig) -> Self {
+        Self {
+            endpoint: config.doh_endpoint.clone(),
+            getter: HttpsGetter::new(config),
+        }
+    }
+}
+
+#[async_trait]
+impl TxtResolver for DohTxtResolver {
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>, AppError> {
+        let query = form_urlencoded::Serializer
+            ::new(String::new())
+            .append_pair("name", name)
+            .append_pair("type", "TXT")
+            .finish();
+        let uri = format!("{}?{}", self.endpoint, query);
+
+        let (status, body) = self.getter.get(&uri, "application/dns-json").await?;
+
+        if !status.is_success() {
+            return Err(lookup_failed(&uri, status));
+        }
+
+        let response: DohResponse = serde_json
+            ::from_slice(&body)
+            .map_err(|e| lookup_failed(&uri, e))?;
+
+        match response.status {
+            0 => {}
+            NXDOMAIN => {
+                return Ok(Vec::new());
+            }
+            code => {
+                return Err(
+                    AppError::ValidationError(
+                        format!("DNS lookup of {} failed with response code {}", name, code)
+                    )
+                );
+            }
+        }
+
+        Ok(
+            response.answer
+                .iter()
+                .filter(|answer| answer.record_type == TXT_RECORD_TYPE)
+                .map(|answer| parse_txt_data(&answer.data))
+                .collect()
+        )
+    }
+}
+
+/// [`WellKnownFetcher`] that fetches over HTTPS. Redirects are not followed,
+/// so the file has to be served by the domain itself.
+#[derive(Clone)]
+pub struct HttpsWellKnownFetcher {
+    getter: HttpsGetter,
+}
+
+impl HttpsWellKnownFetcher {
+    pub fn new(config: &VerificationConfig) -> Self {
+        Self { getter: HttpsGetter::new(config) }
+    }
+}
+
+#[async_trait]
+impl WellKnownFetcher for HttpsWellKnownFetcher {
+    async fn fetch(&self, url: &str) -> Result<Option<String>, AppError> {
+        let (status, body) = self.getter.get(url, "text/plain").await?;
+
+        if status != StatusCode::OK {
+            return Ok(None);
+        }
+
+        String::from_utf8(body.to_vec())
+            .map(Some)
+            .map_err(|_| AppError::ValidationError(format!("{} is not valid UTF-8", url)))
+    }
+}
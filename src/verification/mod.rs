@@ -0,0 +1,144 @@
+//! Employer domain verification.
+//!
+//! An employer admin requests a challenge token and publishes it in one of two
+//! places under the employer's domain:
+//!
+//! - a DNS TXT record at `_job-board-challenge.<domain>` with the value
+//!   `job-board-verification=<token>`, or
+//! - a file at `https://<domain>/.well-known/job-board-verification.txt` whose
+//!   body is the token.
+//!
+//! Lookups go through the [`TxtResolver`] and [`WellKnownFetcher`] traits, so
+//! tests can swap in local stand-ins for the network implementations in
+//! [`https`].
+
+use std::sync::Arc;
+
+use async_graphql::Enum;
+use async_trait::async_trait;
+use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+use rand_core::{ OsRng, TryRngCore };
+use serde::{ Deserialize, Serialize };
+
+use crate::{ config::VerificationConfig, AppError };
+
+pub mod https;
+
+pub use https::{ DohTxtResolver, HttpsWellKnownFetcher };
+
+/// Label prepended to the domain for the TXT record.
+pub const DNS_RECORD_LABEL: &str = "_job-board-challenge";
+/// Prefix of the TXT record value; the token follows it.
+pub const DNS_RECORD_PREFIX: &str = "job-board-verification=";
+/// Path of the challenge file on the employer's site.
+pub const WELL_KNOWN_PATH: &str = "/.well-known/job-board-verification.txt";
+
+/// Where an employer published its challenge token.
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub enum VerificationMethod {
+    DnsTxt,
+    WellKnownFile,
+}
+
+/// Looks up the TXT records published at a DNS name.
+#[async_trait]
+pub trait TxtResolver: Send + Sync {
+    /// Every TXT record at `name`, with multi-string records joined. A name
+    /// without records resolves to an empty list.
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>, AppError>;
+}
+
+/// Fetches a well-known file from an employer's site.
+#[async_trait]
+pub trait WellKnownFetcher: Send + Sync {
+    /// The body served at `url`, or `None` if the server does not serve it.
+    async fn fetch(&self, url: &str) -> Result<Option<String>, AppError>;
+}
+
+/// Generates a challenge token with 256 bits of entropy.
+pub fn generate_challenge_token() -> Result<String, AppError> {
+    let mut bytes = [0u8; 32];
+    OsRng.try_fill_bytes(&mut bytes).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to generate challenge token: {}", e))
+    })?;
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+pub fn dns_record_name(domain: &str) -> String {
+    format!("{}.{}", DNS_RECORD_LABEL, domain)
+}
+
+pub fn dns_record_value(token: &str) -> String {
+    format!("{}{}", DNS_RECORD_PREFIX, token)
+}
+
+pub fn well_known_url(domain: &str) -> String {
+    format!("https://{}{}", domain, WELL_KNOWN_PATH)
+}
+
+/// Checks challenge tokens against what an employer has published.
+///
+/// Cheap to clone; clones share the same resolver and fetcher.
+#[derive(Clone)]
+pub struct DomainVerifier {
+    resolver: Arc<dyn TxtResolver>,
+    fetcher: Arc<dyn WellKnownFetcher>,
+}
+
+impl DomainVerifier {
+    pub fn new<R, F>(resolver: R, fetcher: F) -> Self
+        where R: TxtResolver + 'static, F: WellKnownFetcher + 'static
+    {
+        Self {
+            resolver: Arc::new(resolver),
+            fetcher: Arc::new(fetcher),
+        }
+    }
+
+    /// A verifier that looks records up over the network.
+    pub fn from_config(config: &VerificationConfig) -> Self {
+        Self::new(DohTxtResolver::new(config), HttpsWellKnownFetcher::new(config))
+    }
+
+    /// Succeeds if `token` is published for `domain` using `method`, and
+    /// fails with a `ValidationError` saying what was found otherwise.
+    pub async fn verify(
+        &self,
+        domain: &str,
+        token: &str,
+        method: VerificationMethod
+    ) -> Result<(), AppError> {
+        match method {
+            VerificationMethod::DnsTxt => {
+                let name = dns_record_name(domain);
+                let expected = dns_record_value(token);
+                let records = self.resolver.txt_records(&name).await?;
+
+                if records.iter().any(|record| record.trim() == expected) {
+                    return Ok(());
+                }
+
+                Err(
+                    AppError::ValidationError(
+                        format!("No TXT record at {} matches the challenge token", name)
+                    )
+                )
+            }
+            VerificationMethod::WellKnownFile => {
+                let url = well_known_url(domain);
+
+                match self.fetcher.fetch(&url).await? {
+                    Some(body) if body.trim() == token => Ok(()),
+                    Some(_) =>
+                        Err(
+                            AppError::ValidationError(
+                                format!("{} does not contain the challenge token", url)
+                            )
+                        ),
+                    None => Err(AppError::ValidationError(format!("{} was not found", url))),
+                }
+            }
+        }
+    }
+}
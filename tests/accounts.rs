@@ -0,0 +1,171 @@
+//! Registration, login throttling and refresh token rotation.
+
+mod common;
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use job_board_lambda::{
+    auth::{ self, Principal },
+    config::Config,
+    models::user::{ Role, MAX_FAILED_LOGINS },
+    GraphQLSchema,
+    StorageBackend,
+};
+use serde_json::Value;
+
+use common::{ error_code, execute, schema, setup };
+
+const EMAIL: &str = "Casey@Example.com";
+const PASSWORD: &str = "correct horse battery";
+
+async fn register(schema: &GraphQLSchema) -> Value {
+    let query = format!(
+        r#"mutation {{ register(email: "{EMAIL}", password: "{PASSWORD}") {{ accessToken refreshToken expiresIn user {{ id email }} }} }}"#
+    );
+
+    execute(schema, &query, None).await
+}
+
+async fn login(schema: &GraphQLSchema, password: &str) -> Value {
+    let query = format!(
+        r#"mutation {{ login(email: "casey@example.com", password: "{password}") {{ accessToken refreshToken }} }}"#
+    );
+
+    execute(schema, &query, None).await
+}
+
+async fn refresh(schema: &GraphQLSchema, token: &str) -> Value {
+    let query = format!(r#"mutation {{ refreshToken(refreshToken: "{token}") {{ refreshToken }} }}"#);
+
+    execute(schema, &query, None).await
+}
+
+#[tokio::test]
+async fn register_issues_tokens_for_a_normalized_email() {
+    let schema = schema();
+
+    let response = register(&schema).await;
+    let payload = &response["data"]["register"];
+
+    assert_eq!(payload["user"]["email"], "casey@example.com");
+    assert_eq!(payload["expiresIn"], Config::default().auth.token_expiry);
+
+    let principal = auth
+        ::verify_token(payload["accessToken"].as_str().unwrap(), &Config::default().auth)
+        .unwrap();
+    assert_eq!(principal.subject, payload["user"]["id"].as_str().unwrap());
+
+    let duplicate = register(&schema).await;
+    assert_eq!(error_code(&duplicate), "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn an_email_claimed_but_not_yet_indexed_cannot_register_again() {
+    let (backend, _, schema) = setup();
+
+    // As left by a concurrent registration that EmailIndex does not show yet
+    let claim = HashMap::from([
+        ("id".to_string(), AttributeValue::S("email#casey@example.com".to_string())),
+        ("claimed_by".to_string(), AttributeValue::S("user-other".to_string())),
+    ]);
+    backend.put_item("Users", claim).await.unwrap();
+
+    let response = register(&schema).await;
+    assert_eq!(error_code(&response), "VALIDATION_ERROR");
+    assert_eq!(backend.scan("Users", &[], Default::default()).await.unwrap().items.len(), 1);
+}
+
+#[tokio::test]
+async fn short_passwords_are_rejected() {
+    let response = execute(
+        &schema(),
+        r#"mutation { register(email: "a@example.com", password: "short") { accessToken } }"#,
+        None
+    ).await;
+
+    assert_eq!(error_code(&response), "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn me_resolves_the_authenticated_user() {
+    let schema = schema();
+    let registered = register(&schema).await;
+    let user_id = registered["data"]["register"]["user"]["id"].as_str().unwrap().to_string();
+
+    let anonymous = execute(&schema, "{ me { email } }", None).await;
+    assert_eq!(error_code(&anonymous), "UNAUTHORIZED");
+
+    let principal = Principal {
+        subject: user_id,
+        email: "casey@example.com".to_string(),
+        role: Role::JobSeeker,
+        employer_id: None,
+    };
+    let response = execute(&schema, "{ me { email role } }", Some(principal)).await;
+    assert_eq!(response["data"]["me"]["email"], "casey@example.com");
+    assert_eq!(response["data"]["me"]["role"], "JOB_SEEKER");
+}
+
+#[tokio::test]
+async fn repeated_failures_lock_the_account() {
+    let schema = schema();
+    register(&schema).await;
+
+    for _ in 0..MAX_FAILED_LOGINS {
+        let response = login(&schema, "wrong password!").await;
+        assert_eq!(response["errors"][0]["message"], "Authentication error: Invalid email or password");
+    }
+
+    let locked = login(&schema, PASSWORD).await;
+    assert_eq!(error_code(&locked), "AUTH_ERROR");
+    assert!(locked["errors"][0]["message"].as_str().unwrap().contains("temporarily locked"));
+}
+
+#[tokio::test]
+async fn refresh_tokens_rotate_and_reuse_revokes_the_family() {
+    let schema = schema();
+    register(&schema).await;
+
+    let session = login(&schema, PASSWORD).await;
+    let first = session["data"]["login"]["refreshToken"].as_str().unwrap().to_string();
+
+    let rotated = refresh(&schema, &first).await;
+    let second = rotated["data"]["refreshToken"]["refreshToken"].as_str().unwrap().to_string();
+    assert_ne!(first, second);
+
+    // Replaying the rotated-away token revokes the new one too
+    assert_eq!(error_code(&refresh(&schema, &first).await), "AUTH_ERROR");
+    assert_eq!(error_code(&refresh(&schema, &second).await), "AUTH_ERROR");
+}
+
+#[tokio::test]
+async fn logout_revokes_the_session() {
+    let schema = schema();
+    let registered = register(&schema).await;
+    let token = registered["data"]["register"]["refreshToken"].as_str().unwrap().to_string();
+
+    let query = format!(r#"mutation {{ logout(refreshToken: "{token}") }}"#);
+    let response = execute(&schema, &query, None).await;
+    assert_eq!(response["data"]["logout"], true);
+
+    assert_eq!(error_code(&refresh(&schema, &token).await), "AUTH_ERROR");
+}
+
+#[tokio::test]
+async fn create_admin_promotes_a_registered_account() {
+    let (_, repository, schema) = setup();
+    let config = Config::default();
+
+    let missing = auth::grant_site_admin(&repository, "casey@example.com").await;
+    assert!(missing.is_err());
+
+    register(&schema).await;
+    let user = auth::grant_site_admin(&repository, " Casey@Example.com").await.unwrap();
+    assert_eq!(user.role, Role::SiteAdmin);
+
+    let response = login(&schema, PASSWORD).await;
+    let token = response["data"]["login"]["accessToken"].as_str().unwrap();
+
+    assert_eq!(auth::verify_token(token, &config.auth).unwrap().role, Role::SiteAdmin);
+}
@@ -0,0 +1,162 @@
+//! The Indeed XML export for job aggregators.
+
+mod common;
+
+use axum::{ body::{ self, Body }, http::{ header, Request, StatusCode } };
+use chrono::{ DateTime, Duration, Utc };
+use job_board_lambda::{
+    aggregator::{ self, IndeedExport },
+    build_schema,
+    config::{ Config, FeedConfig },
+    models::{
+        job_posting::{ ExpectedHoursRange, JobTypeOption, PostingStatus },
+        pay::CadenceOption,
+        work_arrangement::WorkArrangement,
+    },
+    server,
+    Address,
+    Employer,
+    JobPosting,
+    Pay,
+    Repository,
+};
+use rust_decimal::Decimal;
+use tower::ServiceExt;
+
+use common::at;
+
+fn posting(id: &str, job_type: JobTypeOption, created_at: DateTime<Utc>) -> JobPosting {
+    let mut job_posting = JobPosting::new(
+        id.to_string(),
+        format!("Job {}", id),
+        "employer-1".to_string(),
+        Address::new(
+            "1 Main St".to_string(),
+            None,
+            "Marquette".to_string(),
+            "MI".to_string(),
+            "US".to_string(),
+            "49855".to_string()
+        ),
+        None,
+        job_type.to_string(),
+        None,
+        "Work.".to_string(),
+        None,
+        None,
+        None,
+        ExpectedHoursRange::new(40, 40),
+        WorkArrangement::default(),
+        Vec::new()
+    ).unwrap();
+
+    job_posting.status = PostingStatus::Published;
+    job_posting.created_at = created_at;
+    job_posting.updated_at = created_at;
+
+    job_posting
+}
+
+async fn repository() -> Repository {
+    let repo = Repository::in_memory();
+
+    repo.create(
+        Employer::new("employer-1".to_string(), "Lakeshore".to_string(), None, None, None, None).unwrap()
+    ).await.unwrap();
+
+    repo
+}
+
+#[test]
+fn salary_strings() {
+    let pay = |cadence, min, max: Option<Decimal>, currency| {
+        Pay::new(cadence, min, max, currency).unwrap()
+    };
+
+    assert_eq!(
+        aggregator::salary(&pay(CadenceOption::Hour, Decimal::new(245, 1), Some(Decimal::from(32)), "USD")),
+        "$24.50 - $32 per hour"
+    );
+    assert_eq!(
+        aggregator::salary(&pay(CadenceOption::Year, Decimal::from(70000), None, "EUR")),
+        "€70000 per year"
+    );
+    assert_eq!(
+        aggregator::salary(
+            &pay(CadenceOption::Month, Decimal::from(5000), Some(Decimal::from(5000)), "CHF")
+        ),
+        "CHF 5000 per month"
+    );
+}
+
+#[tokio::test]
+async fn jobs_are_mapped_and_escaped() {
+    let repo = repository().await;
+
+    let mut welder = posting("welder", JobTypeOption::Seasonal, at("2026-03-01T10:00:00Z"));
+    welder.job_title = "Welder ]]> Lead\u{8}".to_string();
+    welder.job_description = "Weld <b>pipe</b> & more ]]> done".to_string();
+    welder.extra_info = Some("Bring\u{0}\t<boots>".to_string());
+    welder.pay = Some(
+        Pay::new(CadenceOption::Hour, Decimal::from(24), Some(Decimal::from(32)), "USD").unwrap()
+    );
+    welder.work_arrangement = WorkArrangement::remote();
+    repo.create(welder).await.unwrap();
+
+    let mut out = Vec::new();
+    IndeedExport::new(repo, FeedConfig::default(), Utc::now()).write_to(&mut out).await.unwrap();
+    let xml = String::from_utf8(out).unwrap();
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<source>\n"));
+    assert!(xml.ends_with("</source>\n"));
+    assert!(xml.contains("<title><![CDATA[Welder ]]]]><![CDATA[> Lead]]></title>"));
+    assert!(xml.contains("<date><![CDATA[Sun, 01 Mar 2026 10:00:00 GMT]]></date>"));
+    assert!(xml.contains("<url><![CDATA[http://localhost:3000/jobs/welder]]></url>"));
+    assert!(xml.contains("<company><![CDATA[Lakeshore]]></company>"));
+    assert!(xml.contains("<jobtype><![CDATA[temporary]]></jobtype>"));
+    assert!(xml.contains("<salary><![CDATA[$24 - $32 per hour]]></salary>"));
+    assert!(xml.contains("<remotetype><![CDATA[Fully remote]]></remotetype>"));
+    assert!(
+        xml.contains(
+            "<description><![CDATA[<p>Weld &lt;b&gt;pipe&lt;/b&gt; &amp; more ]]&gt; done</p><p>Bring\t&lt;boots&gt;</p>]]></description>"
+        ),
+        "{}",
+        xml
+    );
+}
+
+#[tokio::test]
+async fn route_streams_every_live_posting_across_pages() {
+    let repo = repository().await;
+    let start = at("2026-01-01T00:00:00Z");
+
+    // More than one page of postings
+    for i in 0..105 {
+        let created_at = start + Duration::minutes(i);
+        repo.create(posting(&format!("job-{:03}", i), JobTypeOption::FullTime, created_at)).await.unwrap();
+    }
+    repo.create(JobPosting {
+        status: PostingStatus::Draft,
+        ..posting("draft", JobTypeOption::FullTime, start)
+    }).await.unwrap();
+
+    let schema = build_schema(repo.clone(), Config::default());
+    let router = server::build_router(schema, repo, &Config::default()).unwrap();
+
+    let response = router
+        .oneshot(Request::get("/feeds/indeed.xml").body(Body::empty()).unwrap()).await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/xml; charset=utf-8");
+
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let xml = String::from_utf8(body.to_vec()).unwrap();
+
+    assert_eq!(xml.matches("<job>").count(), 105);
+    assert!(!xml.contains("[draft]"));
+    assert!(
+        xml.find("[job-104]").unwrap() < xml.find("[job-000]").unwrap(),
+        "newest postings come first"
+    );
+}
@@ -0,0 +1,130 @@
+//! Bearer token authentication through the router.
+
+use axum::{ body::{ self, Body }, http::{ header, Request, StatusCode }, Router };
+use job_board_lambda::{
+    auth::{ self, Principal },
+    config::Config,
+    build_schema,
+    models::user::Role,
+    server,
+    Repository,
+};
+use serde_json::{ json, Value };
+use tower::ServiceExt;
+
+const DELETE_MUTATION: &str = r#"mutation { deleteJobPosting(id: "missing") }"#;
+
+fn employer_member() -> Principal {
+    Principal {
+        subject: "user-1".to_string(),
+        email: "member@example.com".to_string(),
+        role: Role::EmployerMember,
+        employer_id: Some("employer-1".to_string()),
+    }
+}
+
+fn router(config: &Config) -> Router {
+    let repository = Repository::in_memory();
+    let schema = build_schema(repository.clone(), config.clone());
+
+    server::build_router(schema, repository, config).unwrap()
+}
+
+async fn post_graphql(router: Router, query: &str, token: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::post("/graphql").header(header::CONTENT_TYPE, "application/json");
+
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+
+    let request = request.body(Body::from(json!({ "query": query }).to_string())).unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn anonymous_mutations_are_unauthorized() {
+    let config = Config::default();
+
+    let (status, body) = post_graphql(router(&config), DELETE_MUTATION, None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHORIZED");
+}
+
+#[tokio::test]
+async fn anonymous_queries_are_allowed() {
+    let config = Config::default();
+
+    let (_, body) = post_graphql(router(&config), "{ jobPostings { id } }", None).await;
+
+    assert_eq!(body["data"]["jobPostings"], json!([]));
+}
+
+#[tokio::test]
+async fn valid_tokens_authenticate_mutations() {
+    let config = Config::default();
+    // Only site admins get past the ownership guard for an unknown posting
+    let site_admin = Principal { role: Role::SiteAdmin, employer_id: None, ..employer_member() };
+    let token = auth::issue_token(&site_admin, &config.auth).unwrap();
+
+    let (_, body) = post_graphql(router(&config), DELETE_MUTATION, Some(&token)).await;
+
+    // Authenticated, so the request reaches the repository
+    assert_eq!(body["errors"][0]["extensions"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn tokens_for_another_audience_are_rejected() {
+    let config = Config::default();
+    let mut other = config.auth.clone();
+    other.audience = "another_api".to_string();
+    let token = auth::issue_token(&employer_member(), &other).unwrap();
+
+    let (status, body) = post_graphql(router(&config), DELETE_MUTATION, Some(&token)).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "AUTH_ERROR");
+}
+
+#[tokio::test]
+async fn tokens_with_a_bad_signature_are_rejected() {
+    let config = Config::default();
+    let mut other = config.auth.clone();
+    other.jwt_secret = "some-other-secret".to_string();
+    let token = auth::issue_token(&employer_member(), &other).unwrap();
+
+    let (status, _) = post_graphql(router(&config), DELETE_MUTATION, Some(&token)).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn expired_tokens_are_rejected() {
+    let config = Config::default();
+    let now = chrono::Utc::now().timestamp();
+
+    let claims = auth::Claims {
+        sub: "user-1".to_string(),
+        email: "member@example.com".to_string(),
+        role: Role::EmployerMember,
+        employer_id: Some("employer-1".to_string()),
+        iss: config.auth.issuer.clone(),
+        aud: config.auth.audience.clone(),
+        iat: now - 7200,
+        exp: now - 3600,
+    };
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(config.auth.jwt_secret.as_bytes())
+    ).unwrap();
+
+    let (status, body) = post_graphql(router(&config), DELETE_MUTATION, Some(&token)).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["errors"][0]["message"], "Authentication error: Token has expired");
+}
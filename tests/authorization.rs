@@ -0,0 +1,247 @@
+//! Role and ownership guards on resolvers.
+
+mod common;
+
+use job_board_lambda::{ auth::Principal, models::user::Role, GraphQLSchema };
+use serde_json::json;
+
+use common::{
+    create_posting,
+    create_posting_mutation,
+    employer,
+    error_code,
+    execute,
+    principal,
+    schema,
+    site_admin,
+};
+
+fn applicant() -> Principal {
+    Principal {
+        subject: "user-applicant".to_string(),
+        email: "sam@example.com".to_string(),
+        role: Role::JobSeeker,
+        employer_id: None,
+    }
+}
+
+async fn apply(schema: &GraphQLSchema, job_posting_id: &str) -> String {
+    let query = format!(
+        r#"mutation {{ applyToJob(jobPostingId: "{job_posting_id}", applicantName: "Sam", applicantEmail: "Sam@Example.com") {{ id }} }}"#
+    );
+    let response = execute(schema, &query, Some(applicant())).await;
+
+    response["data"]["applyToJob"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn only_site_admins_manage_categories() {
+    let schema = schema();
+    let mutation = r#"mutation { createJobCategory(name: "Trades") { slug } }"#;
+
+    let anonymous = execute(&schema, mutation, None).await;
+    assert_eq!(error_code(&anonymous), "UNAUTHORIZED");
+
+    let employer_admin = execute(
+        &schema,
+        mutation,
+        Some(principal(Role::EmployerAdmin, Some("employer-1")))
+    ).await;
+    assert_eq!(error_code(&employer_admin), "FORBIDDEN");
+
+    let site_admin = execute(&schema, mutation, Some(site_admin())).await;
+    assert_eq!(site_admin["data"]["createJobCategory"]["slug"], "trades");
+}
+
+#[tokio::test]
+async fn job_seekers_cannot_create_postings() {
+    let mutation = create_posting_mutation(&[], "id");
+    let response = execute(&schema(), &mutation, Some(applicant())).await;
+
+    assert_eq!(error_code(&response), "FORBIDDEN");
+}
+
+#[tokio::test]
+async fn only_the_owning_employer_edits_a_posting() {
+    let schema = schema();
+    let id = create_posting(&schema, employer(), &[]).await;
+    let mutation = format!(r#"mutation {{ deleteJobPosting(id: "{id}") }}"#);
+
+    let other = execute(&schema, &mutation, Some(principal(Role::EmployerAdmin, Some("employer-2")))).await;
+    assert_eq!(error_code(&other), "FORBIDDEN");
+
+    let owner = execute(&schema, &mutation, Some(employer())).await;
+    assert_eq!(owner["data"]["deleteJobPosting"], true);
+}
+
+#[tokio::test]
+async fn applications_are_visible_to_the_applicant_and_owning_employer() {
+    let schema = schema();
+    let posting_id = create_posting(&schema, employer(), &[]).await;
+    let application_id = apply(&schema, &posting_id).await;
+    let query = format!(r#"{{ jobApplication(id: "{application_id}") {{ id }} }}"#);
+
+    let stranger = execute(&schema, &query, Some(principal(Role::JobSeeker, None))).await;
+    assert_eq!(error_code(&stranger), "FORBIDDEN");
+
+    let other_employer = execute(
+        &schema,
+        &query,
+        Some(principal(Role::EmployerMember, Some("employer-2")))
+    ).await;
+    assert_eq!(error_code(&other_employer), "FORBIDDEN");
+
+    let own = execute(&schema, &query, Some(applicant())).await;
+    assert_eq!(own["data"]["jobApplication"]["id"], application_id.as_str());
+
+    let employer = execute(&schema, &query, Some(employer())).await;
+    assert_eq!(employer["data"]["jobApplication"]["id"], application_id.as_str());
+}
+
+#[tokio::test]
+async fn sharing_the_applicant_email_grants_no_access() {
+    let schema = schema();
+    let posting_id = create_posting(&schema, employer(), &[]).await;
+    let application_id = apply(&schema, &posting_id).await;
+    let impostor = Principal { subject: "user-impostor".to_string(), ..applicant() };
+
+    let query = format!(r#"{{ jobApplication(id: "{application_id}") {{ id }} }}"#);
+    let response = execute(&schema, &query, Some(impostor.clone())).await;
+    assert_eq!(error_code(&response), "FORBIDDEN");
+
+    let anonymous = execute(
+        &schema,
+        &format!(
+            r#"mutation {{ applyToJob(jobPostingId: "{posting_id}", applicantName: "Sam", applicantEmail: "sam@example.com") {{ id }} }}"#
+        ),
+        None
+    ).await;
+    let anonymous_id = anonymous["data"]["applyToJob"]["id"].as_str().unwrap();
+    let query = format!(r#"{{ jobApplication(id: "{anonymous_id}") {{ id }} }}"#);
+    let response = execute(&schema, &query, Some(applicant())).await;
+    assert_eq!(error_code(&response), "FORBIDDEN");
+
+    let list = r#"{ jobApplicationsByApplicant(applicantEmail: "sam@example.com") { nodes { id } } }"#;
+    let own = execute(&schema, list, Some(applicant())).await;
+    assert_eq!(own["data"]["jobApplicationsByApplicant"]["nodes"], json!([{ "id": application_id }]));
+
+    let listed = execute(&schema, list, Some(impostor)).await;
+    assert_eq!(listed["data"]["jobApplicationsByApplicant"]["nodes"], json!([]));
+}
+
+#[tokio::test]
+async fn applicants_can_only_withdraw() {
+    let schema = schema();
+    let posting_id = create_posting(&schema, employer(), &[]).await;
+    let application_id = apply(&schema, &posting_id).await;
+    let update = |status: &str| {
+        format!(
+            r#"mutation {{ updateApplicationStatus(id: "{application_id}", status: {status}) {{ status statusHistory {{ changedBy }} }} }}"#
+        )
+    };
+
+    let offered = execute(&schema, &update("REVIEWED"), Some(applicant())).await;
+    assert_eq!(error_code(&offered), "FORBIDDEN");
+
+    let withdrawn = execute(&schema, &update("WITHDRAWN"), Some(applicant())).await;
+    assert_eq!(withdrawn["data"]["updateApplicationStatus"]["status"], "WITHDRAWN");
+    assert_eq!(
+        withdrawn["data"]["updateApplicationStatus"]["statusHistory"],
+        json!([{ "changedBy": "user-applicant" }, { "changedBy": "user-applicant" }])
+    );
+}
+
+#[tokio::test]
+async fn only_the_applicant_can_withdraw() {
+    let schema = schema();
+    let posting_id = create_posting(&schema, employer(), &[]).await;
+    let application_id = apply(&schema, &posting_id).await;
+    let withdraw = format!(
+        r#"mutation {{ updateApplicationStatus(id: "{application_id}", status: WITHDRAWN) {{ status }} }}"#
+    );
+
+    let owner = execute(&schema, &withdraw, Some(employer())).await;
+    assert_eq!(error_code(&owner), "FORBIDDEN");
+
+    let admin = execute(&schema, &withdraw, Some(site_admin())).await;
+    assert_eq!(error_code(&admin), "FORBIDDEN");
+
+    let impostor = Principal { subject: "user-impostor".to_string(), ..applicant() };
+    let response = execute(&schema, &withdraw, Some(impostor)).await;
+    assert_eq!(error_code(&response), "FORBIDDEN");
+
+    let query = format!(r#"{{ jobApplication(id: "{application_id}") {{ status }} }}"#);
+    let unchanged = execute(&schema, &query, Some(applicant())).await;
+    assert_eq!(unchanged["data"]["jobApplication"]["status"], "SUBMITTED");
+}
+
+#[tokio::test]
+async fn employer_admins_manage_only_their_own_members() {
+    let schema = schema();
+    let registered = execute(
+        &schema,
+        r#"mutation { register(email: "new@example.com", password: "long enough password") { user { id } } }"#,
+        None
+    ).await;
+    let user_id = registered["data"]["register"]["user"]["id"].as_str().unwrap().to_string();
+
+    let promote_to_admin = format!(
+        r#"mutation {{ assignUserRole(userId: "{user_id}", role: SITE_ADMIN) {{ role }} }}"#
+    );
+    let escalation = execute(
+        &schema,
+        &promote_to_admin,
+        Some(principal(Role::EmployerAdmin, Some("employer-1")))
+    ).await;
+    assert_eq!(error_code(&escalation), "FORBIDDEN");
+
+    let add_member = format!(
+        r#"mutation {{ assignUserRole(userId: "{user_id}", role: EMPLOYER_MEMBER) {{ role employerId }} }}"#
+    );
+    let added = execute(&schema, &add_member, Some(principal(Role::EmployerAdmin, Some("employer-1")))).await;
+    assert_eq!(added["data"]["assignUserRole"]["role"], "EMPLOYER_MEMBER");
+    assert_eq!(added["data"]["assignUserRole"]["employerId"], "employer-1");
+
+    let poach = execute(&schema, &add_member, Some(principal(Role::EmployerAdmin, Some("employer-2")))).await;
+    assert_eq!(error_code(&poach), "FORBIDDEN");
+}
+
+#[tokio::test]
+async fn only_site_admins_get_past_the_guard_for_an_unknown_posting() {
+    let schema = schema();
+    let list = r#"{ jobApplicationsForPosting(jobPostingId: "missing") { nodes { id } } }"#;
+
+    let seeker = execute(&schema, list, Some(applicant())).await;
+    assert_eq!(error_code(&seeker), "FORBIDDEN");
+
+    let member = execute(&schema, list, Some(employer())).await;
+    assert_eq!(error_code(&member), "FORBIDDEN");
+
+    let delete = r#"mutation { deleteJobPosting(id: "missing") }"#;
+    assert_eq!(error_code(&execute(&schema, delete, Some(employer())).await), "FORBIDDEN");
+
+    let admin = execute(&schema, list, Some(site_admin())).await;
+    assert_eq!(admin["data"]["jobApplicationsForPosting"]["nodes"], json!([]));
+    assert_eq!(error_code(&execute(&schema, delete, Some(site_admin())).await), "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn deleting_a_posting_deletes_its_applications() {
+    let schema = schema();
+    let posting_id = create_posting(&schema, employer(), &[]).await;
+    let application_id = apply(&schema, &posting_id).await;
+
+    let delete = format!(r#"mutation {{ deleteJobPosting(id: "{posting_id}") }}"#);
+    let deleted = execute(&schema, &delete, Some(employer())).await;
+    assert_eq!(deleted["data"]["deleteJobPosting"], true);
+
+    let query = format!(r#"{{ jobApplication(id: "{application_id}") {{ id }} }}"#);
+    let admin = execute(&schema, &query, Some(site_admin())).await;
+    assert_eq!(admin["data"]["jobApplication"], json!(null));
+
+    let list = format!(
+        r#"{{ jobApplicationsForPosting(jobPostingId: "{posting_id}") {{ nodes {{ id }} }} }}"#
+    );
+    let listed = execute(&schema, &list, Some(site_admin())).await;
+    assert_eq!(listed["data"]["jobApplicationsForPosting"]["nodes"], json!([]));
+}
@@ -0,0 +1,122 @@
+//! Helpers shared by the integration tests.
+//!
+//! Every test binary compiles its own copy of this module and uses only part
+//! of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use async_graphql::Request;
+use chrono::{ DateTime, Utc };
+use job_board_lambda::{
+    auth::Principal,
+    build_schema,
+    config::Config,
+    models::user::Role,
+    GraphQLSchema,
+    InMemoryBackend,
+    Repository,
+};
+use serde_json::Value;
+
+/// The address every posting gets unless a test supplies its own.
+pub const ADDRESS: &str =
+    r#"{ street: "1 Main St", city: "Marquette", state: "MI", country: "US", zip: "49855" }"#;
+
+/// Arguments `create_posting_mutation` fills in unless overridden.
+const POSTING_ARGUMENTS: [(&str, &str); 5] = [
+    ("jobTitle", r#""Welder""#),
+    ("address", ADDRESS),
+    ("jobType", r#""FULL_TIME""#),
+    ("jobDescription", r#""Welding""#),
+    ("expectedHours", "{ min: 30, max: 40 }"),
+];
+
+pub fn at(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+/// A schema over a fresh in-memory repository with the default config.
+pub fn schema() -> GraphQLSchema {
+    build_schema(Repository::in_memory(), Config::default())
+}
+
+/// A schema with the default config, along with the backend and repository
+/// behind it for tests that inspect or seed storage directly.
+pub fn setup() -> (InMemoryBackend, Repository, GraphQLSchema) {
+    let backend = InMemoryBackend::new();
+    let repository = Repository::with_backend(backend.clone());
+    let schema = build_schema(repository.clone(), Config::default());
+
+    (backend, repository, schema)
+}
+
+pub async fn execute(schema: &GraphQLSchema, query: &str, principal: Option<Principal>) -> Value {
+    let mut request = Request::new(query);
+
+    if let Some(principal) = principal {
+        request = request.data(principal);
+    }
+
+    serde_json::to_value(schema.execute(request).await).unwrap()
+}
+
+pub fn error_code(response: &Value) -> &Value {
+    &response["errors"][0]["extensions"]["code"]
+}
+
+pub fn principal(role: Role, employer_id: Option<&str>) -> Principal {
+    Principal {
+        subject: format!("user-{:?}-{:?}", role, employer_id),
+        email: "someone@example.com".to_string(),
+        role,
+        employer_id: employer_id.map(str::to_string),
+    }
+}
+
+/// A member of `employer-1`.
+pub fn employer() -> Principal {
+    principal(Role::EmployerMember, Some("employer-1"))
+}
+
+pub fn site_admin() -> Principal {
+    principal(Role::SiteAdmin, None)
+}
+
+/// A `createJobPosting` mutation selecting `selection`. Each of `arguments`
+/// replaces the default of the same name, or follows the defaults if there is
+/// none.
+pub fn create_posting_mutation(arguments: &[(&str, &str)], selection: &str) -> String {
+    let mut all = POSTING_ARGUMENTS.to_vec();
+
+    for &(name, value) in arguments {
+        match all.iter_mut().find(|(default, _)| *default == name) {
+            Some(argument) => {
+                argument.1 = value;
+            }
+            None => all.push((name, value)),
+        }
+    }
+
+    let arguments = all
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("mutation {{ createJobPosting({arguments}) {{ {selection} }} }}")
+}
+
+/// Creates a posting as `principal` and returns its id.
+pub async fn create_posting(
+    schema: &GraphQLSchema,
+    principal: Principal,
+    arguments: &[(&str, &str)]
+) -> String {
+    let mutation = create_posting_mutation(arguments, "id");
+    let response = execute(schema, &mutation, Some(principal)).await;
+
+    match response["data"]["createJobPosting"]["id"].as_str() {
+        Some(id) => id.to_string(),
+        None => panic!("createJobPosting failed: {}", response),
+    }
+}
@@ -0,0 +1,203 @@
+//! Employer profiles and the batched `employer` field on job postings.
+
+mod common;
+
+use std::sync::{ atomic::{ AtomicUsize, Ordering }, Arc };
+
+use async_trait::async_trait;
+use job_board_lambda::{
+    build_schema,
+    config::Config,
+    models::user::Role,
+    repository::{ Filter, KeyCondition },
+    storage::{ Condition, InMemoryBackend, Item, ItemPage, PageRequest, StorageError, TransactPut },
+    GraphQLSchema,
+    Repository,
+    StorageBackend,
+};
+
+use common::{ create_posting_mutation, error_code, execute, principal, schema, site_admin };
+
+/// Counts single and batched reads against the Employers table.
+#[derive(Clone, Default)]
+struct CountingBackend {
+    inner: InMemoryBackend,
+    employer_gets: Arc<AtomicUsize>,
+    employer_batches: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl StorageBackend for CountingBackend {
+    async fn get_item(&self, table: &str, id: &str) -> Result<Option<Item>, StorageError> {
+        if table == "Employers" {
+            self.employer_gets.fetch_add(1, Ordering::SeqCst);
+        }
+        self.inner.get_item(table, id).await
+    }
+
+    async fn batch_get_items(&self, table: &str, ids: &[String]) -> Result<Vec<Item>, StorageError> {
+        if table == "Employers" {
+            self.employer_batches.fetch_add(1, Ordering::SeqCst);
+        }
+        self.inner.batch_get_items(table, ids).await
+    }
+
+    async fn put_item(&self, table: &str, item: Item) -> Result<(), StorageError> {
+        self.inner.put_item(table, item).await
+    }
+
+    async fn put_item_if(
+        &self,
+        table: &str,
+        item: Item,
+        condition: Condition
+    ) -> Result<(), StorageError> {
+        self.inner.put_item_if(table, item, condition).await
+    }
+
+    async fn transact_put_items(&self, puts: Vec<TransactPut>) -> Result<(), StorageError> {
+        self.inner.transact_put_items(puts).await
+    }
+
+    async fn delete_item(
+        &self,
+        table: &str,
+        id: &str,
+        condition: Option<Condition>
+    ) -> Result<(), StorageError> {
+        self.inner.delete_item(table, id, condition).await
+    }
+
+    async fn scan(
+        &self,
+        table: &str,
+        filters: &[Filter],
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        self.inner.scan(table, filters, page).await
+    }
+
+    async fn query(
+        &self,
+        table: &str,
+        key_condition: &KeyCondition,
+        filters: &[Filter],
+        descending: bool,
+        page: PageRequest
+    ) -> Result<ItemPage, StorageError> {
+        self.inner.query(table, key_condition, filters, descending, page).await
+    }
+}
+
+async fn create_employer(schema: &GraphQLSchema, name: &str) -> String {
+    let query = format!(
+        r#"mutation {{ createEmployer(name: "{name}", websiteUrl: "https://example.com", domain: "Example.COM") {{ id domain domainVerified }} }}"#
+    );
+    let response = execute(schema, &query, Some(site_admin())).await;
+    let employer = &response["data"]["createEmployer"];
+
+    assert_eq!(employer["domain"], "example.com");
+    assert_eq!(employer["domainVerified"], false);
+    employer["id"].as_str().unwrap().to_string()
+}
+
+async fn create_posting(schema: &GraphQLSchema, employer_id: &str) -> String {
+    let employer_id = format!(r#""{employer_id}""#);
+
+    common::create_posting(schema, site_admin(), &[("employerId", &employer_id)]).await
+}
+
+#[tokio::test]
+async fn postings_resolve_their_employer_in_one_batch() {
+    let backend = CountingBackend::default();
+    let schema = build_schema(Repository::with_backend(backend.clone()), Config::default());
+
+    let acme = create_employer(&schema, "Acme").await;
+    let globex = create_employer(&schema, "Globex").await;
+    for employer_id in [&acme, &globex, &acme] {
+        create_posting(&schema, employer_id).await;
+    }
+
+    backend.employer_gets.store(0, Ordering::SeqCst);
+
+    let response = execute(
+        &schema,
+        "{ jobPostings { employer { name } employerName employerUrl } }",
+        None
+    ).await;
+    let postings = response["data"]["jobPostings"].as_array().unwrap();
+
+    assert_eq!(postings.len(), 3);
+    assert!(postings.iter().all(|posting| posting["employerUrl"] == "https://example.com"));
+    assert_eq!(backend.employer_gets.load(Ordering::SeqCst), 0);
+    assert_eq!(backend.employer_batches.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn postings_filter_by_employer_name_and_id() {
+    let schema = schema();
+    let acme = create_employer(&schema, "Acme").await;
+    let globex = create_employer(&schema, "Globex").await;
+    let acme_posting = create_posting(&schema, &acme).await;
+    create_posting(&schema, &globex).await;
+
+    let by_name = execute(&schema, r#"{ jobPostings(filter: { employerName: "Acme" }) { id } }"#, None).await;
+    assert_eq!(by_name["data"]["jobPostings"][0]["id"], acme_posting.as_str());
+    assert_eq!(by_name["data"]["jobPostings"].as_array().unwrap().len(), 1);
+
+    let by_id = format!(r#"{{ jobPostings(filter: {{ employerId: "{globex}" }}) {{ employerId }} }}"#);
+    let by_id = execute(&schema, &by_id, None).await;
+    assert_eq!(by_id["data"]["jobPostings"][0]["employerId"], globex.as_str());
+
+    let unknown = execute(&schema, r#"{ jobPostings(filter: { employerName: "Initech" }) { id } }"#, None).await;
+    assert_eq!(unknown["data"]["jobPostings"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn members_post_only_for_their_own_employer() {
+    let schema = schema();
+    let acme = create_employer(&schema, "Acme").await;
+    let globex = create_employer(&schema, "Globex").await;
+
+    let globex = format!(r#""{globex}""#);
+    let mutation = create_posting_mutation(&[("employerId", &globex)], "id");
+    let response = execute(&schema, &mutation, Some(principal(Role::EmployerMember, Some(&acme)))).await;
+
+    assert_eq!(error_code(&response), "FORBIDDEN");
+}
+
+#[tokio::test]
+async fn employer_admins_edit_their_own_profile() {
+    let schema = schema();
+    let acme = create_employer(&schema, "Acme").await;
+    let mutation = format!(
+        r#"mutation {{ updateEmployer(id: "{acme}", patch: {{ description: "Welding since 1950", logoUrl: "https://example.com/logo.png" }}) {{ description logoUrl domain }} }}"#
+    );
+
+    let member = execute(&schema, &mutation, Some(principal(Role::EmployerMember, Some(&acme)))).await;
+    assert_eq!(error_code(&member), "FORBIDDEN");
+
+    let other = execute(&schema, &mutation, Some(principal(Role::EmployerAdmin, Some("employer-other")))).await;
+    assert_eq!(error_code(&other), "FORBIDDEN");
+
+    let admin = execute(&schema, &mutation, Some(principal(Role::EmployerAdmin, Some(&acme)))).await;
+    assert_eq!(admin["data"]["updateEmployer"]["description"], "Welding since 1950");
+    assert_eq!(admin["data"]["updateEmployer"]["domain"], "example.com");
+}
+
+#[tokio::test]
+async fn employers_with_postings_cannot_be_deleted() {
+    let schema = schema();
+    let acme = create_employer(&schema, "Acme").await;
+    let posting = create_posting(&schema, &acme).await;
+    let delete_employer = format!(r#"mutation {{ deleteEmployer(id: "{acme}") }}"#);
+
+    let blocked = execute(&schema, &delete_employer, Some(site_admin())).await;
+    assert_eq!(error_code(&blocked), "VALIDATION_ERROR");
+
+    let delete_posting = format!(r#"mutation {{ deleteJobPosting(id: "{posting}") }}"#);
+    execute(&schema, &delete_posting, Some(site_admin())).await;
+
+    let deleted = execute(&schema, &delete_employer, Some(site_admin())).await;
+    assert_eq!(deleted["data"]["deleteEmployer"], true);
+}
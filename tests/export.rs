@@ -0,0 +1,373 @@
+//! Bulk export of postings and applications through a parallel scan.
+
+mod common;
+
+use std::collections::HashSet;
+
+use axum::{ body::{ self, Body }, http::{ header, Request, StatusCode }, response::Response, Router };
+use chrono::{ DateTime, Duration, Utc };
+use job_board_lambda::{
+    auth::{ self, Principal },
+    build_schema,
+    config::Config,
+    csv,
+    export::{ Export, ExportFormat, ExportKind, ExportParams, JOB_POSTING_COLUMNS, SEGMENTS },
+    models::{
+        job_posting::{ ExpectedHoursRange, JobTypeOption, PostingStatus },
+        pay::CadenceOption,
+        user::Role,
+    },
+    repository::ItemQuery,
+    server,
+    Address,
+    JobApplication,
+    JobPosting,
+    Pay,
+    Repository,
+};
+use rust_decimal::Decimal;
+use serde_json::{ json, Value };
+use tower::ServiceExt;
+
+use common::{ at, principal, site_admin };
+
+fn posting(id: &str, employer_id: &str, created_at: DateTime<Utc>) -> JobPosting {
+    let mut job_posting = JobPosting::new(
+        id.to_string(),
+        format!("Job {}", id),
+        employer_id.to_string(),
+        Address::new(
+            "1 Main St".to_string(),
+            None,
+            "Marquette".to_string(),
+            "MI".to_string(),
+            "US".to_string(),
+            "49855".to_string()
+        ),
+        None,
+        JobTypeOption::FullTime.to_string(),
+        None,
+        "Work.".to_string(),
+        None,
+        None,
+        None,
+        ExpectedHoursRange::new(40, 40),
+        Default::default(),
+        Vec::new()
+    ).unwrap();
+
+    job_posting.status = PostingStatus::Published;
+    job_posting.created_at = created_at;
+    job_posting.updated_at = created_at;
+
+    job_posting
+}
+
+fn application(id: &str, job_posting_id: &str, created_at: DateTime<Utc>) -> JobApplication {
+    let mut application = JobApplication::new(
+        id.to_string(),
+        job_posting_id.to_string(),
+        "Sam Doe".to_string(),
+        "sam@example.com".to_string(),
+        None,
+        Some("Hello, \"team\"".to_string())
+    ).unwrap();
+
+    application.created_at = created_at;
+    application.updated_at = created_at;
+
+    application
+}
+
+async fn repository() -> Repository {
+    let repo = Repository::in_memory();
+
+    let mut welder = posting("welder", "employer-1", at("2026-03-01T10:00:00Z"));
+    welder.job_title = "Welder, \"Lead\"".to_string();
+    welder.job_description = "Weld pipe.\nDay shift.".to_string();
+    welder.address.unit = Some("Suite 4".to_string());
+    welder.pay = Some(
+        Pay::new(CadenceOption::Hour, Decimal::new(2450, 2), Some(Decimal::from(32)), "USD").unwrap()
+    );
+    welder.employee_responsibilities = Some(vec!["Read blueprints".to_string(), "Weld".to_string()]);
+    welder.extra_info = Some("=HYPERLINK(\"https://example.com\")".to_string());
+
+    for job_posting in [
+        welder,
+        posting("cook", "employer-1", at("2026-03-05T10:00:00Z")),
+        posting("nurse", "employer-2", at("2026-03-03T10:00:00Z")),
+    ] {
+        repo.create(job_posting).await.unwrap();
+    }
+
+    for application in [
+        application("application-1", "welder", at("2026-03-02T10:00:00Z")),
+        application("application-2", "nurse", at("2026-03-04T10:00:00Z")),
+        application("application-3", "cook", at("2026-03-06T10:00:00Z")),
+    ] {
+        repo.create(application).await.unwrap();
+    }
+
+    repo
+}
+
+async fn export(repo: &Repository, params: ExportParams) -> String {
+    let mut out = Vec::new();
+    Export::start(repo.clone(), params).unwrap().write_to(&mut out).await.unwrap();
+
+    String::from_utf8(out).unwrap()
+}
+
+/// The CSV rows as maps from column to value, sorted by id.
+fn csv_rows(data: &str) -> Vec<serde_json::Map<String, Value>> {
+    let mut records = csv::parse(data).unwrap().into_iter();
+    let header = records.next().unwrap();
+
+    let mut rows: Vec<_> = records
+        .map(|record| {
+            header
+                .iter()
+                .cloned()
+                .zip(record.into_iter().map(Value::String))
+                .collect::<serde_json::Map<_, _>>()
+        })
+        .collect();
+    rows.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    rows
+}
+
+fn ndjson_rows(data: &str) -> Vec<Value> {
+    let mut rows: Vec<Value> = data
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    rows.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    rows
+}
+
+#[tokio::test]
+async fn segments_cover_the_table_exactly_once() {
+    let repo = Repository::in_memory();
+    let start = at("2026-01-01T00:00:00Z");
+
+    for i in 0..250 {
+        repo.create(posting(&format!("job-{:03}", i), "employer-1", start + Duration::minutes(i))).await.unwrap();
+    }
+
+    let mut seen = HashSet::new();
+
+    for segment in 0..SEGMENTS {
+        let query = ItemQuery::scan().segment(segment, SEGMENTS);
+        let mut after = None;
+        let mut count = 0;
+
+        loop {
+            let page = repo.query::<JobPosting>(&query, 40, after).await.unwrap();
+            after = page.next_cursor.clone();
+
+            for job_posting in page.into_entities() {
+                count += 1;
+                assert!(seen.insert(job_posting.id), "read by two segments");
+            }
+
+            if after.is_none() {
+                break;
+            }
+        }
+
+        assert!(count > 0, "segment {} is empty", segment);
+    }
+
+    assert_eq!(seen.len(), 250);
+}
+
+#[tokio::test]
+async fn csv_flattens_nested_fields() {
+    let repo = repository().await;
+
+    let data = export(&repo, ExportParams::default()).await;
+
+    assert!(data.starts_with(&format!("{}\r\n", JOB_POSTING_COLUMNS.join(","))));
+
+    let rows = csv_rows(&data);
+    let ids: Vec<&str> = rows
+        .iter()
+        .map(|row| row["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["cook", "nurse", "welder"]);
+
+    let welder = &rows[2];
+    assert_eq!(welder["job_title"], "Welder, \"Lead\"");
+    assert_eq!(welder["job_description"], "Weld pipe.\nDay shift.");
+    assert_eq!(welder["address.unit"], "Suite 4");
+    assert_eq!(welder["address.city"], "Marquette");
+    assert_eq!(welder["pay.cadence"], "HOUR");
+    assert_eq!(welder["pay.min"], "24.5");
+    assert_eq!(welder["pay.max"], "32");
+    assert_eq!(welder["pay.currency"], "USD");
+    assert_eq!(welder["employee_responsibilities"], "Read blueprints|Weld");
+    assert_eq!(welder["work_arrangement.type"], "ON_SITE");
+    assert_eq!(welder["status"], "PUBLISHED");
+    assert_eq!(welder["created_at"], "2026-03-01T10:00:00.000000Z");
+    assert_eq!(welder["extra_info"], "'=HYPERLINK(\"https://example.com\")");
+
+    assert_eq!(rows[0]["pay.min"], "");
+    assert_eq!(rows[0]["address.unit"], "");
+}
+
+#[test]
+fn formula_escapes_read_back_as_written() {
+    for (field, escaped) in [
+        ("=1+1", "'=1+1"),
+        ("@SUM(A1)", "'@SUM(A1)"),
+        ("-05:00", "'-05:00"),
+        ("\tcell", "'\tcell"),
+        ("Welder", "Welder"),
+        ("'quoted", "'quoted"),
+    ] {
+        assert_eq!(csv::escape_formula(field.to_string()), escaped);
+        assert_eq!(csv::unescape_formula(escaped.to_string()), field);
+    }
+}
+
+#[tokio::test]
+async fn ndjson_nests_fields_in_objects() {
+    let repo = repository().await;
+
+    let data = export(&repo, ExportParams {
+        format: ExportFormat::Ndjson,
+        employer_id: Some("employer-1".to_string()),
+        ..ExportParams::default()
+    }).await;
+
+    let rows = ndjson_rows(&data);
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1]["id"], "welder");
+    assert_eq!(
+        rows[1]["address"],
+        json!({
+            "street": "1 Main St",
+            "unit": "Suite 4",
+            "city": "Marquette",
+            "state": "MI",
+            "country": "US",
+            "zip": "49855"
+        })
+    );
+    assert_eq!(rows[1]["pay"], json!({ "cadence": "HOUR", "min": 24.5, "max": 32, "currency": "USD" }));
+    assert_eq!(rows[1]["employee_responsibilities"], json!(["Read blueprints", "Weld"]));
+    assert_eq!(rows[1]["expected_hours"], json!({ "min": 40, "max": 40 }));
+    assert_eq!(rows[1]["held_for_moderation"], false);
+    assert_eq!(rows[1]["extra_info"], "=HYPERLINK(\"https://example.com\")");
+    assert!(rows[0].get("pay").is_none());
+}
+
+#[tokio::test]
+async fn filters_narrow_by_employer_and_creation_date() {
+    let repo = repository().await;
+
+    let data = export(&repo, ExportParams {
+        format: ExportFormat::Ndjson,
+        employer_id: Some("employer-1".to_string()),
+        from: Some(at("2026-03-01T10:00:00Z")),
+        to: Some(at("2026-03-05T10:00:00Z")),
+        ..ExportParams::default()
+    }).await;
+
+    let ids: Vec<Value> = ndjson_rows(&data)
+        .iter()
+        .map(|row| row["id"].clone())
+        .collect();
+    assert_eq!(ids, ["welder"]);
+
+    let invalid = Export::start(repo, ExportParams {
+        from: Some(at("2026-03-05T10:00:00Z")),
+        to: Some(at("2026-03-01T10:00:00Z")),
+        ..ExportParams::default()
+    });
+    assert!(invalid.is_err());
+}
+
+#[tokio::test]
+async fn applications_are_matched_to_employers_through_postings() {
+    let repo = repository().await;
+
+    let data = export(&repo, ExportParams {
+        kind: ExportKind::JobApplications,
+        employer_id: Some("employer-1".to_string()),
+        ..ExportParams::default()
+    }).await;
+
+    let rows = csv_rows(&data);
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["id"], "application-1");
+    assert_eq!(rows[0]["job_posting_id"], "welder");
+    assert_eq!(rows[0]["employer_id"], "employer-1");
+    assert_eq!(rows[0]["cover_note"], "Hello, \"team\"");
+    assert_eq!(rows[0]["status"], "SUBMITTED");
+    assert_eq!(rows[1]["id"], "application-3");
+
+    let data = export(&repo, ExportParams {
+        kind: ExportKind::JobApplications,
+        format: ExportFormat::Ndjson,
+        to: Some(at("2026-03-04T00:00:00Z")),
+        ..ExportParams::default()
+    }).await;
+
+    assert_eq!(ndjson_rows(&data).len(), 1);
+}
+
+async fn get(router: &Router, uri: &str, principal: Option<Principal>) -> Response {
+    let mut request = Request::get(uri);
+
+    if let Some(principal) = principal {
+        let token = auth::issue_token(&principal, &Config::default().auth).unwrap();
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+
+    router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+#[tokio::test]
+async fn route_is_for_site_admins_only() {
+    let repo = repository().await;
+    let schema = build_schema(repo.clone(), Config::default());
+    let router = server::build_router(schema, repo, &Config::default()).unwrap();
+
+    let response = get(&router, "/admin/export", None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = get(&router, "/admin/export", Some(principal(Role::EmployerAdmin, Some("employer-1")))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = get(
+        &router,
+        "/admin/export?from=2026-03-05T00:00:00Z&to=2026-03-01T00:00:00Z",
+        Some(site_admin())
+    ).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = get(
+        &router,
+        "/admin/export?kind=job_applications&format=ndjson&employer_id=employer-2",
+        Some(site_admin())
+    ).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+    assert_eq!(
+        response.headers()[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"job_applications.ndjson\""
+    );
+
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let rows = ndjson_rows(std::str::from_utf8(&body).unwrap());
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["id"], "application-2");
+    assert_eq!(rows[0]["employer_id"], "employer-2");
+}
@@ -0,0 +1,205 @@
+//! RSS and Atom feeds of the latest postings.
+
+mod common;
+
+use axum::{
+    body::{ self, Body },
+    http::{ header, Request, StatusCode },
+    response::Response,
+    Router,
+};
+use job_board_lambda::{
+    build_schema,
+    config::Config,
+    models::job_posting::{ ExpectedHoursRange, JobTypeOption, PostingStatus },
+    server,
+    Address,
+    Employer,
+    JobPosting,
+    Repository,
+};
+use tower::ServiceExt;
+
+use common::at;
+
+fn posting(id: &str, title: &str, city: &str, job_type: JobTypeOption, created_at: &str) -> JobPosting {
+    let mut job_posting = JobPosting::new(
+        id.to_string(),
+        title.to_string(),
+        "employer-1".to_string(),
+        Address::new(
+            "1 Main St".to_string(),
+            None,
+            city.to_string(),
+            "MI".to_string(),
+            "US".to_string(),
+            "49855".to_string()
+        ),
+        None,
+        job_type.to_string(),
+        None,
+        // Control characters are not allowed in XML and get dropped
+        format!("{} wanted & paid <well>.\u{1b}", title),
+        None,
+        None,
+        None,
+        ExpectedHoursRange::new(40, 40),
+        Default::default(),
+        Vec::new()
+    ).unwrap();
+
+    job_posting.status = PostingStatus::Published;
+    job_posting.created_at = at(created_at);
+    job_posting.updated_at = job_posting.created_at;
+
+    job_posting
+}
+
+async fn router() -> (Repository, Router) {
+    let repo = Repository::in_memory();
+
+    let employer = Employer::new(
+        "employer-1".to_string(),
+        "Lakeshore & Sons".to_string(),
+        None,
+        None,
+        None,
+        None
+    ).unwrap();
+    repo.create(employer).await.unwrap();
+
+    for job_posting in [
+        posting("welder", "Welder", "Marquette", JobTypeOption::FullTime, "2026-03-01T10:00:00Z"),
+        posting("cook", "Line Cook", "Ishpeming", JobTypeOption::PartTime, "2026-03-02T10:00:00Z"),
+        posting("driver", "Driver", "Marquette", JobTypeOption::PartTime, "2026-03-03T10:00:00Z"),
+        JobPosting {
+            employer_id: Some("employer-2".to_string()),
+            ..posting("nurse", "Nurse", "Marquette", JobTypeOption::FullTime, "2026-03-04T10:00:00Z")
+        },
+        JobPosting {
+            status: PostingStatus::Draft,
+            ..posting("draft", "Baker", "Marquette", JobTypeOption::FullTime, "2026-03-05T10:00:00Z")
+        },
+    ] {
+        repo.create(job_posting).await.unwrap();
+    }
+
+    let schema = build_schema(repo.clone(), Config::default());
+    let router = server::build_router(schema, repo.clone(), &Config::default()).unwrap();
+
+    (repo, router)
+}
+
+async fn get(router: &Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+    let mut request = Request::get(uri);
+
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+
+    router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+async fn text(response: Response) -> String {
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn guids(rss: &str) -> Vec<&str> {
+    rss.split("<guid isPermaLink=\"false\">")
+        .skip(1)
+        .map(|rest| rest.split('<').next().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn rss_lists_live_postings_newest_first() {
+    let (_, router) = router().await;
+
+    let response = get(&router, "/feeds/jobs.rss", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/rss+xml; charset=utf-8");
+    assert_eq!(response.headers()[header::LAST_MODIFIED], "Wed, 04 Mar 2026 10:00:00 GMT");
+
+    let rss = text(response).await;
+
+    assert!(rss.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">"));
+    assert_eq!(guids(&rss), ["nurse", "driver", "cook", "welder"]);
+    assert!(rss.contains("<title>Welder at Lakeshore &amp; Sons, Marquette, MI</title>"), "{}", rss);
+    assert!(rss.contains("<link>http://localhost:3000/jobs/welder</link>"));
+    assert!(rss.contains("<pubDate>Sun, 1 Mar 2026 10:00:00 +0000</pubDate>"), "{}", rss);
+    assert!(rss.contains("<description>Welder wanted &amp; paid &lt;well&gt;.</description>"));
+}
+
+#[tokio::test]
+async fn feeds_honor_filters() {
+    let (_, router) = router().await;
+
+    for (query, expected) in [
+        ("?job_type=PART_TIME", vec!["driver", "cook"]),
+        ("?city=Marquette", vec!["nurse", "driver", "welder"]),
+        ("?city=Marquette&job_type=FULL_TIME", vec!["nurse", "welder"]),
+        ("?employer_id=employer-1&limit=2", vec!["driver", "cook"]),
+        ("?employer_name=Lakeshore%20%26%20Sons&city=Marquette", vec!["driver", "welder"]),
+        ("?employer_name=Nobody", vec![]),
+    ] {
+        let rss = text(get(&router, &format!("/feeds/jobs.rss{}", query), &[]).await).await;
+        assert_eq!(guids(&rss), expected, "{}", query);
+    }
+
+    let response = get(&router, "/feeds/jobs.rss?job_type=REMOTE", &[]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn atom_feed_has_entries_with_ids_and_dates() {
+    let (_, router) = router().await;
+
+    let response = get(&router, "/feeds/jobs.atom?job_type=PART_TIME", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/atom+xml; charset=utf-8");
+
+    let atom = text(response).await;
+
+    assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+    assert!(atom.contains("<id>http://localhost:3000/feeds/jobs.atom?job_type=PART_TIME</id>"));
+    assert!(atom.contains("<updated>2026-03-03T10:00:00Z</updated>"));
+    assert!(atom.contains("<id>http://localhost:3000/jobs/driver</id>"));
+    assert!(atom.contains("<author><name>Lakeshore &amp; Sons</name></author>"));
+    assert_eq!(atom.matches("<entry>").count(), 2);
+}
+
+#[tokio::test]
+async fn conditional_requests_are_answered_with_not_modified() {
+    let (repo, router) = router().await;
+
+    let response = get(&router, "/feeds/jobs.rss", &[]).await;
+    let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+    let last_modified = response.headers()[header::LAST_MODIFIED].to_str().unwrap().to_string();
+
+    let response = get(&router, "/feeds/jobs.rss", &[(header::IF_NONE_MATCH, &etag)]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+    let response = get(&router, "/feeds/jobs.rss", &[(header::IF_MODIFIED_SINCE, &last_modified)]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // The Atom rendering of the same postings is a different representation
+    let response = get(&router, "/feeds/jobs.atom", &[(header::IF_NONE_MATCH, &etag)]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut welder = repo.get::<JobPosting>("welder".to_string()).await.unwrap().unwrap();
+    welder.updated_at = at("2026-03-10T12:00:00Z");
+    repo.update(welder).await.unwrap();
+
+    let response = get(&router, "/feeds/jobs.rss", &[(header::IF_NONE_MATCH, &etag)]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag.as_str());
+    assert_eq!(response.headers()[header::LAST_MODIFIED], "Tue, 10 Mar 2026 12:00:00 GMT");
+
+    let response = get(&router, "/feeds/jobs.rss", &[(header::IF_MODIFIED_SINCE, &last_modified)]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
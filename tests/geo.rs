@@ -0,0 +1,208 @@
+//! Locating postings and finding the ones near a point.
+
+mod common;
+
+use job_board_lambda::{
+    build_schema,
+    config::Config,
+    geo::{ self, geohash, TableGeocoder },
+    models::coordinates::Coordinates,
+    GraphQLSchema,
+    Repository,
+};
+use serde_json::{ json, Value };
+
+use common::{ create_posting_mutation, employer, error_code, execute };
+
+fn schema() -> GraphQLSchema {
+    let mut config = Config::default();
+    config.geocoding.table_path = Some(
+        format!("{}/tests/fixtures/geo/geocoding.csv", env!("CARGO_MANIFEST_DIR"))
+    );
+
+    build_schema(Repository::in_memory(), config)
+}
+
+async fn create_posting(
+    schema: &GraphQLSchema,
+    title: &str,
+    address: &str,
+    arguments: &[(&str, &str)]
+) -> Value {
+    let title = json!(title).to_string();
+    let mut arguments = arguments.to_vec();
+    arguments.extend([("jobTitle", title.as_str()), ("address", address)]);
+
+    let mutation = create_posting_mutation(&arguments, "id coordinates { latitude longitude }");
+
+    execute(schema, &mutation, Some(employer())).await["data"]["createJobPosting"].clone()
+}
+
+fn address(city: &str, zip: &str) -> String {
+    format!(r#"{{ street: "1 Main St", city: "{city}", state: "MI", country: "US", zip: "{zip}" }}"#)
+}
+
+/// Titles and distances, rounded to the kilometre, of the postings near a point.
+async fn near(schema: &GraphQLSchema, lat: f64, lng: f64, radius_km: f64) -> Value {
+    let query = format!(
+        r#"{{ jobPostingsNear(lat: {lat}, lng: {lng}, radiusKm: {radius_km}) {{ distanceKm jobPosting {{ jobTitle }} }} }}"#
+    );
+    let response = execute(schema, &query, None).await;
+
+    if response["errors"].is_array() {
+        return error_code(&response).clone();
+    }
+
+    response["data"]["jobPostingsNear"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| {
+            json!([
+                result["jobPosting"]["jobTitle"],
+                result["distanceKm"].as_f64().unwrap().round() as i64,
+            ])
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn postings_are_located_from_their_address() {
+    let schema = schema();
+
+    let by_zip = create_posting(&schema, "Welder", &address("Marquette", "49855-1234"), &[]).await;
+    assert_eq!(by_zip["coordinates"], json!({ "latitude": 46.5436, "longitude": -87.3954 }));
+
+    let by_city = create_posting(&schema, "Miner", &address("houghton", "49931"), &[]).await;
+    assert_eq!(by_city["coordinates"], json!({ "latitude": 47.1211, "longitude": -88.5694 }));
+
+    let unknown = create_posting(&schema, "Clerk", &address("Nowhere", "00000"), &[]).await;
+    assert_eq!(unknown["coordinates"], Value::Null);
+
+    let supplied = create_posting(
+        &schema,
+        "Ranger",
+        &address("Nowhere", "00000"),
+        &[("coordinates", "{ latitude: 46.9, longitude: -86.5 }")]
+    ).await;
+    assert_eq!(supplied["coordinates"], json!({ "latitude": 46.9, "longitude": -86.5 }));
+}
+
+#[tokio::test]
+async fn near_search_refines_by_distance_and_sorts_nearest_first() {
+    let schema = schema();
+
+    create_posting(&schema, "Ishpeming", &address("Ishpeming", "49849"), &[]).await;
+    create_posting(&schema, "Marquette", &address("Marquette", "49855"), &[]).await;
+    create_posting(&schema, "Negaunee", &address("Negaunee", "49866"), &[]).await;
+    create_posting(&schema, "Escanaba", &address("Escanaba", "49829"), &[]).await;
+    create_posting(&schema, "Unlocated", &address("Nowhere", "00000"), &[]).await;
+    create_posting(&schema, "Draft", &address("Marquette", "49855"), &[("draft", "true")]).await;
+
+    // 25 miles around Marquette
+    assert_eq!(
+        near(&schema, 46.5436, -87.3954, 40.2).await,
+        json!([["Marquette", 0], ["Negaunee", 17], ["Ishpeming", 22]])
+    );
+
+    assert_eq!(
+        near(&schema, 46.5436, -87.3954, 100.0).await,
+        json!([["Marquette", 0], ["Negaunee", 17], ["Ishpeming", 22], ["Escanaba", 92]])
+    );
+
+    assert_eq!(near(&schema, 46.5436, -87.3954, 10.0).await, json!([["Marquette", 0]]));
+}
+
+#[tokio::test]
+async fn near_search_covers_neighbouring_geohash_cells() {
+    let schema = schema();
+    let nowhere = address("Nowhere", "00000");
+
+    // Either side of the prime meridian at Greenwich, and of the antimeridian
+    // in Fiji; the hashes of each pair share no prefix at all
+    for (title, latitude, longitude) in [
+        ("West of Greenwich", 51.4779, -0.002),
+        ("East of Greenwich", 51.4779, 0.002),
+        ("West of the antimeridian", -16.8, 179.99),
+        ("East of the antimeridian", -16.8, -179.99),
+    ] {
+        let coordinates = format!("{{ latitude: {latitude}, longitude: {longitude} }}");
+        create_posting(&schema, title, &nowhere, &[("coordinates", &coordinates)]).await;
+    }
+
+    let west = geohash::encode(Coordinates::new(51.4779, -0.002).unwrap(), 9);
+    let east = geohash::encode(Coordinates::new(51.4779, 0.002).unwrap(), 9);
+    assert_ne!(west[..1], east[..1]);
+
+    assert_eq!(
+        near(&schema, 51.4779, -0.001, 1.0).await,
+        json!([["West of Greenwich", 0], ["East of Greenwich", 0]])
+    );
+    assert_eq!(
+        near(&schema, -16.8, 179.999, 5.0).await,
+        json!([["West of the antimeridian", 1], ["East of the antimeridian", 1]])
+    );
+}
+
+#[tokio::test]
+async fn changing_the_address_locates_the_posting_again() {
+    let schema = schema();
+
+    let posting = create_posting(&schema, "Welder", &address("Marquette", "49855"), &[]).await;
+    let id = posting["id"].as_str().unwrap();
+
+    let update = format!(
+        r#"mutation {{ updateJobPosting(id: "{id}", patch: {{ address: {} }}) {{ coordinates {{ latitude longitude }} }} }}"#,
+        address("Escanaba", "49829")
+    );
+    let response = execute(&schema, &update, Some(employer())).await;
+    assert_eq!(
+        response["data"]["updateJobPosting"]["coordinates"],
+        json!({ "latitude": 45.7453, "longitude": -87.0646 })
+    );
+
+    assert_eq!(near(&schema, 46.5436, -87.3954, 40.0).await, json!([]));
+    assert_eq!(near(&schema, 45.7453, -87.0646, 40.0).await, json!([["Welder", 0]]));
+
+    let clear = format!(
+        r#"mutation {{ updateJobPosting(id: "{id}", patch: {{ coordinates: null }}) {{ coordinates {{ latitude }} }} }}"#
+    );
+    let response = execute(&schema, &clear, Some(employer())).await;
+    assert_eq!(response["data"]["updateJobPosting"]["coordinates"], Value::Null);
+    assert_eq!(near(&schema, 45.7453, -87.0646, 40.0).await, json!([]));
+}
+
+#[tokio::test]
+async fn invalid_searches_and_coordinates_are_rejected() {
+    let schema = schema();
+
+    assert_eq!(near(&schema, 46.5, -87.4, 0.0).await, json!("VALIDATION_ERROR"));
+    assert_eq!(near(&schema, 46.5, -87.4, 600.0).await, json!("VALIDATION_ERROR"));
+    assert_eq!(near(&schema, 91.0, -87.4, 10.0).await, json!("VALIDATION_ERROR"));
+
+    let mutation = create_posting_mutation(
+        &[("coordinates", "{ latitude: 10, longitude: 181 }")],
+        "id"
+    );
+    let response = execute(&schema, &mutation, Some(employer())).await;
+    assert_eq!(error_code(&response), "VALIDATION_ERROR");
+}
+
+#[test]
+fn geohash_and_distance_match_reference_values() {
+    let point = Coordinates::new(57.64911, 10.40744).unwrap();
+    assert_eq!(geohash::encode(point, 11), "u4pruydqqvj");
+
+    let london = Coordinates::new(51.5074, -0.1278).unwrap();
+    let paris = Coordinates::new(48.8566, 2.3522).unwrap();
+    assert_eq!(geo::distance_km(london, paris).round(), 344.0);
+    assert_eq!(geo::distance_km(paris, paris), 0.0);
+}
+
+#[test]
+fn geocoding_tables_are_validated() {
+    assert!(TableGeocoder::from_csv("US,49855,Marquette,MI,46.5").is_err());
+    assert!(TableGeocoder::from_csv("US,49855,Marquette,MI,north,-87.4").is_err());
+    assert!(TableGeocoder::from_csv("US,49855,Marquette,MI,95.0,-87.4").is_err());
+    assert!(TableGeocoder::from_csv("# comment\n\nUS,49855,,,46.5,-87.4").is_ok());
+}
@@ -0,0 +1,351 @@
+//! Bulk import of job postings from CSV and NDJSON.
+
+mod common;
+
+use async_graphql::{ Request, Variables };
+use chrono::Utc;
+use job_board_lambda::{
+    auth::Principal,
+    build_schema,
+    config::Config,
+    csv,
+    geo::TableGeocoder,
+    import::{ ImportFormat, ImportReport, ImportRowError, Importer },
+    models::{
+        job_posting::{ JobTypeOption, PostingStatus },
+        pay::CadenceOption,
+        work_arrangement::WorkArrangementType,
+    },
+    repository::ItemQuery,
+    Employer,
+    JobPosting,
+    Repository,
+};
+use rust_decimal::Decimal;
+use serde_json::{ json, Value };
+
+use common::{ employer, error_code, site_admin };
+
+const HEADER: &str =
+    "job_title,address.street,address.city,address.state,address.country,address.zip,job_type,job_description,expected_hours.min,expected_hours.max";
+
+async fn repository() -> Repository {
+    let repo = Repository::in_memory();
+
+    for (id, name) in [("employer-1", "Lakeshore"), ("employer-2", "Harbor")] {
+        repo.create(
+            Employer::new(id.to_string(), name.to_string(), None, None, None, None).unwrap()
+        ).await.unwrap();
+    }
+
+    repo
+}
+
+async fn import(
+    repo: &Repository,
+    format: ImportFormat,
+    data: &str,
+    dry_run: bool
+) -> ImportReport {
+    let config = Config::default();
+    let geocoder = TableGeocoder::new();
+    let principal = employer();
+
+    Importer::new(repo, &config, &geocoder, &principal)
+        .import(format, data, None, dry_run, Utc::now()).await
+        .unwrap()
+}
+
+async fn job_postings(repo: &Repository) -> Vec<JobPosting> {
+    let mut job_postings = repo
+        .query::<JobPosting>(&ItemQuery::scan(), 100, None).await
+        .unwrap()
+        .into_entities();
+    job_postings.sort_by(|a, b| a.job_title.cmp(&b.job_title));
+
+    job_postings
+}
+
+fn row_error(row: usize, field: Option<&str>, message: &str) -> ImportRowError {
+    ImportRowError {
+        row,
+        field: field.map(str::to_string),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn csv_handles_quotes_line_breaks_and_blank_lines() {
+    let data = "\u{feff}a,b,c\r\n\"x, y\",\"say \"\"hi\"\"\",\"two\nlines\"\r\n\r\n1,,\"\"\n";
+
+    assert_eq!(csv::parse(data).unwrap(), [
+        vec!["a", "b", "c"],
+        vec!["x, y", "say \"hi\"", "two\nlines"],
+        vec!["1", "", ""],
+    ]);
+
+    for invalid in ["a,\"b\nc", "a,b\"c\"", "\"a\"b,c"] {
+        assert!(csv::parse(invalid).is_err(), "{:?}", invalid);
+    }
+}
+
+#[tokio::test]
+async fn csv_rows_become_published_postings() {
+    let repo = repository().await;
+    let data = format!(
+        "{},pay.cadence,pay.min,pay.max,employee_responsibilities,draft\n\
+         Welder,1 Main St,Marquette,MI,US,49855,full_time,\"Weld pipe, mostly.\nDay shift.\",36,44,hour,24.50,32,Read blueprints | Weld,\n\
+         Baker,2 Front St,Ishpeming,MI,US,49849,PART_TIME,Bake bread,20,25,,,,,TRUE\n",
+        HEADER
+    );
+
+    let report = import(&repo, ImportFormat::Csv, &data, false).await;
+
+    assert_eq!(report, ImportReport {
+        rows: 2,
+        valid_rows: 2,
+        imported: 2,
+        dry_run: false,
+        errors: Vec::new(),
+    });
+
+    let job_postings = job_postings(&repo).await;
+    let (baker, welder) = (&job_postings[0], &job_postings[1]);
+
+    assert_eq!(welder.employer_id.as_deref(), Some("employer-1"));
+    assert_eq!(welder.job_type, JobTypeOption::FullTime);
+    assert_eq!(welder.job_description, "Weld pipe, mostly.\nDay shift.");
+    assert_eq!(welder.address.city, "Marquette");
+    assert_eq!(
+        welder.employee_responsibilities,
+        Some(vec!["Read blueprints".to_string(), "Weld".to_string()])
+    );
+    assert_eq!(welder.status, PostingStatus::Published);
+    assert!(welder.expires_at.is_some());
+
+    let pay = welder.pay.as_ref().unwrap();
+    assert_eq!(pay.cadence, CadenceOption::Hour);
+    assert_eq!(pay.min, Decimal::new(2450, 2));
+    assert_eq!(pay.max, Some(Decimal::from(32)));
+    assert_eq!(pay.currency, "USD");
+
+    assert_eq!(baker.status, PostingStatus::Draft);
+    assert!(baker.pay.is_none());
+}
+
+#[tokio::test]
+async fn ndjson_accepts_nested_objects_and_lists() {
+    let repo = repository().await;
+    let row = json!({
+        "job_title": "CAD Drafter",
+        "address": {
+            "street": "1 Main St",
+            "city": "Marquette",
+            "state": "MI",
+            "country": "US",
+            "zip": "49855"
+        },
+        "coordinates": { "latitude": 46.5436, "longitude": -87.3954 },
+        "pay": { "cadence": "YEAR", "min": 70000, "currency": "eur" },
+        "job_type": "CONTRACT",
+        "job_description": "Draft fixtures.",
+        "experience_requirements": ["AutoCAD", "GD&T"],
+        "expected_hours": { "min": 40, "max": 40 },
+        "work_arrangement": {
+            "type": "REMOTE",
+            "countries": ["de", "NL"],
+            "utc_offsets": { "earliest": "+00:00", "latest": "+02:00" }
+        },
+        "extra_info": null
+    });
+
+    let report = import(&repo, ImportFormat::Ndjson, &format!("\n{}\n\n", row), false).await;
+
+    assert_eq!(report.imported, 1, "{:?}", report.errors);
+
+    let drafter = &job_postings(&repo).await[0];
+
+    assert_eq!(drafter.coordinates.unwrap().latitude, 46.5436);
+    assert_eq!(drafter.pay.as_ref().unwrap().currency, "EUR");
+    assert_eq!(drafter.pay.as_ref().unwrap().max, None);
+    assert_eq!(
+        drafter.experience_requirements,
+        Some(vec!["AutoCAD".to_string(), "GD&T".to_string()])
+    );
+    assert_eq!(drafter.work_arrangement.arrangement_type, WorkArrangementType::Remote);
+    assert_eq!(drafter.work_arrangement.countries, ["DE", "NL"]);
+    assert_eq!(drafter.extra_info, None);
+}
+
+#[tokio::test]
+async fn dry_run_reports_every_row_error_and_writes_nothing() {
+    let repo = repository().await;
+    let data = format!(
+        "{},pay.cadence,pay.min,pay.currency,category_ids,bonus\n\
+         Welder,1 Main St,Marquette,MI,US,49855,FULL_TIME,Weld,36,44,HOUR,24,USD,,\n\
+         ,PO Box,Marquette,MI,US,,REMOTE,Weld,forty,44,HOUR,24,XYZ,trades,yes\n\
+         Baker,2 Front St,Ishpeming,MI,US,49849\n",
+        HEADER
+    );
+
+    let report = import(&repo, ImportFormat::Csv, &data, true).await;
+
+    assert_eq!(report.rows, 3);
+    assert_eq!(report.valid_rows, 1);
+    assert_eq!(report.imported, 0);
+    assert!(report.dry_run);
+    assert_eq!(report.errors, [
+        row_error(0, Some("bonus"), "Unknown column"),
+        row_error(2, Some("job_title"), "A value is required"),
+        row_error(2, Some("address"), "Street value invalid"),
+        row_error(2, Some("pay"), "Unknown ISO 4217 currency code: XYZ"),
+        row_error(2, Some("job_type"), "Unknown job type: REMOTE"),
+        row_error(2, Some("expected_hours.min"), "Invalid value: forty"),
+        row_error(2, Some("category_ids"), "Job category trades does not exist"),
+        row_error(3, None, "Expected 15 fields, found 6"),
+        row_error(3, Some("job_type"), "A value is required"),
+        row_error(3, Some("job_description"), "A value is required"),
+        row_error(3, Some("expected_hours.min"), "A value is required"),
+        row_error(3, Some("expected_hours.max"), "A value is required"),
+    ]);
+
+    assert!(job_postings(&repo).await.is_empty());
+}
+
+#[tokio::test]
+async fn one_bad_row_rejects_the_whole_import() {
+    let repo = repository().await;
+    let data = [
+        r#"{"job_title": "Welder", "address": {"street": "1 Main St", "city": "Marquette", "state": "MI", "country": "US", "zip": "49855"}, "job_type": "FULL_TIME", "job_description": "Weld", "expected_hours": {"min": 36, "max": 44}}"#,
+        r#"{"job_title": "Baker", "address": {"street": "2 Front St", "city": "Ishpeming", "state": "MI", "country": "US", "zip": "49849"}, "job_type": "FULL_TIME", "job_description": "Bake", "expected_hours": {"min": 20, "max": 25}, "draft": true, "publish_at": "2030-01-01T00:00:00Z", "salary": 10}"#,
+        "[1, 2]",
+        "{not json",
+    ].join("\n");
+
+    let report = import(&repo, ImportFormat::Ndjson, &data, false).await;
+
+    assert_eq!(report.valid_rows, 1);
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.errors[..3], [
+        row_error(2, Some("draft"), "Drafts are scheduled when they are published"),
+        row_error(2, Some("salary"), "Unknown column"),
+        row_error(3, None, "Expected a JSON object"),
+    ]);
+    assert_eq!(report.errors[3].row, 4);
+    assert!(report.errors[3].message.starts_with("Invalid JSON"));
+
+    assert!(job_postings(&repo).await.is_empty());
+}
+
+async fn execute(repo: &Repository, principal: Principal, variables: Value) -> Value {
+    let schema = build_schema(repo.clone(), Config::default());
+    let request = Request::new(
+        r#"mutation($format: ImportFormat!, $data: String!, $employerId: ID) {
+            importJobPostings(format: $format, data: $data, employerId: $employerId) {
+                rows imported errors { row field message }
+            }
+        }"#
+    )
+        .variables(Variables::from_json(variables))
+        .data(principal);
+
+    serde_json::to_value(schema.execute(request).await).unwrap()
+}
+
+fn ndjson_rows(count: usize, employer_id: Option<&str>) -> String {
+    (0..count)
+        .map(|i| {
+            let mut row = json!({
+                "job_title": format!("Job {:03}", i),
+                "address": { "street": "1 Main St", "city": "Marquette", "state": "MI", "country": "US", "zip": "49855" },
+                "job_type": "FULL_TIME",
+                "job_description": "Work.",
+                "expected_hours": { "min": 40, "max": 40 }
+            });
+
+            if let Some(employer_id) = employer_id {
+                row["employer_id"] = json!(employer_id);
+            }
+
+            row.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn mutation_writes_many_rows_for_the_callers_employer() {
+    let repo = repository().await;
+
+    let response = execute(
+        &repo,
+        employer(),
+        json!({ "format": "NDJSON", "data": ndjson_rows(60, None) })
+    ).await;
+
+    assert_eq!(response["data"]["importJobPostings"]["rows"], 60, "{}", response);
+    assert_eq!(response["data"]["importJobPostings"]["imported"], 60);
+
+    let job_postings = job_postings(&repo).await;
+
+    assert_eq!(job_postings.len(), 60);
+    assert!(
+        job_postings
+            .iter()
+            .all(|job_posting| job_posting.employer_id.as_deref() == Some("employer-1"))
+    );
+}
+
+#[tokio::test]
+async fn employer_ids_follow_the_create_rules() {
+    let repo = repository().await;
+
+    let response = execute(
+        &repo,
+        employer(),
+        json!({ "format": "NDJSON", "data": ndjson_rows(1, Some("employer-2")) })
+    ).await;
+
+    assert_eq!(
+        response["data"]["importJobPostings"]["errors"],
+        json!([
+            {
+                "row": 1,
+                "field": "employer_id",
+                "message": "Job postings can only be created for your own employer"
+            },
+        ])
+    );
+
+    let response = execute(
+        &repo,
+        site_admin(),
+        json!({ "format": "NDJSON", "data": ndjson_rows(2, None), "employerId": "employer-2" })
+    ).await;
+
+    assert_eq!(response["data"]["importJobPostings"]["imported"], 2, "{}", response);
+    assert!(
+        job_postings(&repo).await
+            .iter()
+            .all(|job_posting| job_posting.employer_id.as_deref() == Some("employer-2"))
+    );
+
+    let response = execute(
+        &repo,
+        site_admin(),
+        json!({ "format": "NDJSON", "data": ndjson_rows(1, None), "employerId": "employer-9" })
+    ).await;
+
+    assert_eq!(
+        response["data"]["importJobPostings"]["errors"][0]["message"],
+        "Employer employer-9 does not exist"
+    );
+
+    let response = execute(
+        &repo,
+        site_admin(),
+        json!({ "format": "CSV", "data": "job_title\n\"Welder\" Lead" })
+    ).await;
+
+    assert_eq!(error_code(&response), "VALIDATION_ERROR", "{}", response);
+}
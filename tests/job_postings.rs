@@ -0,0 +1,77 @@
+//! Looking up and listing job postings.
+
+mod common;
+
+use job_board_lambda::{ pagination::MAX_PAGE_SIZE, GraphQLSchema };
+use serde_json::{ json, Value };
+
+use common::{ create_posting, employer, error_code, execute, schema, site_admin };
+
+fn count(response: &Value) -> usize {
+    response["data"]["jobPostings"].as_array().unwrap().len()
+}
+
+#[tokio::test]
+async fn unknown_ids_resolve_to_null_unless_strict() {
+    let schema = schema();
+    let id = create_posting(&schema, employer(), &[]).await;
+
+    let lenient = execute(&schema, r#"{ jobPosting(id: "missing") { id } }"#, None).await;
+    assert_eq!(lenient["data"]["jobPosting"], json!(null));
+    assert!(lenient["errors"].is_null());
+
+    let strict = execute(&schema, r#"{ jobPosting(id: "missing", strict: true) { id } }"#, None).await;
+    assert_eq!(error_code(&strict), "NOT_FOUND");
+
+    let query = format!(r#"{{ jobPosting(id: "{id}", strict: true) {{ id }} }}"#);
+    let found = execute(&schema, &query, None).await;
+    assert_eq!(found["data"]["jobPosting"]["id"], id.as_str());
+}
+
+async fn create_category(schema: &GraphQLSchema, name: &str) -> String {
+    let mutation = format!(r#"mutation {{ createJobCategory(name: "{name}") {{ id }} }}"#);
+    let response = execute(schema, &mutation, Some(site_admin())).await;
+
+    response["data"]["createJobCategory"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn categories_keep_their_order_and_skip_deleted_ones() {
+    let schema = schema();
+    let trades = create_category(&schema, "Trades").await;
+    let welding = create_category(&schema, "Welding").await;
+    let retail = create_category(&schema, "Retail").await;
+
+    let category_ids = format!(r#"["{retail}", "{trades}", "{welding}"]"#);
+    let id = create_posting(&schema, employer(), &[("categoryIds", &category_ids)]).await;
+
+    let mutation = format!(r#"mutation {{ deleteJobCategory(id: "{trades}") }}"#);
+    execute(&schema, &mutation, Some(site_admin())).await;
+
+    let query = format!(r#"{{ jobPosting(id: "{id}") {{ categories {{ name }} }} }}"#);
+    let response = execute(&schema, &query, None).await;
+    assert_eq!(
+        response["data"]["jobPosting"]["categories"],
+        json!([{ "name": "Retail" }, { "name": "Welding" }])
+    );
+}
+
+#[tokio::test]
+async fn listing_limits_are_clamped_to_a_page() {
+    let schema = schema();
+    let total = (MAX_PAGE_SIZE as usize) + 1;
+
+    for _ in 0..total {
+        create_posting(&schema, employer(), &[]).await;
+    }
+
+    let list = |limit: &str| format!("{{ jobPostings{limit} {{ id }} }}");
+
+    assert_eq!(count(&execute(&schema, &list("(limit: 2)"), None).await), 2);
+    assert_eq!(count(&execute(&schema, &list("(limit: 0)"), None).await), 1);
+    assert_eq!(
+        count(&execute(&schema, &list("(limit: 2147483647)"), None).await),
+        MAX_PAGE_SIZE as usize
+    );
+    assert_eq!(count(&execute(&schema, &list(""), None).await), total);
+}
@@ -0,0 +1,221 @@
+//! schema.org JobPosting documents, checked against golden files.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite the golden files from the current
+//! output after an intended change.
+
+mod common;
+
+use std::fs;
+
+use axum::{ body::{ self, Body }, http::{ header, Request, StatusCode } };
+use job_board_lambda::{
+    build_schema,
+    config::Config,
+    json_ld,
+    models::{
+        coordinates::Coordinates,
+        job_posting::{ ExpectedHoursRange, JobTypeOption, PostingStatus },
+        pay::CadenceOption,
+        work_arrangement::{ WorkArrangement, WorkArrangementType },
+    },
+    server,
+    Address,
+    Employer,
+    JobPosting,
+    Pay,
+    Repository,
+};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use common::{ at, execute };
+
+fn employer() -> Employer {
+    let mut employer = Employer::new(
+        "employer-1".to_string(),
+        "Lakeshore Fabrication".to_string(),
+        None,
+        Some("https://lakeshore.example".to_string()),
+        Some("https://lakeshore.example/logo.png".to_string()),
+        None
+    ).unwrap();
+    employer.created_at = at("2026-01-05T09:00:00Z");
+    employer.updated_at = employer.created_at;
+
+    employer
+}
+
+fn welder() -> JobPosting {
+    JobPosting {
+        id: "posting-welder".to_string(),
+        job_title: "Pipe Welder".to_string(),
+        employer_id: Some("employer-1".to_string()),
+        address: Address::new(
+            "1 Main St".to_string(),
+            Some("Suite 4".to_string()),
+            "Marquette".to_string(),
+            "MI".to_string(),
+            "US".to_string(),
+            "49855".to_string()
+        ),
+        coordinates: Some(Coordinates::new(46.5436, -87.3954).unwrap()),
+        pay: Some(
+            Pay::new(
+                CadenceOption::Hour,
+                Decimal::new(2450, 2),
+                Some(Decimal::from(32)),
+                "usd"
+            ).unwrap()
+        ),
+        job_type: JobTypeOption::FullTime,
+        link_to_application: None,
+        job_description: "Weld steel pipe in our shop.\n\nDay shift, <no> weekends & overtime paid.".to_string(),
+        employee_responsibilities: Some(vec!["Read blueprints".to_string()]),
+        experience_requirements: Some(vec!["2+ years of \"TIG\" welding".to_string()]),
+        extra_info: Some("Boots provided.".to_string()),
+        expected_hours: ExpectedHoursRange::new(36, 44),
+        work_arrangement: WorkArrangement::default(),
+        category_ids: Vec::new(),
+        held_for_moderation: false,
+        status: PostingStatus::Published,
+        publish_at: Some(at("2026-03-02T14:30:00Z")),
+        expires_at: Some(at("2999-04-01T14:30:00Z")),
+        closed_at: None,
+        purge_at: None,
+        created_at: at("2026-03-01T10:00:00Z"),
+        updated_at: at("2026-03-02T14:30:00Z"),
+    }
+}
+
+fn remote_drafter() -> JobPosting {
+    JobPosting {
+        id: "posting-remote".to_string(),
+        job_title: "CAD Drafter".to_string(),
+        employer_id: None,
+        job_description: "Draft fixtures in CAD.".to_string(),
+        pay: Some(Pay::new(CadenceOption::Year, Decimal::from(70000), None, "EUR").unwrap()),
+        job_type: JobTypeOption::Seasonal,
+        link_to_application: Some("https://careers.example/cad".to_string()),
+        employee_responsibilities: None,
+        experience_requirements: Some(Vec::new()),
+        extra_info: None,
+        coordinates: None,
+        expected_hours: ExpectedHoursRange::new(40, 40),
+        work_arrangement: WorkArrangement {
+            arrangement_type: WorkArrangementType::Remote,
+            countries: vec!["DE".to_string(), "NL".to_string()],
+            utc_offsets: None,
+        },
+        publish_at: None,
+        expires_at: None,
+        created_at: at("2026-02-10T08:00:00Z"),
+        ..welder()
+    }
+}
+
+/// Compares `actual` with the golden file `name`, or rewrites the file when
+/// `UPDATE_GOLDEN` is set.
+fn assert_golden(name: &str, actual: &Value) {
+    let path = format!("{}/tests/fixtures/json_ld/{}", env!("CARGO_MANIFEST_DIR"), name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, serde_json::to_string_pretty(actual).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+    assert_eq!(
+        actual,
+        &expected,
+        "{} differs from the golden file:\n{}",
+        name,
+        serde_json::to_string_pretty(actual).unwrap()
+    );
+}
+
+#[test]
+fn on_site_posting_matches_golden_file() {
+    assert_golden("on_site.json", &json_ld::job_posting(&welder(), Some(&employer())));
+}
+
+#[test]
+fn remote_posting_without_employer_matches_golden_file() {
+    assert_golden("remote.json", &json_ld::job_posting(&remote_drafter(), None));
+}
+
+async fn seeded_repository() -> Repository {
+    let repo = Repository::in_memory();
+
+    repo.create(employer()).await.unwrap();
+    repo.create(welder()).await.unwrap();
+    repo.create(JobPosting {
+        id: "posting-draft".to_string(),
+        status: PostingStatus::Draft,
+        ..welder()
+    }).await.unwrap();
+
+    repo
+}
+
+#[tokio::test]
+async fn graphql_field_serves_the_document() {
+    let schema = build_schema(seeded_repository().await, Config::default());
+
+    let query = r#"{ jobPosting(id: "posting-welder") { jsonLd } }"#;
+    let response = execute(&schema, query, None).await;
+    let document = response["data"]["jobPosting"]["jsonLd"].as_str().unwrap();
+
+    assert_golden("on_site.json", &serde_json::from_str(document).unwrap());
+}
+
+#[tokio::test]
+async fn markup_in_posting_text_cannot_close_the_script_element() {
+    let repo = seeded_repository().await;
+    repo.put(JobPosting {
+        job_title: "Welder </script><script>alert(1)</script> & fitter".to_string(),
+        ..welder()
+    }).await.unwrap();
+
+    let schema = build_schema(repo.clone(), Config::default());
+    let query = r#"{ jobPosting(id: "posting-welder") { jsonLd } }"#;
+    let response = execute(&schema, query, None).await;
+    let field = response["data"]["jobPosting"]["jsonLd"].as_str().unwrap().to_string();
+
+    let router = server::build_router(schema, repo, &Config::default()).unwrap();
+    let request = Request::get("/jobs/posting-welder.jsonld").body(Body::empty()).unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let route = String::from_utf8(body.to_vec()).unwrap();
+
+    for document in [field, route] {
+        assert!(!document.contains(['<', '>', '&']), "{}", document);
+        assert!(document.contains("Welder \\u003c/script\\u003e"), "{}", document);
+
+        let document: Value = serde_json::from_str(&document).unwrap();
+        assert_eq!(document["title"], "Welder </script><script>alert(1)</script> & fitter");
+    }
+}
+
+#[tokio::test]
+async fn route_serves_public_postings_only() {
+    let repo = seeded_repository().await;
+    let schema = build_schema(repo.clone(), Config::default());
+    let router = server::build_router(schema, repo, &Config::default()).unwrap();
+
+    let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+    let response = router.clone().oneshot(get("/jobs/posting-welder.jsonld")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/ld+json");
+
+    let body = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_golden("on_site.json", &serde_json::from_slice(&body).unwrap());
+
+    for uri in ["/jobs/posting-draft.jsonld", "/jobs/unknown.jsonld", "/jobs/posting-welder"] {
+        let response = router.clone().oneshot(get(uri)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
@@ -0,0 +1,131 @@
+//! Replays recorded API Gateway and Function URL events through the router.
+
+use std::fs;
+
+use axum::{ body::{ self, Body }, http::{ header, Method, Response } };
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use job_board_lambda::{
+    config::Config,
+    build_schema,
+    server::{ self, lambda::{ self, PayloadVersion } },
+    Repository,
+};
+use serde_json::Value;
+
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/lambda/{}", env!("CARGO_MANIFEST_DIR"), name);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn router() -> axum::Router {
+    let repository = Repository::in_memory();
+    let schema = build_schema(repository.clone(), Config::default());
+
+    server::build_router(schema, repository, &Config::default()).unwrap()
+}
+
+#[tokio::test]
+async fn translates_v1_rest_api_event() {
+    let (version, request) = lambda::request_from_event(fixture("apigw_v1_post_graphql.json")).unwrap();
+
+    assert_eq!(version, PayloadVersion::V1);
+    assert_eq!(request.method(), Method::POST);
+    assert_eq!(request.uri(), "/graphql");
+    assert_eq!(request.headers()[header::CONTENT_TYPE], "application/json");
+
+    let body = body::to_bytes(request.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], br#"{"query":"{ jobPostings { id } }"}"#);
+}
+
+#[tokio::test]
+async fn translates_v2_http_api_event() {
+    let (version, request) = lambda::request_from_event(fixture("apigw_v2_get_health.json")).unwrap();
+
+    assert_eq!(version, PayloadVersion::V2);
+    assert_eq!(request.method(), Method::GET);
+    assert_eq!(request.uri(), "/health?verbose=1&verbose=2");
+    assert_eq!(request.headers()[header::COOKIE], "session=abc; theme=dark");
+}
+
+#[tokio::test]
+async fn decodes_base64_function_url_body() {
+    let (version, request) = lambda::request_from_event(
+        fixture("function_url_post_graphql.json")
+    ).unwrap();
+
+    assert_eq!(version, PayloadVersion::V2);
+
+    let body = body::to_bytes(request.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], br#"{"query":"{ __typename }"}"#);
+}
+
+#[tokio::test]
+async fn rejects_unknown_events() {
+    assert!(lambda::request_from_event(serde_json::json!({ "Records": [] })).is_err());
+}
+
+#[tokio::test]
+async fn serves_v1_event_through_router() {
+    let response = lambda::handle_event(router(), fixture("apigw_v1_post_graphql.json")).await.unwrap();
+
+    assert_eq!(response["statusCode"], 200);
+    assert_eq!(response["isBase64Encoded"], false);
+    assert!(response["multiValueHeaders"]["content-type"].is_array());
+
+    let body: Value = serde_json::from_str(response["body"].as_str().unwrap()).unwrap();
+    assert_eq!(body["data"]["jobPostings"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn serves_v2_event_through_router() {
+    let response = lambda::handle_event(router(), fixture("apigw_v2_get_health.json")).await.unwrap();
+
+    assert_eq!(response["statusCode"], 200);
+    assert_eq!(response["body"], "OK");
+    assert!(response["headers"].is_object());
+}
+
+#[tokio::test]
+async fn base64_encodes_compressed_responses() {
+    let response = lambda::handle_event(
+        router(),
+        fixture("function_url_post_graphql.json")
+    ).await.unwrap();
+
+    assert_eq!(response["statusCode"], 200);
+    assert_eq!(response["headers"]["content-encoding"], "gzip");
+    assert_eq!(response["isBase64Encoded"], true);
+    assert!(STANDARD.decode(response["body"].as_str().unwrap()).is_ok());
+}
+
+#[tokio::test]
+async fn untranslatable_requests_get_a_bad_request_response() {
+    let mut event = fixture("apigw_v2_get_health.json");
+    event["headers"]["bad header"] = Value::from("x");
+
+    let response = lambda::handle_event(router(), event).await.unwrap();
+    assert_eq!(response["statusCode"], 400);
+
+    let mut event = fixture("apigw_v1_post_graphql.json");
+    event["path"] = Value::from("not a path");
+
+    let response = lambda::handle_event(router(), event).await.unwrap();
+    assert_eq!(response["statusCode"], 400);
+    assert!(response["multiValueHeaders"]["content-type"].is_array());
+}
+
+#[tokio::test]
+async fn oversized_responses_become_bad_gateway() {
+    let body = vec![b'a'; lambda::MAX_RESPONSE_BODY_BYTES + 1];
+    let response = Response::new(Body::from(body));
+    let response = lambda::event_from_response(PayloadVersion::V2, response).await;
+
+    assert_eq!(response["statusCode"], 502);
+    assert_eq!(response["headers"]["content-type"], "text/plain; charset=utf-8");
+
+    let body = vec![b'a'; lambda::MAX_RESPONSE_BODY_BYTES];
+    let response = Response::new(Body::from(body));
+    let response = lambda::event_from_response(PayloadVersion::V2, response).await;
+
+    assert_eq!(response["statusCode"], 200);
+}
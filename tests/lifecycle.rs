@@ -0,0 +1,222 @@
+//! Posting lifecycle: drafts, scheduling, closing, read-time expiry, the
+//! sweep and Time to Live purging, all against the in-memory backend.
+
+mod common;
+
+use chrono::{ DateTime, Duration, Utc };
+use job_board_lambda::{
+    config::LifecycleConfig,
+    db::job_posting_tables::PURGE_AT_ATTRIBUTE,
+    lifecycle::{ sweep_job_postings, SweepReport },
+    models::{ job_posting::PostingStatus, timestamp, user::Role },
+    DynamoDbEntity,
+    GraphQLSchema,
+    JobPosting,
+    Repository,
+    StorageBackend,
+};
+use serde_json::Value;
+
+use common::{
+    create_posting_mutation,
+    employer,
+    error_code,
+    execute,
+    principal,
+    setup,
+    site_admin,
+};
+
+async fn create_posting(schema: &GraphQLSchema, arguments: &[(&str, &str)]) -> Value {
+    let mutation = create_posting_mutation(arguments, "id status publishAt expiresAt");
+
+    execute(schema, &mutation, Some(employer())).await["data"]["createJobPosting"].clone()
+}
+
+async fn listed_ids(schema: &GraphQLSchema) -> Vec<String> {
+    let response = execute(schema, "{ jobPostings { id } }", None).await;
+
+    response["data"]["jobPostings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|posting| posting["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+async fn stored(repository: &Repository, id: &str) -> JobPosting {
+    repository.get::<JobPosting>(id.to_string()).await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn postings_are_published_with_the_default_lifetime() {
+    let (_, _, schema) = setup();
+
+    let posting = create_posting(&schema, &[]).await;
+    assert_eq!(posting["status"], "PUBLISHED");
+
+    let publish_at = posting["publishAt"].as_str().unwrap().parse::<DateTime<Utc>>().unwrap();
+    let expires_at = posting["expiresAt"].as_str().unwrap().parse::<DateTime<Utc>>().unwrap();
+    assert_eq!(expires_at - publish_at, Duration::days(30));
+
+    assert_eq!(listed_ids(&schema).await, vec![posting["id"].as_str().unwrap()]);
+}
+
+#[tokio::test]
+async fn drafts_are_listed_only_once_published() {
+    let (_, _, schema) = setup();
+
+    let draft = create_posting(&schema, &[("draft", "true")]).await;
+    let id = draft["id"].as_str().unwrap();
+    assert_eq!(draft["status"], "DRAFT");
+    assert_eq!(listed_ids(&schema).await, Vec::<String>::new());
+
+    let by_id = format!(r#"{{ jobPosting(id: "{id}") {{ status }} }}"#);
+    assert_eq!(execute(&schema, &by_id, None).await["data"]["jobPosting"], Value::Null);
+    assert_eq!(
+        execute(&schema, &by_id, Some(employer())).await["data"]["jobPosting"]["status"],
+        "DRAFT"
+    );
+
+    let publish = format!(r#"mutation {{ publishJobPosting(id: "{id}") {{ status }} }}"#);
+    let published = execute(&schema, &publish, Some(employer())).await;
+    assert_eq!(published["data"]["publishJobPosting"]["status"], "PUBLISHED");
+
+    assert_eq!(listed_ids(&schema).await, vec![id]);
+}
+
+#[tokio::test]
+async fn scheduled_postings_wait_for_publish_at() {
+    let (_, _, schema) = setup();
+    let publish_at = timestamp::format(&(Utc::now() + Duration::days(2)));
+
+    let publish_at = format!(r#""{publish_at}""#);
+
+    let posting = create_posting(&schema, &[("publishAt", &publish_at)]).await;
+    assert_eq!(posting["status"], "PUBLISHED");
+    assert_eq!(listed_ids(&schema).await, Vec::<String>::new());
+
+    let latest = execute(&schema, "{ latestJobPostings { edges { node { id } } } }", None).await;
+    assert_eq!(latest["data"]["latestJobPostings"]["edges"], serde_json::json!([]));
+
+    let expired = format!(r#""{}""#, timestamp::format(&(Utc::now() - Duration::hours(1))));
+    let mutation = create_posting_mutation(&[("expiresAt", &expired)], "id");
+    let rejected = execute(&schema, &mutation, Some(employer())).await;
+    assert_eq!(error_code(&rejected), "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn closed_postings_leave_the_listings_but_stay_reachable() {
+    let (_, _, schema) = setup();
+    let posting = create_posting(&schema, &[]).await;
+    let id = posting["id"].as_str().unwrap();
+
+    let close = format!(r#"mutation {{ closeJobPosting(id: "{id}") {{ status closedAt }} }}"#);
+    let closed = execute(&schema, &close, Some(employer())).await;
+    assert_eq!(closed["data"]["closeJobPosting"]["status"], "CLOSED");
+    assert!(closed["data"]["closeJobPosting"]["closedAt"].is_string());
+
+    assert_eq!(listed_ids(&schema).await, Vec::<String>::new());
+
+    let by_id = format!(r#"{{ jobPosting(id: "{id}") {{ status }} }}"#);
+    assert_eq!(execute(&schema, &by_id, None).await["data"]["jobPosting"]["status"], "CLOSED");
+
+    let apply = format!(
+        r#"mutation {{ applyToJob(jobPostingId: "{id}", applicantName: "Sam", applicantEmail: "sam@example.com") {{ id }} }}"#
+    );
+    assert_eq!(error_code(&execute(&schema, &apply, None).await), "VALIDATION_ERROR");
+
+    let again = execute(&schema, &close, Some(employer())).await;
+    assert_eq!(error_code(&again), "VALIDATION_ERROR");
+
+    let other = execute(&schema, &close, Some(principal(Role::EmployerMember, Some("employer-2")))).await;
+    assert_eq!(error_code(&other), "FORBIDDEN");
+}
+
+#[tokio::test]
+async fn expiry_is_enforced_before_the_sweep_runs() {
+    let (_, repository, schema) = setup();
+    let posting = create_posting(&schema, &[]).await;
+    let id = posting["id"].as_str().unwrap();
+
+    let mut job_posting = stored(&repository, id).await;
+    job_posting.expires_at = Some(Utc::now() - Duration::minutes(1));
+    repository.update(job_posting).await.unwrap();
+
+    assert_eq!(listed_ids(&schema).await, Vec::<String>::new());
+
+    let by_id = format!(r#"{{ jobPosting(id: "{id}") {{ status }} }}"#);
+    assert_eq!(execute(&schema, &by_id, None).await["data"]["jobPosting"]["status"], "EXPIRED");
+    assert_eq!(stored(&repository, id).await.status, PostingStatus::Published);
+}
+
+#[tokio::test]
+async fn sweep_expires_archives_and_schedules_purging() {
+    let (backend, repository, schema) = setup();
+    let config = LifecycleConfig::default();
+    let id = create_posting(&schema, &[]).await["id"].as_str().unwrap().to_string();
+
+    let report = sweep_job_postings(&repository, &config, Utc::now()).await.unwrap();
+    assert_eq!(report, SweepReport { scanned: 1, expired: 0, archived: 0 });
+
+    let after_expiry = Utc::now() + Duration::days(31);
+    let report = sweep_job_postings(&repository, &config, after_expiry).await.unwrap();
+    assert_eq!(report, SweepReport { scanned: 1, expired: 1, archived: 0 });
+    assert_eq!(stored(&repository, &id).await.status, PostingStatus::Expired);
+
+    let after_archive_delay = after_expiry + Duration::days(90);
+    let report = sweep_job_postings(&repository, &config, after_archive_delay).await.unwrap();
+    assert_eq!(report, SweepReport { scanned: 1, expired: 0, archived: 1 });
+
+    let archived = stored(&repository, &id).await;
+    assert_eq!(archived.status, PostingStatus::Archived);
+    assert!(archived.purge_at.is_some());
+
+    // Archived postings are no longer swept
+    let report = sweep_job_postings(&repository, &config, after_archive_delay).await.unwrap();
+    assert_eq!(report.scanned, 0);
+
+    // Time to Live removes the posting once its retention has passed
+    let table = JobPosting::table_name();
+    assert_eq!(backend.expire_items(table, PURGE_AT_ATTRIBUTE, after_archive_delay).unwrap(), 0);
+    let after_retention = after_archive_delay + Duration::days(366);
+    assert_eq!(backend.expire_items(table, PURGE_AT_ATTRIBUTE, after_retention).unwrap(), 1);
+    assert!(repository.get::<JobPosting>(id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn legacy_postings_are_listed_until_the_sweep_expires_them() {
+    let (backend, repository, schema) = setup();
+    let id = create_posting(&schema, &[]).await["id"].as_str().unwrap().to_string();
+
+    // Strip the lifecycle attributes, as on postings stored before they existed
+    let mut item = stored(&repository, &id).await.to_item();
+    for attribute in ["posting_status", "publish_at", "expires_at"] {
+        item.remove(attribute);
+    }
+    backend.put_item(JobPosting::table_name(), item).await.unwrap();
+
+    assert_eq!(listed_ids(&schema).await, vec![id.clone()]);
+
+    let config = LifecycleConfig::default();
+    sweep_job_postings(&repository, &config, Utc::now()).await.unwrap();
+
+    let job_posting = stored(&repository, &id).await;
+    assert_eq!(job_posting.status, PostingStatus::Published);
+    assert!(job_posting.expires_at.is_some());
+
+    sweep_job_postings(&repository, &config, Utc::now() + Duration::days(31)).await.unwrap();
+    assert_eq!(listed_ids(&schema).await, Vec::<String>::new());
+}
+
+#[tokio::test]
+async fn only_site_admins_trigger_the_sweep() {
+    let (_, _, schema) = setup();
+    create_posting(&schema, &[]).await;
+    let sweep = "mutation { sweepJobPostings { scanned expired archived } }";
+
+    assert_eq!(error_code(&execute(&schema, sweep, Some(employer())).await), "FORBIDDEN");
+
+    let report = execute(&schema, sweep, Some(site_admin())).await;
+    assert_eq!(report["data"]["sweepJobPostings"]["scanned"], 1);
+}
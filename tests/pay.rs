@@ -0,0 +1,194 @@
+//! Pay ranges, currencies and annualized pay filtering.
+
+mod common;
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use job_board_lambda::{
+    models::{ job_posting::ExpectedHoursRange, pay::CadenceOption },
+    DynamoDbEntity,
+    GraphQLSchema,
+    JobPosting,
+    Pay,
+    StorageBackend,
+};
+use rust_decimal::Decimal;
+use serde_json::{ json, Value };
+
+use common::{ create_posting_mutation, employer, error_code, execute, schema, setup };
+
+async fn create_posting(schema: &GraphQLSchema, title: &str, pay: &str, hours: (u8, u8)) -> Value {
+    let (min, max) = hours;
+    let title = json!(title).to_string();
+    let hours = format!("{{ min: {min}, max: {max} }}");
+    let mutation = create_posting_mutation(
+        &[("jobTitle", &title), ("expectedHours", &hours), ("pay", pay)],
+        "id pay { cadence min max currency } annualizedPay { min max currency }"
+    );
+
+    execute(schema, &mutation, Some(employer())).await
+}
+
+async fn titles_paying(schema: &GraphQLSchema, filter: &str) -> Vec<String> {
+    let query = format!(r#"{{ jobPostings(filter: {{ {filter} }}) {{ jobTitle }} }}"#);
+    let response = execute(schema, &query, None).await;
+
+    let mut titles: Vec<String> = response["data"]["jobPostings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|posting| posting["jobTitle"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[tokio::test]
+async fn hourly_pay_is_annualized_over_the_expected_hours() {
+    let schema = schema();
+
+    let response = create_posting(
+        &schema,
+        "Welder",
+        r#"{ cadence: HOUR, min: "20.50", max: "25", currency: "usd" }"#,
+        (30, 40)
+    ).await;
+    let posting = &response["data"]["createJobPosting"];
+
+    assert_eq!(
+        posting["pay"],
+        json!({ "cadence": "HOUR", "min": "20.50", "max": "25", "currency": "USD" })
+    );
+    assert_eq!(
+        posting["annualizedPay"],
+        json!({ "min": "31980", "max": "52000", "currency": "USD" })
+    );
+}
+
+#[tokio::test]
+async fn invalid_pay_is_rejected() {
+    let schema = schema();
+
+    for pay in [
+        r#"{ cadence: YEAR, min: "50000", currency: "DOLLARS" }"#,
+        r#"{ cadence: YEAR, min: "50000", max: "40000", currency: "USD" }"#,
+        r#"{ cadence: YEAR, min: "-1", currency: "USD" }"#,
+        r#"{ cadence: HOUR, min: "100000000000000000000000000", currency: "USD" }"#,
+        r#"{ cadence: HOUR, min: "20", max: "1000000000001", currency: "USD" }"#,
+    ] {
+        let response = create_posting(&schema, "Welder", pay, (40, 40)).await;
+        assert_eq!(error_code(&response), "VALIDATION_ERROR", "{}", pay);
+    }
+}
+
+#[tokio::test]
+async fn expected_hours_must_be_ordered_and_fit_in_a_week() {
+    let schema = schema();
+    let pay = r#"{ cadence: HOUR, min: "20", currency: "USD" }"#;
+
+    for hours in [(60, 10), (40, 169)] {
+        let response = create_posting(&schema, "Welder", pay, hours).await;
+        assert_eq!(error_code(&response), "VALIDATION_ERROR", "{:?}", hours);
+    }
+
+    let response = create_posting(&schema, "Welder", pay, (40, 40)).await;
+    let id = response["data"]["createJobPosting"]["id"].as_str().unwrap();
+
+    let query = format!(
+        r#"mutation {{ updateJobPosting(id: "{id}", patch: {{ expectedHours: {{ min: 50 }} }}) {{ id }} }}"#
+    );
+    let response = execute(&schema, &query, Some(employer())).await;
+    assert_eq!(error_code(&response), "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn min_annual_pay_compares_across_cadences_within_a_currency() {
+    let schema = schema();
+
+    create_posting(
+        &schema,
+        "Hourly",
+        r#"{ cadence: HOUR, min: "20", currency: "USD" }"#,
+        (30, 40)
+    ).await;
+    create_posting(
+        &schema,
+        "Salaried",
+        r#"{ cadence: YEAR, min: "60000", max: "80000", currency: "USD" }"#,
+        (40, 40)
+    ).await;
+    create_posting(
+        &schema,
+        "Monthly",
+        r#"{ cadence: MONTH, min: "3000", currency: "EUR" }"#,
+        (40, 40)
+    ).await;
+    common::create_posting(
+        &schema,
+        employer(),
+        &[("jobTitle", r#""Unpaid""#), ("expectedHours", "{ min: 10, max: 10 }")]
+    ).await;
+
+    assert_eq!(
+        titles_paying(&schema, r#"minAnnualPay: "40000""#).await,
+        vec!["Hourly", "Salaried"]
+    );
+    assert_eq!(titles_paying(&schema, r#"minAnnualPay: "50000""#).await, vec!["Salaried"]);
+    assert_eq!(
+        titles_paying(&schema, r#"minAnnualPay: "30000", payCurrency: "eur""#).await,
+        vec!["Monthly"]
+    );
+    assert_eq!(
+        titles_paying(&schema, r#"minAnnualPay: "50000", jobType: FULL_TIME"#).await,
+        vec!["Salaried"]
+    );
+}
+
+#[tokio::test]
+async fn legacy_whole_number_pay_reads_as_us_dollars() {
+    let (backend, repository, schema) = setup();
+
+    let response = create_posting(
+        &schema,
+        "Welder",
+        r#"{ cadence: WEEK, min: "900", currency: "USD" }"#,
+        (40, 40)
+    ).await;
+    let id = response["data"]["createJobPosting"]["id"].as_str().unwrap().to_string();
+
+    let mut item = repository.get::<JobPosting>(id.clone()).await.unwrap().unwrap().to_item();
+    item.insert(
+        "pay".to_string(),
+        AttributeValue::M(
+            HashMap::from([
+                ("cadence".to_string(), AttributeValue::S("WEEK".to_string())),
+                ("min_base_pay".to_string(), AttributeValue::S("850".to_string())),
+            ])
+        )
+    );
+    backend.put_item(JobPosting::table_name(), item).await.unwrap();
+
+    let query = format!(
+        r#"{{ jobPosting(id: "{id}") {{ pay {{ min currency minBasePay }} annualizedPay {{ min }} }} }}"#
+    );
+    let posting = execute(&schema, &query, None).await;
+
+    assert_eq!(
+        posting["data"]["jobPosting"],
+        json!({
+            "pay": { "min": "850", "currency": "USD", "minBasePay": 850 },
+            "annualizedPay": { "min": "44200" }
+        })
+    );
+}
+
+#[test]
+fn daily_pay_assumes_eight_hour_days() {
+    let pay = Pay::new(CadenceOption::Day, Decimal::from(200), None, "cad").unwrap();
+    let annualized = pay.annualized(&ExpectedHoursRange::new(20, 40)).unwrap();
+
+    assert_eq!(annualized.min, Decimal::from(26000));
+    assert_eq!(annualized.max, Decimal::from(52000));
+    assert_eq!(annualized.currency, "CAD");
+}
@@ -0,0 +1,226 @@
+//! Keyword search through the inverted index.
+
+mod common;
+
+use job_board_lambda::{ search::{ self, analyzer }, GraphQLSchema, JobPosting, StorageBackend };
+use serde_json::{ json, Value };
+
+use common::{ employer, error_code, execute, schema, setup };
+
+async fn create_posting(
+    schema: &GraphQLSchema,
+    title: &str,
+    description: &str,
+    requirements: &[&str],
+    arguments: &[(&str, &str)]
+) -> String {
+    let title = json!(title).to_string();
+    let description = json!(description).to_string();
+    let requirements = json!(requirements).to_string();
+    let mut arguments = arguments.to_vec();
+    arguments.extend([
+        ("jobTitle", title.as_str()),
+        ("jobDescription", description.as_str()),
+        ("experienceRequirements", requirements.as_str()),
+    ]);
+
+    common::create_posting(schema, employer(), &arguments).await
+}
+
+async fn search(schema: &GraphQLSchema, query: &str) -> Value {
+    let query = format!(
+        r#"{{ searchJobPostings(query: {}) {{ score highlights {{ field snippet }} jobPosting {{ jobTitle }} }} }}"#,
+        json!(query)
+    );
+
+    execute(schema, &query, None).await["data"]["searchJobPostings"].clone()
+}
+
+async fn search_titles(schema: &GraphQLSchema, query: &str) -> Value {
+    search(schema, query).await
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["jobPosting"]["jobTitle"].clone())
+        .collect()
+}
+
+#[test]
+fn words_are_stemmed_and_stop_words_dropped() {
+    for (word, stem) in [
+        ("caresses", "caress"),
+        ("ponies", "poni"),
+        ("relational", "relat"),
+        ("hopping", "hop"),
+        ("generalizations", "gener"),
+        ("running", "run"),
+        ("welding", "weld"),
+        ("welders", "welder"),
+        ("electrical", "electr"),
+    ] {
+        assert_eq!(analyzer::stem(word), stem, "{}", word);
+    }
+
+    assert_eq!(
+        analyzer::terms("The Welders, and the WELDING of pipes!"),
+        vec!["welder", "weld", "pipe"]
+    );
+    assert_eq!(analyzer::terms("Café crème"), vec!["café", "crème"]);
+}
+
+#[test]
+fn welding_words_meet_on_one_stem() {
+    assert_eq!(analyzer::terms("Welding welds weld welded"), vec!["weld"; 4]);
+
+    // Porter only strips -er from stems of measure two or more
+    assert_eq!(analyzer::terms("welder welders"), vec!["welder"; 2]);
+}
+
+#[tokio::test]
+async fn searches_match_other_forms_of_a_word() {
+    let schema = schema();
+    create_posting(&schema, "Pipe fitter", "Welds pipe joints.", &[], &[]).await;
+    create_posting(&schema, "Welder", "Fabricates frames.", &[], &[]).await;
+
+    assert_eq!(search_titles(&schema, "welding").await, json!(["Pipe fitter"]));
+    assert_eq!(search_titles(&schema, "welders").await, json!(["Welder"]));
+}
+
+#[tokio::test]
+async fn results_are_ranked_and_highlighted() {
+    let schema = schema();
+
+    create_posting(
+        &schema,
+        "Pipe Welder",
+        "Weld steel pipe in our fabrication shop. You will weld daily.",
+        &["Certified welding experience"],
+        &[]
+    ).await;
+    create_posting(
+        &schema,
+        "Shop Assistant",
+        "Help around the shop; occasional welding <basic> & grinding.",
+        &[],
+        &[]
+    ).await;
+    create_posting(&schema, "Accountant", "Keep the books balanced.", &[], &[]).await;
+
+    let hits = search(&schema, "welding").await;
+
+    assert_eq!(hits.as_array().unwrap().len(), 2);
+    assert_eq!(hits[0]["jobPosting"]["jobTitle"], "Pipe Welder");
+    assert_eq!(hits[1]["jobPosting"]["jobTitle"], "Shop Assistant");
+    assert!(hits[0]["score"].as_f64().unwrap() > hits[1]["score"].as_f64().unwrap());
+
+    assert_eq!(
+        hits[0]["highlights"],
+        json!([
+            {
+                "field": "JOB_DESCRIPTION",
+                "snippet": "<mark>Weld</mark> steel pipe in our fabrication shop. You will <mark>weld</mark> daily."
+            },
+            {
+                "field": "EXPERIENCE_REQUIREMENTS",
+                "snippet": "Certified <mark>welding</mark> experience"
+            }
+        ])
+    );
+    assert_eq!(
+        hits[1]["highlights"][0]["snippet"],
+        "Help around the shop; occasional <mark>welding</mark> &lt;basic&gt; &amp; grinding."
+    );
+
+    // Rarer terms weigh more, and only some terms need to match
+    assert_eq!(search_titles(&schema, "shop books").await, json!(["Accountant", "Shop Assistant", "Pipe Welder"]));
+    assert_eq!(search_titles(&schema, "the and of").await, json!([]));
+    assert_eq!(search_titles(&schema, "plumbing").await, json!([]));
+}
+
+#[tokio::test]
+async fn long_text_is_cut_around_the_matches() {
+    let schema = schema();
+
+    let filler = "lorem ipsum dolor sit amet ".repeat(10);
+    create_posting(
+        &schema,
+        "Forklift driver",
+        &format!("{filler}Operate a forklift in the warehouse. {filler}"),
+        &[],
+        &[]
+    ).await;
+
+    let hits = search(&schema, "warehouse").await;
+    let snippet = hits[0]["highlights"][0]["snippet"].as_str().unwrap();
+
+    assert!(snippet.starts_with('…') && snippet.ends_with('…'), "{}", snippet);
+    assert!(snippet.contains("forklift in the <mark>warehouse</mark>."), "{}", snippet);
+}
+
+#[tokio::test]
+async fn index_follows_updates_and_deletes() {
+    let schema = schema();
+
+    let id = create_posting(&schema, "Line cook", "Cook on the grill line.", &[], &[]).await;
+    create_posting(&schema, "Baker", "Bake bread.", &[], &[("draft", "true")]).await;
+
+    assert_eq!(search_titles(&schema, "grill").await, json!(["Line cook"]));
+    assert_eq!(search_titles(&schema, "bread").await, json!([]));
+
+    let update = format!(
+        r#"mutation {{ updateJobPosting(id: "{id}", patch: {{ jobTitle: "Prep cook", jobDescription: "Prepare vegetables." }}) {{ id }} }}"#
+    );
+    execute(&schema, &update, Some(employer())).await;
+
+    assert_eq!(search_titles(&schema, "grill").await, json!([]));
+    assert_eq!(search_titles(&schema, "vegetable").await, json!(["Prep cook"]));
+
+    let delete = format!(r#"mutation {{ deleteJobPosting(id: "{id}") }}"#);
+    execute(&schema, &delete, Some(employer())).await;
+
+    assert_eq!(search_titles(&schema, "vegetable").await, json!([]));
+}
+
+#[tokio::test]
+async fn overlong_queries_are_rejected() {
+    let schema = schema();
+    create_posting(&schema, "Welder", "Weld pipe.", &[], &[]).await;
+
+    let words = (0..17).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+    let long = "weld ".repeat(60);
+
+    for query in [words, long] {
+        let query = format!(r#"{{ searchJobPostings(query: {}) {{ score }} }}"#, json!(query));
+        let response = execute(&schema, &query, None).await;
+        assert_eq!(error_code(&response), "VALIDATION_ERROR");
+    }
+
+    // Repeated and stop words do not count against the limit
+    let repeated = "the welder and the weld ".repeat(8);
+    assert_eq!(search_titles(&schema, &repeated).await, json!(["Welder"]));
+}
+
+#[tokio::test]
+async fn reindex_rebuilds_the_index_and_drops_orphans() {
+    let (backend, repo, schema) = setup();
+
+    let kept = create_posting(&schema, "Electrician", "Wire new homes.", &[], &[]).await;
+    let removed = create_posting(&schema, "Electrical engineer", "Design circuits.", &[], &[]).await;
+
+    // Deleted behind the index's back, as Time to Live does
+    backend.delete_item("JobPostings", &removed, None).await.unwrap();
+
+    // And the posting loses its index entries
+    let posting = repo.get::<JobPosting>(kept.clone()).await.unwrap().unwrap();
+    search::remove_job_posting(&repo, &posting.id).await.unwrap();
+    assert_eq!(search_titles(&schema, "electrician").await, json!([]));
+
+    assert_eq!(search::reindex_job_postings(&repo).await.unwrap(), 1);
+
+    assert_eq!(search_titles(&schema, "electrician").await, json!(["Electrician"]));
+    assert_eq!(
+        search::reindex_job_postings(&repo).await.unwrap(),
+        1,
+        "reindexing is idempotent"
+    );
+}
@@ -0,0 +1,214 @@
+//! Employer domain verification and the moderation hold, against local
+//! stand-ins for DNS and HTTPS.
+
+mod common;
+
+use std::{ collections::HashMap, sync::{ Arc, Mutex } };
+
+use async_trait::async_trait;
+use job_board_lambda::{
+    auth::Principal,
+    config::Config,
+    models::user::Role,
+    schema_builder,
+    verification::{ https::parse_txt_data, DomainVerifier, TxtResolver, WellKnownFetcher },
+    AppError,
+    GraphQLSchema,
+    Repository,
+};
+use serde_json::Value;
+
+use common::{ error_code, execute, principal, site_admin };
+
+/// Serves whatever the test publishes, keyed by DNS name or URL.
+#[derive(Clone, Default)]
+struct Published(Arc<Mutex<HashMap<String, String>>>);
+
+impl Published {
+    fn publish(&self, key: &str, value: &str) {
+        self.0.lock().unwrap().insert(key.to_string(), value.to_string());
+    }
+}
+
+#[async_trait]
+impl TxtResolver for Published {
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>, AppError> {
+        Ok(self.0.lock().unwrap().get(name).cloned().into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl WellKnownFetcher for Published {
+    async fn fetch(&self, url: &str) -> Result<Option<String>, AppError> {
+        Ok(self.0.lock().unwrap().get(url).cloned())
+    }
+}
+
+fn schema(published: &Published, hold_unverified_postings: bool) -> GraphQLSchema {
+    let mut config = Config::default();
+    config.verification.hold_unverified_postings = hold_unverified_postings;
+
+    schema_builder(Repository::in_memory(), config)
+        .data(DomainVerifier::new(published.clone(), published.clone()))
+        .finish()
+}
+
+fn employer_admin(employer_id: &str) -> Principal {
+    principal(Role::EmployerAdmin, Some(employer_id))
+}
+
+async fn create_employer(schema: &GraphQLSchema) -> String {
+    let response = execute(
+        schema,
+        r#"mutation { createEmployer(name: "Acme", websiteUrl: "https://www.acme.example/careers") { id } }"#,
+        Some(site_admin())
+    ).await;
+
+    response["data"]["createEmployer"]["id"].as_str().unwrap().to_string()
+}
+
+async fn request_challenge(schema: &GraphQLSchema, employer_id: &str) -> Value {
+    let query = format!(
+        r#"mutation {{ requestDomainChallenge(employerId: "{employer_id}") {{ domain token dnsRecordName dnsRecordValue wellKnownUrl }} }}"#
+    );
+    let response = execute(schema, &query, Some(employer_admin(employer_id))).await;
+
+    response["data"]["requestDomainChallenge"].clone()
+}
+
+async fn verify(schema: &GraphQLSchema, employer_id: &str, method: &str) -> Value {
+    let query = format!(
+        r#"mutation {{ verifyDomain(employerId: "{employer_id}", method: {method}) {{ domain domainVerified }} }}"#
+    );
+
+    execute(schema, &query, Some(employer_admin(employer_id))).await
+}
+
+async fn create_posting(schema: &GraphQLSchema, employer_id: &str) -> String {
+    common::create_posting(schema, employer_admin(employer_id), &[]).await
+}
+
+#[tokio::test]
+async fn dns_txt_record_verifies_the_website_domain() {
+    let published = Published::default();
+    let schema = schema(&published, false);
+    let employer_id = create_employer(&schema).await;
+    let posting_id = create_posting(&schema, &employer_id).await;
+
+    let challenge = request_challenge(&schema, &employer_id).await;
+    assert_eq!(challenge["domain"], "www.acme.example");
+    assert_eq!(challenge["dnsRecordName"], "_job-board-challenge.www.acme.example");
+
+    let not_yet = verify(&schema, &employer_id, "DNS_TXT").await;
+    assert_eq!(error_code(&not_yet), "VALIDATION_ERROR");
+
+    published.publish(
+        challenge["dnsRecordName"].as_str().unwrap(),
+        challenge["dnsRecordValue"].as_str().unwrap()
+    );
+
+    let verified = verify(&schema, &employer_id, "DNS_TXT").await;
+    assert_eq!(verified["data"]["verifyDomain"]["domainVerified"], true);
+
+    let query = format!(r#"{{ jobPosting(id: "{posting_id}") {{ verified }} }}"#);
+    let posting = execute(&schema, &query, None).await;
+    assert_eq!(posting["data"]["jobPosting"]["verified"], true);
+
+    // A verified challenge cannot be replayed
+    let replay = verify(&schema, &employer_id, "DNS_TXT").await;
+    assert_eq!(error_code(&replay), "VALIDATION_ERROR");
+}
+
+#[tokio::test]
+async fn well_known_file_must_contain_the_token() {
+    let published = Published::default();
+    let schema = schema(&published, false);
+    let employer_id = create_employer(&schema).await;
+
+    let challenge = request_challenge(&schema, &employer_id).await;
+    let url = challenge["wellKnownUrl"].as_str().unwrap();
+    assert_eq!(url, "https://www.acme.example/.well-known/job-board-verification.txt");
+
+    published.publish(url, "not the token");
+    let wrong = verify(&schema, &employer_id, "WELL_KNOWN_FILE").await;
+    assert_eq!(error_code(&wrong), "VALIDATION_ERROR");
+
+    published.publish(url, &format!("{}\n", challenge["token"].as_str().unwrap()));
+    let verified = verify(&schema, &employer_id, "WELL_KNOWN_FILE").await;
+    assert_eq!(verified["data"]["verifyDomain"]["domainVerified"], true);
+}
+
+#[tokio::test]
+async fn changing_the_domain_resets_verification() {
+    let published = Published::default();
+    let schema = schema(&published, false);
+    let employer_id = create_employer(&schema).await;
+
+    let challenge = request_challenge(&schema, &employer_id).await;
+    published.publish(
+        challenge["wellKnownUrl"].as_str().unwrap(),
+        challenge["token"].as_str().unwrap()
+    );
+    verify(&schema, &employer_id, "WELL_KNOWN_FILE").await;
+
+    let query = format!(
+        r#"mutation {{ updateEmployer(id: "{employer_id}", patch: {{ domain: "other.example" }}) {{ domain domainVerified }} }}"#
+    );
+    let updated = execute(&schema, &query, Some(employer_admin(&employer_id))).await;
+
+    assert_eq!(updated["data"]["updateEmployer"]["domain"], "other.example");
+    assert_eq!(updated["data"]["updateEmployer"]["domainVerified"], false);
+}
+
+#[tokio::test]
+async fn unverified_postings_are_held_until_the_domain_is_verified() {
+    let published = Published::default();
+    let schema = schema(&published, true);
+    let employer_id = create_employer(&schema).await;
+    let posting_id = create_posting(&schema, &employer_id).await;
+    let by_id = format!(r#"{{ jobPosting(id: "{posting_id}") {{ id heldForModeration }} }}"#);
+
+    let listed = execute(&schema, "{ jobPostings { id } }", None).await;
+    assert_eq!(listed["data"]["jobPostings"], serde_json::json!([]));
+
+    let anonymous = execute(&schema, &by_id, None).await;
+    assert_eq!(anonymous["data"]["jobPosting"], Value::Null);
+
+    let owner = execute(&schema, &by_id, Some(employer_admin(&employer_id))).await;
+    assert_eq!(owner["data"]["jobPosting"]["heldForModeration"], true);
+
+    let challenge = request_challenge(&schema, &employer_id).await;
+    published.publish(
+        challenge["dnsRecordName"].as_str().unwrap(),
+        challenge["dnsRecordValue"].as_str().unwrap()
+    );
+    verify(&schema, &employer_id, "DNS_TXT").await;
+
+    let listed = execute(&schema, "{ jobPostings { id } }", None).await;
+    assert_eq!(listed["data"]["jobPostings"][0]["id"], posting_id.as_str());
+}
+
+#[tokio::test]
+async fn site_admins_can_approve_held_postings() {
+    let published = Published::default();
+    let schema = schema(&published, true);
+    let employer_id = create_employer(&schema).await;
+    let posting_id = create_posting(&schema, &employer_id).await;
+    let approve = format!(r#"mutation {{ approveJobPosting(id: "{posting_id}") {{ heldForModeration }} }}"#);
+
+    let by_owner = execute(&schema, &approve, Some(employer_admin(&employer_id))).await;
+    assert_eq!(error_code(&by_owner), "FORBIDDEN");
+
+    let approved = execute(&schema, &approve, Some(site_admin())).await;
+    assert_eq!(approved["data"]["approveJobPosting"]["heldForModeration"], false);
+
+    let listed = execute(&schema, "{ latestJobPostings { edges { node { id } } } }", None).await;
+    assert_eq!(listed["data"]["latestJobPostings"]["edges"][0]["node"]["id"], posting_id.as_str());
+}
+
+#[test]
+fn txt_data_strings_are_joined_and_unescaped() {
+    assert_eq!(parse_txt_data(r#""job-board-" "verification=abc""#), "job-board-verification=abc");
+    assert_eq!(parse_txt_data(r#""say \"hi\" \059""#), r#"say "hi" ;"#);
+    assert_eq!(parse_txt_data("plain"), "plain");
+}
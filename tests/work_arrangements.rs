@@ -0,0 +1,175 @@
+//! Work arrangements alongside job types, and filtering on both.
+
+mod common;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use job_board_lambda::{ DynamoDbEntity, GraphQLSchema, JobPosting, StorageBackend };
+use serde_json::{ json, Value };
+
+use common::{ create_posting_mutation, employer, error_code, execute, schema, setup };
+
+async fn create_posting(
+    schema: &GraphQLSchema,
+    title: &str,
+    job_type: &str,
+    arrangement: &str
+) -> Value {
+    let title = json!(title).to_string();
+    let job_type = json!(job_type).to_string();
+    let mut arguments = vec![("jobTitle", title.as_str()), ("jobType", job_type.as_str())];
+
+    if !arrangement.is_empty() {
+        arguments.push(("workArrangement", arrangement));
+    }
+
+    let mutation = create_posting_mutation(
+        &arguments,
+        "id jobType workArrangement { type countries utcOffsets { earliest latest } }"
+    );
+
+    execute(schema, &mutation, Some(employer())).await
+}
+
+async fn titles(schema: &GraphQLSchema, filter: &str) -> Value {
+    let query = format!(r#"{{ jobPostings(filter: {{ {filter} }}) {{ jobTitle }} }}"#);
+    let response = execute(schema, &query, None).await;
+
+    if response["errors"].is_array() {
+        return error_code(&response).clone();
+    }
+
+    let mut titles: Vec<String> = response["data"]["jobPostings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|posting| posting["jobTitle"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    json!(titles)
+}
+
+async fn seed(schema: &GraphQLSchema) {
+    create_posting(schema, "Shop floor", "FULL_TIME", "").await;
+    create_posting(schema, "Hybrid office", "PART_TIME", "{ type: HYBRID }").await;
+    create_posting(schema, "Remote anywhere", "FULL_TIME", "{ type: REMOTE }").await;
+    create_posting(
+        schema,
+        "Remote Americas",
+        "CONTRACT",
+        r#"{ type: REMOTE, countries: ["us", "CA"], utcOffsets: { earliest: "-08:00", latest: "-05:00" } }"#
+    ).await;
+    create_posting(
+        schema,
+        "Remote Europe",
+        "FULL_TIME",
+        r#"{ type: REMOTE, countries: ["DE"], utcOffsets: { earliest: "UTC", latest: "+02:00" } }"#
+    ).await;
+}
+
+#[tokio::test]
+async fn remote_full_time_jobs_can_be_expressed() {
+    let schema = schema();
+
+    let response = create_posting(
+        &schema,
+        "Remote welder",
+        "FULL_TIME",
+        r#"{ type: REMOTE, countries: ["us", "ca"], utcOffsets: { earliest: "-8", latest: "UTC-05:00" } }"#
+    ).await;
+
+    assert_eq!(
+        response["data"]["createJobPosting"],
+        json!({
+            "id": response["data"]["createJobPosting"]["id"],
+            "jobType": "FULL_TIME",
+            "workArrangement": {
+                "type": "REMOTE",
+                "countries": ["US", "CA"],
+                "utcOffsets": { "earliest": "-08:00", "latest": "-05:00" }
+            }
+        })
+    );
+
+    let on_site = create_posting(&schema, "Welder", "FULL_TIME", "").await;
+    assert_eq!(
+        on_site["data"]["createJobPosting"]["workArrangement"],
+        json!({ "type": "ON_SITE", "countries": [], "utcOffsets": null })
+    );
+}
+
+#[tokio::test]
+async fn invalid_arrangements_are_rejected() {
+    let schema = schema();
+
+    for (job_type, arrangement) in [
+        ("FULL_TIME", r#"{ type: HYBRID, countries: ["US"] }"#),
+        ("FULL_TIME", r#"{ type: REMOTE, countries: ["USA"] }"#),
+        ("FULL_TIME", r#"{ type: REMOTE, utcOffsets: { earliest: "+03:00", latest: "-03:00" } }"#),
+        ("FULL_TIME", r#"{ type: REMOTE, utcOffsets: { earliest: "+15:00", latest: "+15:00" } }"#),
+        ("REMOTE", ""),
+    ] {
+        let response = create_posting(&schema, "Welder", job_type, arrangement).await;
+        assert_eq!(error_code(&response), "VALIDATION_ERROR", "{}", arrangement);
+    }
+}
+
+#[tokio::test]
+async fn listings_filter_on_job_type_and_arrangement() {
+    let schema = schema();
+    seed(&schema).await;
+
+    assert_eq!(
+        titles(&schema, "jobType: FULL_TIME, workArrangement: REMOTE").await,
+        json!(["Remote Europe", "Remote anywhere"])
+    );
+    assert_eq!(titles(&schema, "workArrangement: ON_SITE").await, json!(["Shop floor"]));
+    assert_eq!(titles(&schema, "workArrangement: HYBRID").await, json!(["Hybrid office"]));
+    assert_eq!(
+        titles(&schema, r#"remoteCountry: "ca""#).await,
+        json!(["Remote Americas", "Remote anywhere"])
+    );
+    assert_eq!(
+        titles(&schema, r#"utcOffset: "+01:00""#).await,
+        json!(["Remote Europe", "Remote anywhere"])
+    );
+    assert_eq!(
+        titles(&schema, r#"remoteCountry: "US", utcOffset: "-06:00", jobType: CONTRACT"#).await,
+        json!(["Remote Americas"])
+    );
+    assert_eq!(
+        titles(&schema, r#"workArrangement: ON_SITE, remoteCountry: "US""#).await,
+        json!("VALIDATION_ERROR")
+    );
+    // U+2212 MINUS SIGN rather than a hyphen
+    assert_eq!(titles(&schema, "utcOffset: \"\u{2212}05:00\"").await, json!("VALIDATION_ERROR"));
+}
+
+#[tokio::test]
+async fn legacy_remote_job_type_reads_as_full_time_remote() {
+    let (backend, repository, schema) = setup();
+
+    let response = create_posting(&schema, "Legacy", "FULL_TIME", "").await;
+    let id = response["data"]["createJobPosting"]["id"].as_str().unwrap().to_string();
+
+    // As stored before work arrangements existed
+    let mut item = repository.get::<JobPosting>(id.clone()).await.unwrap().unwrap().to_item();
+    item.insert("job_type".to_string(), AttributeValue::S("REMOTE".to_string()));
+    item.remove("work_arrangement");
+    item.remove("work_arrangement_type");
+    backend.put_item(JobPosting::table_name(), item).await.unwrap();
+
+    let query = format!(r#"{{ jobPosting(id: "{id}") {{ jobType workArrangement {{ type }} }} }}"#);
+    let posting = execute(&schema, &query, None).await;
+    assert_eq!(
+        posting["data"]["jobPosting"],
+        json!({ "jobType": "FULL_TIME", "workArrangement": { "type": "REMOTE" } })
+    );
+
+    // Stored again in the current format, it is found by arrangement
+    let job_posting = repository.get::<JobPosting>(id).await.unwrap().unwrap();
+    repository.update(job_posting).await.unwrap();
+    assert_eq!(
+        titles(&schema, "jobType: FULL_TIME, workArrangement: REMOTE").await,
+        json!(["Legacy"])
+    );
+}